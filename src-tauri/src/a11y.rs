@@ -0,0 +1,164 @@
+//! Accessibility — tagged-PDF audit (StructTreeRoot / Lang / Title / Figure alt text) + quick fixes via lopdf
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use serde::Serialize;
+use std::collections::HashSet;
+
+use crate::common::{
+  assert_input_not_output, catalog_id, decode_text, encode_text, info_mut, load_pdf, save_pdf, InputOne,
+};
+use crate::inspect::{page_images, resolve};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct A11yCheck {
+  /// 稳定标识，前端可据此本地化
+  pub id: &'static str,
+  pub passed: bool,
+  pub detail: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct A11yReport {
+  pub tagged: bool,
+  pub checks: Vec<A11yCheck>,
+}
+
+#[tauri::command]
+pub async fn accessibility_report(input: InputOne) -> Result<A11yReport, String> {
+  let doc = load_pdf(&input)?;
+  let catalog = doc.catalog().map_err(|e| format!("缺少文档目录（Root）：{e}"))?;
+  let mut checks = Vec::new();
+
+  let struct_root = catalog.get(b"StructTreeRoot").ok().map(|o| resolve(&doc, o));
+  checks.push(A11yCheck {
+    id: "structTree",
+    passed: struct_root.is_some(),
+    detail: if struct_root.is_some() { "存在 StructTreeRoot".into() } else { "缺少 StructTreeRoot（未打标签）".into() },
+  });
+
+  let marked = catalog.get(b"MarkInfo").ok()
+    .and_then(|o| resolve(&doc, o).as_dict().ok())
+    .and_then(|m| m.get(b"Marked").ok())
+    .and_then(|o| o.as_bool().ok())
+    .unwrap_or(false);
+  checks.push(A11yCheck {
+    id: "markInfo",
+    passed: marked,
+    detail: if marked { "MarkInfo /Marked true".into() } else { "MarkInfo /Marked 缺失或为 false".into() },
+  });
+
+  let lang = text_entry(&doc, catalog.get(b"Lang").ok());
+  checks.push(A11yCheck {
+    id: "lang",
+    passed: lang.is_some(),
+    detail: lang.map(|l| format!("文档语言：{l}")).unwrap_or_else(|| "缺少文档 /Lang".into()),
+  });
+
+  let title = doc.trailer.get(b"Info").ok()
+    .and_then(|o| resolve(&doc, o).as_dict().ok())
+    .and_then(|info| text_entry(&doc, info.get(b"Title").ok()));
+  checks.push(A11yCheck {
+    id: "title",
+    passed: title.is_some(),
+    detail: title.map(|t| format!("标题：{t}")).unwrap_or_else(|| "Info 缺少 /Title".into()),
+  });
+
+  let display_title = catalog.get(b"ViewerPreferences").ok()
+    .and_then(|o| resolve(&doc, o).as_dict().ok())
+    .and_then(|v| v.get(b"DisplayDocTitle").ok())
+    .and_then(|o| o.as_bool().ok())
+    .unwrap_or(false);
+  checks.push(A11yCheck {
+    id: "displayDocTitle",
+    passed: display_title,
+    detail: if display_title { "窗口标题显示文档标题".into() } else { "ViewerPreferences /DisplayDocTitle 未开启".into() },
+  });
+
+  // 图片替代文本：有标签时统计 Figure 元素的 /Alt；无标签但有图片则直接判失败
+  let (figures, with_alt) = struct_root.map(|r| count_figures(&doc, r)).unwrap_or((0, 0));
+  let has_images = doc.get_pages().values().any(|&id| !page_images(&doc, id).is_empty());
+  let alt_ok = if struct_root.is_some() { with_alt == figures } else { !has_images };
+  checks.push(A11yCheck {
+    id: "altText",
+    passed: alt_ok,
+    detail: if struct_root.is_some() {
+      format!("Figure 元素 {figures} 个，其中 {with_alt} 个有 /Alt")
+    } else if has_images {
+      "文档含图片但未打标签，无法提供替代文本".into()
+    } else {
+      "文档不含图片".into()
+    },
+  });
+
+  Ok(A11yReport { tagged: struct_root.is_some() && marked, checks })
+}
+
+/// 写入 Catalog /Lang、Info /Title、ViewerPreferences /DisplayDocTitle
+#[tauri::command]
+pub async fn set_a11y_basics(input: InputOne, output: String, lang: String, title: String, display_title: bool) -> Result<String, String> {
+  let lang = lang.trim();
+  if !is_bcp47(lang) { return Err(format!("语言标签不是有效的 BCP-47：{lang}（例如 zh-CN、en-US）")); }
+  assert_input_not_output(&input, &output)?;
+  let mut doc = load_pdf(&input)?;
+
+  info_mut(&mut doc)?.set("Title", encode_text(title.trim()));
+
+  let cat = catalog_id(&doc)?;
+  let prefs_ref = doc.get_dictionary(cat).ok()
+    .and_then(|c| c.get(b"ViewerPreferences").ok())
+    .and_then(|o| o.as_reference().ok());
+  if let Some(id) = prefs_ref {
+    if let Ok(Object::Dictionary(p)) = doc.get_object_mut(id) { p.set("DisplayDocTitle", display_title); }
+  }
+  let Ok(Object::Dictionary(catalog)) = doc.get_object_mut(cat) else { return Err("文档目录（Root）不是字典".into()) };
+  catalog.set("Lang", Object::string_literal(lang));
+  if prefs_ref.is_none() {
+    let mut prefs = catalog.get(b"ViewerPreferences").ok().and_then(|o| o.as_dict().ok()).cloned().unwrap_or_else(Dictionary::new);
+    prefs.set("DisplayDocTitle", display_title);
+    catalog.set("ViewerPreferences", prefs);
+  }
+
+  save_pdf(&mut doc, &output)?;
+  Ok(output)
+}
+
+/// BCP-47 语法校验：主标签 2–8 字母，其后子标签 1–8 位字母数字；支持 x- 私有用途
+fn is_bcp47(tag: &str) -> bool {
+  let mut parts = tag.split('-');
+  let Some(primary) = parts.next() else { return false };
+  let primary_ok = (primary.len() >= 2 && primary.len() <= 8 && primary.chars().all(|c| c.is_ascii_alphabetic()))
+    || primary.eq_ignore_ascii_case("x") || primary.eq_ignore_ascii_case("i");
+  primary_ok && parts.all(|p| !p.is_empty() && p.len() <= 8 && p.chars().all(|c| c.is_ascii_alphanumeric()))
+    && !(primary.len() == 1 && !tag.contains('-'))
+}
+
+fn text_entry(doc: &Document, obj: Option<&Object>) -> Option<String> {
+  let s = decode_text(resolve(doc, obj?).as_str().ok()?);
+  let s = s.trim().to_string();
+  (!s.is_empty()).then_some(s)
+}
+
+/// 遍历结构树，返回 (Figure 数, 带非空 /Alt 的 Figure 数)
+fn count_figures(doc: &Document, root: &Object) -> (usize, usize) {
+  let mut seen: HashSet<ObjectId> = HashSet::new();
+  let mut stack: Vec<&Object> = vec![root];
+  let (mut figures, mut with_alt) = (0, 0);
+  while let Some(obj) = stack.pop() {
+    if let Object::Reference(id) = obj {
+      if !seen.insert(*id) { continue; }
+    }
+    match resolve(doc, obj) {
+      Object::Array(items) => stack.extend(items.iter()),
+      Object::Dictionary(d) => {
+        if d.get(b"S").and_then(|o| o.as_name()).map(|n| n == b"Figure").unwrap_or(false) {
+          figures += 1;
+          if text_entry(doc, d.get(b"Alt").ok()).is_some() { with_alt += 1; }
+        }
+        if let Ok(k) = d.get(b"K") { stack.push(k); }
+      }
+      _ => {}
+    }
+  }
+  (figures, with_alt)
+}
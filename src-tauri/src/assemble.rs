@@ -0,0 +1,185 @@
+//! Assemble — merge (qpdf) + one bookmark per source file + page-number overlay, in a single command
+use tauri::{AppHandle, Emitter};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+use crate::common::{
+  assert_input_not_output, ensure_parent_dir, page_count, run_qpdf, save_pdf, stage_input, work_dir, AtomicOutput, InputOne,
+  PageGuard,
+};
+use crate::naming::{resolve_output, NamingSource};
+use crate::orient::{apply_rotations, detect_rotations, PageRotation};
+use crate::outline::{read_outline, write_outline, OutlineNode};
+use crate::stamp::{stamp_page_numbers, PageNumberStyle};
+
+const PROGRESS_EVT: &str = "assemble:progress";
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "phase", rename_all = "lowercase")]
+enum Progress<'a> {
+  Merge { files: usize },
+  Bookmarks,
+  Numbers { pages: usize },
+  Rotate { pages: usize },
+  Done { path: &'a str },
+}
+
+/// assemble 可选步骤（均可省略，省略即只合并）
+#[derive(Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AssembleOptions {
+  /// 每个源文件一条顶层书签
+  pub toc: bool,
+  /// 书签标题，按输入顺序；缺项时用文件名
+  pub titles: Vec<String>,
+  /// 源文件自带的书签挂到对应文件书签下
+  pub keep_source_bookmarks: bool,
+  /// 有值则叠加页码
+  pub page_numbers: Option<PageNumberStyle>,
+}
+
+fn emit_progress(app: &AppHandle, payload: &Progress) {
+  let _ = app.emit(PROGRESS_EVT, payload);
+}
+
+#[tauri::command]
+pub async fn assemble(app: AppHandle, inputs: Vec<InputOne>, output: String, options: Option<AssembleOptions>) -> Result<String, String> {
+  let output = resolve_output(&output, "assemble", &inputs.iter().map(NamingSource::from).collect::<Vec<_>>())?;
+  let opts = options.unwrap_or_default();
+  if inputs.is_empty() { return Err("请选择至少一个 PDF".into()); }
+  for input in &inputs { assert_input_not_output(input, &output)?; }
+  ensure_parent_dir(&output)?;
+  let work = work_dir(&app, "assemble")?;
+  let res = run_assemble(&app, &work, &inputs, &output, &opts);
+  let _ = fs::remove_dir_all(&work);
+  res.map(|_| output)
+}
+
+fn run_assemble(app: &AppHandle, work: &Path, inputs: &[InputOne], output: &str, opts: &AssembleOptions) -> Result<(), String> {
+  // 字节版可能重名，每个输入单独一个子目录
+  let mut paths = Vec::with_capacity(inputs.len());
+  for (i, input) in inputs.iter().enumerate() {
+    let dir = work.join(format!("{i:03}"));
+    fs::create_dir_all(&dir).map_err(|e| format!("创建临时目录失败：{e}"))?;
+    paths.push(stage_input(&dir, input)?);
+  }
+
+  emit_progress(app, &Progress::Merge { files: paths.len() });
+  let merged = work.join("merged.pdf").to_string_lossy().to_string();
+  let mut args: Vec<String> = vec!["--empty".into(), "--pages".into()];
+  for p in &paths { args.push(p.clone()); args.push("1-z".into()); }
+  args.push("--".into());
+  args.push(merged.clone());
+  run_qpdf(app, &args)?;
+  let mut doc = lopdf::Document::load(&merged).map_err(|e| format!("解析合并结果失败：{e}"))?;
+
+  if opts.toc || opts.keep_source_bookmarks {
+    emit_progress(app, &Progress::Bookmarks);
+    let mut nodes = Vec::new();
+    let mut offset = 0u32;
+    for (i, (path, input)) in paths.iter().zip(inputs).enumerate() {
+      let src = lopdf::Document::load(path).map_err(|e| format!("解析 PDF 失败：{e}（{path}）"))?;
+      let pages = src.get_pages().len() as u32;
+      let children = if opts.keep_source_bookmarks { shift(&read_outline(&src), offset) } else { Vec::new() };
+      if opts.toc {
+        let title = opts.titles.get(i).map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).unwrap_or_else(|| display_name(input));
+        nodes.push(OutlineNode { title, page: Some(offset + 1), children });
+      } else {
+        nodes.extend(children);
+      }
+      offset += pages;
+    }
+    write_outline(&mut doc, &nodes)?;
+  }
+
+  if let Some(style) = &opts.page_numbers {
+    emit_progress(app, &Progress::Numbers { pages: doc.get_pages().len() });
+    stamp_page_numbers(&mut doc, style)?;
+  }
+
+  save_pdf(&mut doc, output)?;
+  emit_progress(app, &Progress::Done { path: output });
+  Ok(())
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssembleScansResult {
+  pub path: String,
+  /// 非单页输入等提示（仍按原样并入）
+  pub warnings: Vec<String>,
+  /// 开启 auto_rotate 时每页的 OSD 结果（合并后页码）
+  pub rotations: Vec<PageRotation>,
+}
+
+/// 手机扫描"一页一个 PDF"按顺序拼成一份；auto_rotate 时合并后逐页 OSD 纠正方向（OSD 前按 guard 检查合并结果的页数）
+#[tauri::command]
+pub async fn assemble_scans(
+  app: AppHandle,
+  inputs: Vec<InputOne>,
+  output: String,
+  auto_rotate: bool,
+  guard: Option<PageGuard>,
+) -> Result<AssembleScansResult, String> {
+  let output = resolve_output(&output, "assemble_scans", &inputs.iter().map(NamingSource::from).collect::<Vec<_>>())?;
+  if inputs.is_empty() { return Err("请选择至少一个 PDF".into()); }
+  for input in &inputs { assert_input_not_output(input, &output)?; }
+  let out = AtomicOutput::new(&output)?;
+  let work = work_dir(&app, "assemble_scans")?;
+  let res = run_assemble_scans(&app, &work, &inputs, &out.path(), auto_rotate, guard.unwrap_or_default());
+  let _ = fs::remove_dir_all(&work);
+  let (warnings, rotations) = res?;
+  out.commit()?;
+  emit_progress(&app, &Progress::Done { path: &output });
+  Ok(AssembleScansResult { path: output, warnings, rotations })
+}
+
+fn run_assemble_scans(
+  app: &AppHandle,
+  work: &Path,
+  inputs: &[InputOne],
+  output: &str,
+  auto_rotate: bool,
+  guard: PageGuard,
+) -> Result<(Vec<String>, Vec<PageRotation>), String> {
+  let mut paths = Vec::with_capacity(inputs.len());
+  let mut warnings = Vec::new();
+  for (i, input) in inputs.iter().enumerate() {
+    let dir = work.join(format!("{i:03}"));
+    fs::create_dir_all(&dir).map_err(|e| format!("创建临时目录失败：{e}"))?;
+    let path = stage_input(&dir, input)?;
+    let pages = page_count(app, &path)?;
+    if pages != 1 { warnings.push(format!("第 {} 个输入 {} 有 {pages} 页，不是单页扫描", i + 1, display_name(input))); }
+    paths.push(path);
+  }
+
+  emit_progress(app, &Progress::Merge { files: paths.len() });
+  let merged = if auto_rotate { work.join("merged.pdf").to_string_lossy().to_string() } else { output.to_string() };
+  let mut args: Vec<String> = vec!["--empty".into(), "--pages".into()];
+  for p in &paths { args.push(p.clone()); args.push("1-z".into()); }
+  args.push("--".into());
+  args.push(merged.clone());
+  run_qpdf(app, &args)?;
+  if !auto_rotate { return Ok((warnings, Vec::new())); }
+
+  guard.check(app, &merged)?;
+  let rotations = detect_rotations(app, work, &merged)?;
+  emit_progress(app, &Progress::Rotate { pages: rotations.len() });
+  apply_rotations(app, &merged, output, &rotations)?;
+  Ok((warnings, rotations))
+}
+
+/// 书签页码整体平移（合并后源文件从 offset + 1 页开始）
+fn shift(nodes: &[OutlineNode], offset: u32) -> Vec<OutlineNode> {
+  nodes.iter().map(|n| OutlineNode {
+    title: n.title.clone(),
+    page: n.page.map(|p| p + offset),
+    children: shift(&n.children, offset),
+  }).collect()
+}
+
+/// 书签默认标题：去掉扩展名的文件名
+fn display_name(input: &InputOne) -> String {
+  let name = match input { InputOne::Path(p) => p.as_str(), InputOne::Bytes(pdf) => pdf.name.as_str() };
+  Path::new(name).file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_else(|| name.to_string())
+}
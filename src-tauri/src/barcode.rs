@@ -0,0 +1,100 @@
+//! Barcode stamp — QR / Code128 drawn as vector modules in a Form XObject, overlaid on a page range via lopdf
+use barcoders::sym::code128::Code128;
+use lopdf::{dictionary, Object, Stream};
+use qrcode::{Color, QrCode};
+use serde::Deserialize;
+
+use crate::common::{assert_input_not_output, load_pdf, parse_range, save_pdf, InputOne};
+use crate::naming::resolve_output;
+use crate::stamp::{add_resource, append_page_content, place, visible_area, StampPosition, DEFAULT_MARGIN_PT};
+
+/// 二维码边长（含静区），1 英寸
+const QR_SIZE_PT: f32 = 72.0;
+const QR_QUIET_MODULES: usize = 4;
+/// Code128 单模块宽度与条高
+const BAR_MODULE_PT: f32 = 1.0;
+const BAR_HEIGHT_PT: f32 = 36.0;
+const BAR_QUIET_MODULES: usize = 10;
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum Symbology { Qr, Code128 }
+
+/// data 编码成条码画在白底上；pages 为 qpdf 页范围语法，默认仅第一页（"1-z" 为每页）
+#[tauri::command]
+pub async fn stamp_barcode(
+  input: InputOne,
+  output: String,
+  data: String,
+  symbology: Symbology,
+  position: StampPosition,
+  pages: Option<String>,
+) -> Result<String, String> {
+  let output = resolve_output(&output, "barcode", &[(&input).into()])?;
+  if data.is_empty() { return Err("条码内容不能为空".into()); }
+  assert_input_not_output(&input, &output)?;
+  let mut doc = load_pdf(&input)?;
+  let (w, h, ops) = match symbology {
+    Symbology::Qr => qr_ops(&data)?,
+    Symbology::Code128 => code128_ops(&data)?,
+  };
+
+  let form = doc.add_object(Stream::new(dictionary! {
+    "Type" => "XObject",
+    "Subtype" => "Form",
+    "BBox" => vec![Object::Real(0.0), Object::Real(0.0), Object::Real(w), Object::Real(h)],
+  }, ops.into_bytes()));
+  let name = format!("LpBarcode{}", form.0);
+
+  let page_ids = doc.get_pages();
+  let total = page_ids.len() as u32;
+  for no in parse_range(pages.as_deref().unwrap_or("1"), total)? {
+    let Some(&page_id) = page_ids.get(&no) else { continue };
+    let area = visible_area(&doc, page_id).ok_or(format!("第 {no} 页缺少 MediaBox"))?;
+    let (x, y) = place(area, w, h, position, DEFAULT_MARGIN_PT);
+    add_resource(&mut doc, page_id, b"XObject", &name, form)?;
+    append_page_content(&mut doc, page_id, format!("q 1 0 0 1 {x} {y} cm /{name} Do Q\n").into_bytes())?;
+  }
+  save_pdf(&mut doc, &output)?;
+  Ok(output)
+}
+
+/// 白底 + 按行合并相邻深色模块的矩形，返回 (宽, 高, 内容流)
+fn qr_ops(data: &str) -> Result<(f32, f32, String), String> {
+  let code = QrCode::new(data.as_bytes()).map_err(|e| format!("生成二维码失败：{e}"))?;
+  let n = code.width();
+  let colors = code.to_colors();
+  let module = QR_SIZE_PT / (n + 2 * QR_QUIET_MODULES) as f32;
+  let mut ops = format!("1 g 0 0 {QR_SIZE_PT} {QR_SIZE_PT} re f 0 g\n");
+  for row in 0..n {
+    let y = QR_SIZE_PT - (row + QR_QUIET_MODULES + 1) as f32 * module;
+    let mut col = 0;
+    while col < n {
+      if colors[row * n + col] != Color::Dark { col += 1; continue; }
+      let start = col;
+      while col < n && colors[row * n + col] == Color::Dark { col += 1; }
+      let x = (start + QR_QUIET_MODULES) as f32 * module;
+      ops.push_str(&format!("{x} {y} {} {module} re\n", (col - start) as f32 * module));
+    }
+  }
+  ops.push_str("f\n");
+  Ok((QR_SIZE_PT, QR_SIZE_PT, ops))
+}
+
+/// 未指定字符集时按 B 集（可打印 ASCII）编码
+fn code128_ops(data: &str) -> Result<(f32, f32, String), String> {
+  let prefixed = if data.starts_with(['À', 'Ɓ', 'Ć']) { data.to_string() } else { format!("Ɓ{data}") };
+  let bars = Code128::new(&prefixed).map_err(|e| format!("无法编码为 Code128：{e}"))?.encode();
+  let w = (bars.len() + 2 * BAR_QUIET_MODULES) as f32 * BAR_MODULE_PT;
+  let mut ops = format!("1 g 0 0 {w} {BAR_HEIGHT_PT} re f 0 g\n");
+  let mut i = 0;
+  while i < bars.len() {
+    if bars[i] == 0 { i += 1; continue; }
+    let start = i;
+    while i < bars.len() && bars[i] == 1 { i += 1; }
+    let x = (start + BAR_QUIET_MODULES) as f32 * BAR_MODULE_PT;
+    ops.push_str(&format!("{x} 0 {} {BAR_HEIGHT_PT} re\n", (i - start) as f32 * BAR_MODULE_PT));
+  }
+  ops.push_str("f\n");
+  Ok((w, BAR_HEIGHT_PT, ops))
+}
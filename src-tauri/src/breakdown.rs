@@ -0,0 +1,100 @@
+//! Size breakdown — largest streams by stored size, classified from qpdf --json=2 (no stream data)
+use tauri::AppHandle;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::{collections::HashSet, fs, path::Path};
+
+use crate::common::{run_qpdf, stage_input, work_dir, InputOne};
+
+const DEFAULT_LIMIT: usize = 20;
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ObjectKind { Image, Font, Content, Form, IccProfile, Metadata, Attachment, Other }
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectSize {
+  /// 对象引用，如 "12 0 R"
+  pub object: String,
+  pub kind: ObjectKind,
+  /// 流在文件中的存储字节数（/Length，压缩后）
+  pub bytes: u64,
+  pub filter: Option<String>,
+}
+
+/// 按存储体积降序返回最大的 limit 个流（默认 20）
+#[tauri::command]
+pub async fn size_breakdown(app: AppHandle, input: InputOne, limit: Option<u32>) -> Result<Vec<ObjectSize>, String> {
+  let work = work_dir(&app, "breakdown")?;
+  let res = run_breakdown(&app, &work, &input, limit.map(|n| n as usize).unwrap_or(DEFAULT_LIMIT));
+  let _ = fs::remove_dir_all(&work);
+  res
+}
+
+fn run_breakdown(app: &AppHandle, work: &Path, input: &InputOne, limit: usize) -> Result<Vec<ObjectSize>, String> {
+  let in_path = stage_input(work, input)?;
+  let out = run_qpdf(app, &["--json=2".into(), "--json-key=qpdf".into(), "--json-stream-data=none".into(), in_path])?;
+  let json: Value = serde_json::from_slice(&out.stdout).map_err(|e| format!("解析 qpdf JSON 失败：{e}"))?;
+  let objects = json.get("qpdf").and_then(|q| q.get(1)).and_then(Value::as_object).ok_or("qpdf JSON 缺少对象表")?;
+
+  let contents = content_streams(objects);
+  let mut sizes: Vec<ObjectSize> = objects.iter().filter_map(|(key, obj)| {
+    let dict = obj.get("stream")?.get("dict")?.as_object()?;
+    let object = key.strip_prefix("obj:")?.to_string();
+    let bytes = match dict.get("/Length")? {
+      Value::Number(n) => n.as_u64()?,
+      Value::String(r) => objects.get(&format!("obj:{r}"))?.get("value")?.as_u64()?,
+      _ => return None,
+    };
+    let kind = if contents.contains(&object) { ObjectKind::Content } else { classify(dict) };
+    let filter = match dict.get("/Filter") {
+      Some(Value::String(f)) => Some(f.trim_start_matches('/').to_string()),
+      Some(Value::Array(a)) => a.first().and_then(Value::as_str).map(|f| f.trim_start_matches('/').to_string()),
+      _ => None,
+    };
+    Some(ObjectSize { object, kind, bytes, filter })
+  }).collect();
+
+  sizes.sort_by(|a, b| b.bytes.cmp(&a.bytes));
+  sizes.truncate(limit);
+  Ok(sizes)
+}
+
+/// 所有页面 /Contents 引用的流
+fn content_streams(objects: &Map<String, Value>) -> HashSet<String> {
+  let mut out = HashSet::new();
+  for obj in objects.values() {
+    let Some(page) = obj.get("value").and_then(Value::as_object) else { continue };
+    if page.get("/Type").and_then(Value::as_str) != Some("/Page") { continue; }
+    match page.get("/Contents") {
+      Some(Value::String(r)) => {
+        // /Contents 也可能是间接数组
+        match objects.get(&format!("obj:{r}")).and_then(|o| o.get("value")).and_then(Value::as_array) {
+          Some(arr) => out.extend(arr.iter().filter_map(Value::as_str).map(String::from)),
+          None => { out.insert(r.clone()); }
+        }
+      }
+      Some(Value::Array(arr)) => out.extend(arr.iter().filter_map(Value::as_str).map(String::from)),
+      _ => {}
+    }
+  }
+  out
+}
+
+fn classify(dict: &Map<String, Value>) -> ObjectKind {
+  let name = |k: &str| dict.get(k).and_then(Value::as_str);
+  match (name("/Type"), name("/Subtype")) {
+    (_, Some("/Image")) => ObjectKind::Image,
+    (_, Some("/Form")) => ObjectKind::Form,
+    (Some("/Metadata"), _) => ObjectKind::Metadata,
+    (Some("/EmbeddedFile"), _) => ObjectKind::Attachment,
+    (Some("/ObjStm" | "/XRef"), _) => ObjectKind::Other,
+    // FontFile / FontFile2 / FontFile3
+    (_, Some("/Type1C" | "/CIDFontType0C" | "/OpenType")) => ObjectKind::Font,
+    _ if dict.contains_key("/Length1") || dict.contains_key("/Length2") => ObjectKind::Font,
+    // ICCBased 流只有分量数 /N（可选 /Alternate）
+    _ if dict.get("/N").is_some_and(Value::is_number) => ObjectKind::IccProfile,
+    _ => ObjectKind::Other,
+  }
+}
@@ -0,0 +1,76 @@
+//! Burst statements — split at regex-detected IDs, name parts by ID, package everything into one ZIP
+use tauri::AppHandle;
+use regex::Regex;
+use serde::Serialize;
+use std::{
+  collections::HashSet,
+  fs,
+  io::{self, BufWriter, Write},
+  path::Path,
+};
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+use crate::common::{stage_input, work_dir, AtomicOutput, InputOne};
+use crate::split::{extract_range, part_path, text_boundaries, unique_path};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BurstEntry {
+  /// 捕获的 ID；首个 ID 之前的页为 None
+  pub id: Option<String>,
+  /// 原文档页范围，如 "4-6"
+  pub pages: String,
+  /// ZIP 内文件名
+  pub file: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BurstResult {
+  pub zip: String,
+  pub manifest: Vec<BurstEntry>,
+}
+
+/// id_regex 有捕获组时取第 1 组作为 ID，否则取整个匹配；连续多页 ID 相同时归为同一份（多页对账单只在 ID 首次出现处分开）
+#[tauri::command]
+pub async fn burst_statements(app: AppHandle, input: InputOne, id_regex: String, out_zip: String) -> Result<BurstResult, String> {
+  let re = Regex::new(&id_regex).map_err(|e| format!("正则无效：{e}"))?;
+  let out = AtomicOutput::new(&out_zip)?;
+  let work = work_dir(&app, "burst")?;
+  let res = run_burst(&app, &work, &input, &re, &out.path());
+  let _ = fs::remove_dir_all(&work);
+  let manifest = res?;
+  out.commit()?;
+  Ok(BurstResult { zip: out_zip, manifest })
+}
+
+fn run_burst(app: &AppHandle, work: &Path, input: &InputOne, re: &Regex, zip_path: &str) -> Result<Vec<BurstEntry>, String> {
+  let in_path = stage_input(work, input)?;
+  let doc = lopdf::Document::load(&in_path).map_err(|e| format!("解析 PDF 失败：{e}"))?;
+  let parts_dir = work.join("parts");
+  fs::create_dir_all(&parts_dir).map_err(|e| format!("创建临时目录失败：{e}"))?;
+  let parts_dir = parts_dir.to_string_lossy().to_string();
+
+  let file = fs::File::create(zip_path).map_err(|e| format!("创建 ZIP 失败：{e}"))?;
+  let mut zip = ZipWriter::new(BufWriter::new(file));
+  // PDF 本身多已压缩，Deflate 只为兼容性
+  let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+  let mut used = HashSet::new();
+  let mut manifest = Vec::new();
+  for (i, part) in text_boundaries(&doc, re, true)?.into_iter().enumerate() {
+    let pdf = match &part.label {
+      Some(id) => unique_path(&parts_dir, id, &mut used),
+      None => part_path(&parts_dir, i, &part.range),
+    };
+    extract_range(app, &in_path, &part.range, &pdf)?;
+
+    let name = Path::new(&pdf).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    zip.start_file(name.as_str(), options).map_err(|e| format!("写入 ZIP 失败：{e}"))?;
+    let mut src = fs::File::open(&pdf).map_err(|e| format!("读取拆分结果失败：{e}"))?;
+    io::copy(&mut src, &mut zip).map_err(|e| format!("写入 ZIP 失败：{e}"))?;
+    manifest.push(BurstEntry { id: part.label, pages: part.range, file: name });
+  }
+  zip.finish().and_then(|mut w| w.flush().map_err(Into::into)).map_err(|e| format!("写入 ZIP 失败：{e}"))?;
+  Ok(manifest)
+}
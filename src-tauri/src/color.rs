@@ -0,0 +1,343 @@
+//! Color management — Ghostscript pdfwrite color conversion (RGB / CMYK / Gray), ICC output intent, PDF/X, print cost estimate, spot color / overprint preflight
+use tauri::AppHandle;
+use lopdf::{Dictionary, Document, Object};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeSet, fs, path::Path};
+
+use memchr::memmem;
+
+use crate::common::{
+  assert_output_not_same, ensure_parent_dir, gs_exe, load_pdf, run_gs, run_with_env, stage_input, work_dir, InputOne,
+  PageGuard,
+};
+use crate::inspect::{inherited, resolve};
+use crate::naming::resolve_output;
+use crate::render::classify_color;
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorSpace { Rgb, Cmyk, Gray }
+
+impl ColorSpace {
+  /// (-sColorConversionStrategy, -dProcessColorModel)
+  fn gs_names(&self) -> (&'static str, &'static str) {
+    match self {
+      ColorSpace::Rgb => ("RGB", "/DeviceRGB"),
+      ColorSpace::Cmyk => ("CMYK", "/DeviceCMYK"),
+      ColorSpace::Gray => ("Gray", "/DeviceGray"),
+    }
+  }
+}
+
+#[tauri::command]
+pub async fn convert_colorspace(app: AppHandle, input: InputOne, output: String, target: ColorSpace, icc_profile: Option<String>) -> Result<String, String> {
+  let output = resolve_output(&output, "colorspace", &[(&input).into()])?;
+  ensure_parent_dir(&output)?;
+  if let Some(icc) = icc_profile.as_deref() { read_icc(icc)?; }
+  let work = work_dir(&app, "color")?;
+  let res = run_convert(&app, &work, &input, &output, target, icc_profile.as_deref());
+  let _ = fs::remove_dir_all(&work);
+  res.map(|_| output)
+}
+
+fn run_convert(app: &AppHandle, work: &Path, input: &InputOne, output: &str, target: ColorSpace, icc: Option<&str>) -> Result<(), String> {
+  let in_path = stage_input(work, input)?;
+  assert_output_not_same(&in_path, output)?;
+
+  let (strategy, model) = target.gs_names();
+  let mut args: Vec<String> = vec![
+    "-sDEVICE=pdfwrite".into(),
+    format!("-sColorConversionStrategy={strategy}"),
+    format!("-dProcessColorModel={model}"),
+    "-dOverrideICC=true".into(),
+    "-dNOPAUSE".into(), "-dQUIET".into(), "-dBATCH".into(),
+  ];
+  if let Some(icc) = icc { args.push(format!("-sOutputICCProfile={icc}")); }
+  args.push(format!("-sOutputFile={output}"));
+  args.push(in_path);
+  run_gs(app, &args).map(|_| ())
+}
+
+#[tauri::command]
+pub async fn embed_icc(app: AppHandle, input: InputOne, output: String, icc_path: String) -> Result<String, String> {
+  let output = resolve_output(&output, "icc", &[(&input).into()])?;
+  ensure_parent_dir(&output)?;
+  let icc = read_icc(&icc_path)?;
+  let work = work_dir(&app, "icc")?;
+  let res = run_embed_icc(&app, &work, &input, &output, &icc_path, &icc);
+  let _ = fs::remove_dir_all(&work);
+  res.map(|_| output)
+}
+
+fn run_embed_icc(app: &AppHandle, work: &Path, input: &InputOne, output: &str, icc_path: &str, icc: &IccInfo) -> Result<(), String> {
+  let in_path = stage_input(work, input)?;
+  assert_output_not_same(&in_path, output)?;
+
+  // pdfmark 序言：ICC 流对象 + OutputIntent 挂到 Catalog
+  let ident = Path::new(icc_path).file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_else(|| "Custom".into());
+  let prologue = format!(
+    "/ICCProfile ({icc}) def\n\
+     [/_objdef {{icc_out}} /type /stream /OBJ pdfmark\n\
+     [{{icc_out}} << /N {n} >> /PUT pdfmark\n\
+     [{{icc_out}} ICCProfile (r) file /PUT pdfmark\n\
+     [/_objdef {{intent_out}} /type /dict /OBJ pdfmark\n\
+     [{{intent_out}} << /Type /OutputIntent /S /GTS_PDFA1 /DestOutputProfile {{icc_out}} /OutputConditionIdentifier ({id}) /Info ({id}) >> /PUT pdfmark\n\
+     [{{Catalog}} << /OutputIntents [ {{intent_out}} ] >> /PUT pdfmark\n",
+    icc = ps_string(icc_path), n = icc.components, id = ps_string(&ident),
+  );
+  let prologue_path = work.join("output_intent.ps");
+  fs::write(&prologue_path, prologue).map_err(|e| format!("写入 pdfmark 序言失败：{e}"))?;
+
+  let args: Vec<String> = vec![
+    "-sDEVICE=pdfwrite".into(),
+    format!("-sOutputICCProfile={icc_path}"),
+    format!("--permit-file-read={}", icc_path.replace('\\', "/")),
+    "-dNOPAUSE".into(), "-dQUIET".into(), "-dBATCH".into(),
+    format!("-sOutputFile={output}"),
+    prologue_path.to_string_lossy().to_string(),
+    in_path,
+  ];
+  run_gs(app, &args).map(|_| ())
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub enum PdfxStandard {
+  #[serde(rename = "x1a")] X1a,
+  #[serde(rename = "x4")] X4,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PdfxReport {
+  pub path: String,
+  /// 输出带 GTS_PDFXVersion 且 gs 未回退为普通 PDF
+  pub conformant: bool,
+  /// gs 输出中与 PDF/X 相关的告警（不合规项）
+  pub issues: Vec<String>,
+}
+
+#[tauri::command]
+pub async fn convert_pdfx(app: AppHandle, input: InputOne, output: String, standard: PdfxStandard, output_intent_icc: String) -> Result<PdfxReport, String> {
+  let output = resolve_output(&output, "pdfx", &[(&input).into()])?;
+  ensure_parent_dir(&output)?;
+  let icc = read_icc(&output_intent_icc)?;
+  if icc.components != 4 { return Err("PDF/X 输出意图需要 CMYK ICC 配置文件".into()); }
+  let work = work_dir(&app, "pdfx")?;
+  let res = run_pdfx(&app, &work, &input, &output, standard, &output_intent_icc);
+  let _ = fs::remove_dir_all(&work);
+  res
+}
+
+fn run_pdfx(app: &AppHandle, work: &Path, input: &InputOne, output: &str, standard: PdfxStandard, icc_path: &str) -> Result<PdfxReport, String> {
+  let in_path = stage_input(work, input)?;
+  assert_output_not_same(&in_path, output)?;
+
+  // 以 gs 自带 lib/PDFX_def.ps 为模板，替换 ICC 路径与输出条件标识
+  let (bin_dir, exe, envs) = gs_exe(app)?;
+  let template_path = bin_dir.parent().map(|r| r.join("lib").join("PDFX_def.ps"))
+    .filter(|p| p.exists())
+    .ok_or_else(|| "未找到 PDFX_def.ps：应位于 binaries/ghostscript/lib/".to_string())?;
+  let template = fs::read_to_string(&template_path).map_err(|e| format!("读取 PDFX_def.ps 失败：{e}"))?;
+  let ident = Path::new(icc_path).file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_else(|| "Custom".into());
+  let def: String = template.lines().map(|l| {
+    if l.trim_start().starts_with("/ICCProfile ") { format!("/ICCProfile ({}) def", ps_string(icc_path)) }
+    else if l.contains("/OutputConditionIdentifier (") { format!("  /OutputConditionIdentifier ({})", ps_string(&ident)) }
+    else { l.to_string() }
+  }).collect::<Vec<_>>().join("\n");
+  let def_path = work.join("PDFX_def.ps");
+  fs::write(&def_path, def).map_err(|e| format!("写入 PDFX_def.ps 失败：{e}"))?;
+
+  let (pdfx, compat) = match standard { PdfxStandard::X1a => ("1", "1.3"), PdfxStandard::X4 => ("4", "1.6") };
+  let args: Vec<String> = vec![
+    format!("-dPDFX={pdfx}"),
+    "-sDEVICE=pdfwrite".into(),
+    format!("-dCompatibilityLevel={compat}"),
+    "-dPDFSETTINGS=/prepress".into(),
+    "-sColorConversionStrategy=CMYK".into(),
+    "-sProcessColorModel=DeviceCMYK".into(),
+    format!("--permit-file-read={}", icc_path.replace('\\', "/")),
+    "-dNOPAUSE".into(), "-dBATCH".into(), // 不加 -dQUIET：需要解析 PDF/X 告警
+    format!("-sOutputFile={output}"),
+    def_path.to_string_lossy().to_string(),
+    in_path,
+  ];
+  let out = run_with_env(&bin_dir, &exe, &args, &envs)?;
+  let log = format!("{}\n{}", String::from_utf8_lossy(&out.stdout), String::from_utf8_lossy(&out.stderr));
+  if !out.status.success() { return Err(format!("Ghostscript PDF/X 转换失败：{}", log.trim())); }
+
+  let issues: Vec<String> = log.lines()
+    .map(str::trim)
+    .filter(|l| {
+      let low = l.to_ascii_lowercase();
+      low.contains("pdf/x") || low.contains("pdfx") || low.contains("reverting to normal pdf") || low.contains("not permitted")
+    })
+    .map(String::from)
+    .collect();
+  let reverted = issues.iter().any(|l| l.to_ascii_lowercase().contains("reverting to normal pdf"));
+  let bytes = fs::read(output).map_err(|e| format!("读取输出失败：{e}"))?;
+  let tagged = memmem::find(&bytes, b"GTS_PDFXVersion").is_some();
+
+  Ok(PdfxReport { path: output.to_string(), conformant: tagged && !reverted, issues })
+}
+
+pub(crate) struct IccInfo {
+  /// 颜色分量数：Gray=1 / RGB=3 / CMYK=4
+  pub components: u8,
+}
+
+/// 每页单价（货币单位由调用方决定）
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PrintRates {
+  pub color_page: f64,
+  pub bw_page: f64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrintCost {
+  pub color_pages: u32,
+  pub bw_pages: u32,
+  pub total: f64,
+}
+
+/// 逐页判断彩色 / 黑白并按单价报价；判定与 split_by_color 一致
+#[tauri::command]
+pub async fn print_estimate(app: AppHandle, input: InputOne, rates: PrintRates, guard: Option<PageGuard>) -> Result<PrintCost, String> {
+  if [rates.color_page, rates.bw_page].iter().any(|r| !r.is_finite() || *r < 0.0) {
+    return Err("单价需为非负数".into());
+  }
+  let work = work_dir(&app, "print_estimate")?;
+  let guard = guard.unwrap_or_default();
+  let res = stage_input(&work, &input).and_then(|in_path| {
+    guard.check(&app, &in_path)?;
+    classify_color(&app, &in_path, &work.join("render"))
+  });
+  let _ = fs::remove_dir_all(&work);
+  let pages = res?;
+  let color_pages = pages.iter().filter(|&&c| c).count() as u32;
+  let bw_pages = pages.len() as u32 - color_pages;
+  let total = color_pages as f64 * rates.color_page + bw_pages as f64 * rates.bw_page;
+  Ok(PrintCost { color_pages, bw_pages, total })
+}
+
+/// 校验 ICC 文件：存在 + 头部 36..40 为 "acsp" 签名 + 声明长度与文件一致；读出数据色彩空间
+pub(crate) fn read_icc(path: &str) -> Result<IccInfo, String> {
+  let data = fs::read(path).map_err(|e| format!("读取 ICC 文件失败：{e}（{path}）"))?;
+  if data.len() < 128 { return Err(format!("ICC 文件过短：{path}")); }
+  if &data[36..40] != b"acsp" { return Err(format!("不是有效 ICC 配置文件（缺少 acsp 签名）：{path}")); }
+  let declared = u32::from_be_bytes([data[0], data[1], data[2], data[3]]) as usize;
+  if declared != data.len() { return Err(format!("ICC 头部声明长度 {declared} 与文件大小 {} 不符：{path}", data.len())); }
+  let components = match &data[16..20] {
+    b"GRAY" => 1,
+    b"RGB " => 3,
+    b"CMYK" => 4,
+    other => return Err(format!("不支持的 ICC 色彩空间：{}", String::from_utf8_lossy(other).trim())),
+  };
+  Ok(IccInfo { components })
+}
+
+/// PostScript 字符串转义；Windows 路径改用 /（gs 同样接受）
+pub(crate) fn ps_string(s: &str) -> String {
+  s.replace('\\', "/").replace('(', "\\(").replace(')', "\\)")
+}
+
+// ---------- 专色 / 叠印预检 ----------
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpotColorInfo {
+  /// Separation 名或 DeviceN 色料名（四色 Cyan / Magenta / Yellow / Black 与 All / None 不算专色）
+  pub name: String,
+  pub pages: Vec<u32>,
+  /// 其中资源里同时有叠印图形状态（ExtGState /OP 或 /op 为 true）的页
+  pub overprint_pages: Vec<u32>,
+}
+
+/// 扫描各页资源（含 Form XObject、图片、底纹、图案）里的 Separation / DeviceN 颜色空间，按专色列出用到它的页
+#[tauri::command]
+pub async fn spot_color_report(input: InputOne) -> Result<Vec<SpotColorInfo>, String> {
+  let doc = load_pdf(&input)?;
+  let mut report: Vec<SpotColorInfo> = Vec::new();
+  for (no, page_id) in doc.get_pages() {
+    let mut scan = SpotScan::default();
+    if let Some(res) = inherited(&doc, page_id, b"Resources").and_then(|o| o.as_dict().ok()) {
+      scan.resources(&doc, res, 0);
+    }
+    for name in scan.spots {
+      let i = match report.iter().position(|s| s.name == name) {
+        Some(i) => i,
+        None => {
+          report.push(SpotColorInfo { name, pages: Vec::new(), overprint_pages: Vec::new() });
+          report.len() - 1
+        }
+      };
+      report[i].pages.push(no);
+      if scan.overprint { report[i].overprint_pages.push(no); }
+    }
+  }
+  Ok(report)
+}
+
+#[derive(Default)]
+struct SpotScan {
+  spots: BTreeSet<String>,
+  overprint: bool,
+}
+
+impl SpotScan {
+  fn resources(&mut self, doc: &Document, res: &Dictionary, depth: u8) {
+    if depth > 8 { return; }
+    let sub = |key: &[u8]| res.get(key).ok().and_then(|o| resolve(doc, o).as_dict().ok());
+    for (_, cs) in sub(b"ColorSpace").into_iter().flat_map(Dictionary::iter) { self.color_space(doc, cs, 0); }
+    for (_, sh) in sub(b"Shading").into_iter().flat_map(Dictionary::iter) { self.shading(doc, sh); }
+    for (_, gs) in sub(b"ExtGState").into_iter().flat_map(Dictionary::iter) {
+      let Ok(d) = resolve(doc, gs).as_dict() else { continue };
+      if [&b"OP"[..], b"op"].iter().any(|k| d.get(k).and_then(Object::as_bool).unwrap_or(false)) { self.overprint = true; }
+    }
+    // 着色图案带自己的底纹，平铺图案带自己的资源
+    for (_, pat) in sub(b"Pattern").into_iter().flat_map(Dictionary::iter) {
+      match resolve(doc, pat) {
+        Object::Stream(s) => if let Some(r) = s.dict.get(b"Resources").ok().and_then(|o| resolve(doc, o).as_dict().ok()) { self.resources(doc, r, depth + 1) },
+        Object::Dictionary(d) => if let Ok(sh) = d.get(b"Shading") { self.shading(doc, sh) },
+        _ => {}
+      }
+    }
+    for (_, xo) in sub(b"XObject").into_iter().flat_map(Dictionary::iter) {
+      let Object::Stream(s) = resolve(doc, xo) else { continue };
+      if let Ok(cs) = s.dict.get(b"ColorSpace") { self.color_space(doc, cs, 0); }
+      if let Some(r) = s.dict.get(b"Resources").ok().and_then(|o| resolve(doc, o).as_dict().ok()) { self.resources(doc, r, depth + 1); }
+    }
+  }
+
+  /// 底纹类型 1–3 是字典，4–7 是流
+  fn shading(&mut self, doc: &Document, sh: &Object) {
+    let dict = match resolve(doc, sh) {
+      Object::Dictionary(d) => d,
+      Object::Stream(s) => &s.dict,
+      _ => return,
+    };
+    if let Ok(cs) = dict.get(b"ColorSpace") { self.color_space(doc, cs, 0); }
+  }
+
+  /// [/Separation /名 备用空间 函数]、[/DeviceN [/名 ...] ...]；Indexed / Pattern 看其基础空间
+  fn color_space(&mut self, doc: &Document, cs: &Object, depth: u8) {
+    if depth > 4 { return; }
+    let Ok(arr) = resolve(doc, cs).as_array() else { return };
+    match arr.first().and_then(|o| o.as_name().ok()) {
+      Some(b"Separation") => if let Some(n) = arr.get(1).and_then(|o| resolve(doc, o).as_name().ok()) { self.add(n) },
+      Some(b"DeviceN") => {
+        let names = arr.get(1).and_then(|o| resolve(doc, o).as_array().ok());
+        for n in names.into_iter().flatten().filter_map(|o| o.as_name().ok()) { self.add(n); }
+      }
+      Some(b"Indexed" | b"Pattern") => if let Some(base) = arr.get(1) { self.color_space(doc, base, depth + 1) },
+      _ => {}
+    }
+  }
+
+  fn add(&mut self, name: &[u8]) {
+    if !matches!(name, b"Cyan" | b"Magenta" | b"Yellow" | b"Black" | b"All" | b"None") {
+      self.spots.insert(String::from_utf8_lossy(name).into_owned());
+    }
+  }
+}
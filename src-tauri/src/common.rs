@@ -0,0 +1,489 @@
+//! Shared helpers — input staging, temp dirs, atomic outputs, qpdf/Ghostscript discovery & runners
+use tauri::{AppHandle, Manager};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::{
+  fs, ffi::OsStr,
+  path::{Path, PathBuf},
+  process::Command,
+  sync::atomic::{AtomicU32, Ordering},
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+#[derive(Deserialize, Clone)]
+pub struct PdfIn { pub name: String, pub data: Vec<u8> }
+
+/// 单文件入参：路径 或 {name,data} 字节
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum InputOne {
+  Path(String),
+  Bytes(PdfIn),
+}
+
+static WORK_SEQ: AtomicU32 = AtomicU32::new(0);
+
+pub(crate) fn ensure_parent_dir(output: &str) -> Result<(), String> {
+  if let Some(parent) = Path::new(output).parent() {
+    fs::create_dir_all(parent).map_err(|e| format!("创建输出目录失败：{e}"))?;
+  }
+  Ok(())
+}
+
+pub(crate) fn assert_output_not_same(input: &str, output: &str) -> Result<(), String> {
+  let ic = PathBuf::from(input).canonicalize().unwrap_or_else(|_| PathBuf::from(input));
+  let oc = PathBuf::from(output).canonicalize().unwrap_or_else(|_| PathBuf::from(output));
+  if ic == oc { return Err(format!("输出路径不能与输入文件相同：{}", input)); }
+  Ok(())
+}
+
+/// 路径版入参不能与输出相同（字节版总是写到临时目录，无需检查）
+pub(crate) fn assert_input_not_output(input: &InputOne, output: &str) -> Result<(), String> {
+  match input {
+    InputOne::Path(p) => assert_output_not_same(p, output),
+    InputOne::Bytes(_) => Ok(()),
+  }
+}
+
+/// 原子输出：外部工具先写到目标目录下的临时文件，commit 时 fsync + 重命名覆盖到目标（同 sign.rs 的 persist 做法）；
+/// 未 commit 就 drop 时临时文件自动删除，目标路径上不会出现写了一半的文件
+pub(crate) struct AtomicOutput {
+  tmp: tempfile::TempPath,
+  dest: PathBuf,
+}
+
+impl AtomicOutput {
+  pub fn new(output: &str) -> Result<Self, String> {
+    ensure_parent_dir(output)?;
+    let dest = PathBuf::from(output);
+    let dir = match dest.parent() { Some(d) if !d.as_os_str().is_empty() => d.to_path_buf(), _ => PathBuf::from(".") };
+    let tmp = tempfile::Builder::new().prefix(".lumenpdf_").suffix(".tmp").tempfile_in(&dir)
+      .map_err(|e| format!("创建临时输出失败：{e}"))?
+      .into_temp_path();
+    Ok(AtomicOutput { tmp, dest })
+  }
+
+  pub fn path(&self) -> String {
+    self.tmp.to_string_lossy().to_string()
+  }
+
+  pub fn commit(self) -> Result<(), String> {
+    let len = fs::metadata(&self.tmp).map(|m| m.len()).unwrap_or(0);
+    if len == 0 { return Err("输出为空，未写入目标文件".into()); }
+    fs::File::open(&self.tmp).and_then(|f| f.sync_all()).map_err(|e| format!("写入失败：{e}"))?;
+    self.tmp.persist(&self.dest).map_err(|e| format!("原子重命名失败：{}（{}）", e.error, self.dest.display()))
+  }
+
+  /// 就地替换（目标即原文件）：先把原文件复制为 <原文件>.bak，再原子覆盖；返回备份路径。
+  /// .bak 已存在时直接覆盖，只保留最近一次修改前的版本
+  pub fn commit_in_place(self) -> Result<String, String> {
+    let mut bak = self.dest.clone().into_os_string();
+    bak.push(".bak");
+    let bak = PathBuf::from(bak);
+    fs::copy(&self.dest, &bak).map_err(|e| format!("创建备份失败：{e}（{}）", bak.display()))?;
+    self.commit()?;
+    Ok(bak.to_string_lossy().to_string())
+  }
+}
+
+/// 就地修改只接受路径版入参（字节版没有可替换的原文件）
+pub(crate) fn in_place_path(input: &InputOne) -> Result<&str, String> {
+  match input {
+    InputOne::Path(p) => Ok(p),
+    InputOne::Bytes(pdf) => Err(format!("就地修改需要文件路径，字节入参无法替换原文件（{}）", pdf.name)),
+  }
+}
+
+/// 新建临时工作目录：%TEMP%/<identifier>/<tag>_<ts>_<seq>
+pub(crate) fn work_dir(app: &AppHandle, tag: &str) -> Result<PathBuf, String> {
+  let mut work = std::env::temp_dir();
+  work.push(app.config().identifier.replace('.', "_"));
+  let ts = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
+  let seq = WORK_SEQ.fetch_add(1, Ordering::Relaxed);
+  work.push(format!("{tag}_{ts}_{seq}"));
+  fs::create_dir_all(&work).map_err(|e| format!("创建临时目录失败：{e}"))?;
+  Ok(work)
+}
+
+pub(crate) fn write_temp_pdf(app: &AppHandle, p: &PdfIn, tag: &str) -> Result<(PathBuf, String), String> {
+  let work = work_dir(app, tag)?;
+  let path = stage_bytes(&work, p)?;
+  Ok((work, path))
+}
+
+/// 把入参落到磁盘：路径版原样返回，字节版写入 work 目录
+pub(crate) fn stage_input(work: &Path, input: &InputOne) -> Result<String, String> {
+  match input {
+    InputOne::Path(p) => Ok(p.clone()),
+    InputOne::Bytes(pdf) => stage_bytes(work, pdf),
+  }
+}
+
+fn stage_bytes(work: &Path, p: &PdfIn) -> Result<String, String> {
+  let path = work.join(sanitize(&p.name));
+  fs::write(&path, &p.data).map_err(|e| format!("写入临时文件失败：{e}"))?;
+  Ok(path.to_string_lossy().to_string())
+}
+
+/// lopdf 读入：路径版直接 load，字节版 load_mem
+pub(crate) fn load_pdf(input: &InputOne) -> Result<lopdf::Document, String> {
+  match input {
+    InputOne::Path(p) => lopdf::Document::load(p).map_err(|e| format!("解析 PDF 失败：{e}（{p}）")),
+    InputOne::Bytes(pdf) => lopdf::Document::load_mem(&pdf.data).map_err(|e| format!("解析 PDF 失败：{e}（{}）", pdf.name)),
+  }
+}
+
+/// lopdf 写出到 output（自动创建父目录）
+pub(crate) fn save_pdf(doc: &mut lopdf::Document, output: &str) -> Result<(), String> {
+  ensure_parent_dir(output)?;
+  doc.save(output).map(|_| ()).map_err(|e| format!("写出 PDF 失败：{e}（{output}）"))
+}
+
+pub(crate) fn catalog_id(doc: &lopdf::Document) -> Result<lopdf::ObjectId, String> {
+  doc.trailer.get(b"Root").and_then(|o| o.as_reference()).map_err(|e| format!("缺少文档目录（Root）：{e}"))
+}
+
+/// 文档 Info 字典（不存在则新建并挂到 trailer）
+pub(crate) fn info_mut(doc: &mut lopdf::Document) -> Result<&mut lopdf::Dictionary, String> {
+  let id = match doc.trailer.get(b"Info").and_then(|o| o.as_reference()) {
+    Ok(id) => id,
+    Err(_) => {
+      let id = doc.add_object(lopdf::Dictionary::new());
+      doc.trailer.set("Info", id);
+      id
+    }
+  };
+  match doc.get_object_mut(id) {
+    Ok(lopdf::Object::Dictionary(d)) => Ok(d),
+    _ => Err("Info 不是字典".into()),
+  }
+}
+
+/// PDF 文本串解码：UTF-16BE（带 BOM）或 PDFDocEncoding（按 Latin-1 近似）
+pub(crate) fn decode_text(bytes: &[u8]) -> String {
+  if bytes.starts_with(&[0xFE, 0xFF]) {
+    let units: Vec<u16> = bytes[2..].chunks_exact(2).map(|c| u16::from_be_bytes([c[0], c[1]])).collect();
+    String::from_utf16_lossy(&units)
+  } else {
+    bytes.iter().map(|&b| b as char).collect()
+  }
+}
+
+/// PDF 文本串编码：纯 ASCII 用字面串，否则 UTF-16BE + BOM
+pub(crate) fn encode_text(s: &str) -> lopdf::Object {
+  if s.is_ascii() { return lopdf::Object::string_literal(s); }
+  let mut bytes = vec![0xFE, 0xFF];
+  for u in s.encode_utf16() { bytes.extend_from_slice(&u.to_be_bytes()); }
+  lopdf::Object::String(bytes, lopdf::StringFormat::Hexadecimal)
+}
+
+/// 解析 qpdf 风格页范围（"1-3,8,z"、"r1" 为倒数第一页、"5-1" 倒序、"1-z:odd" 取序列中奇数位），返回 1 基页码序列
+pub(crate) fn parse_range(spec: &str, total: u32) -> Result<Vec<u32>, String> {
+  let (body, parity) = match spec.trim().rsplit_once(':') {
+    Some((b, "odd")) => (b, Some(0)),
+    Some((b, "even")) => (b, Some(1)),
+    Some(_) => return Err(format!("页范围无效：{spec}")),
+    None => (spec, None),
+  };
+  let page = |t: &str| -> Result<u32, String> {
+    let t = t.trim();
+    let n = if t == "z" { total }
+      else if let Some(r) = t.strip_prefix('r') { r.parse::<u32>().ok().filter(|&k| k >= 1 && k <= total).map(|k| total + 1 - k).unwrap_or(0) }
+      else { t.parse::<u32>().map_err(|_| format!("页范围无效：{spec}"))? };
+    if n == 0 || n > total { return Err(format!("页码越界：{t}（文档共 {total} 页）")); }
+    Ok(n)
+  };
+  let mut out = Vec::new();
+  for part in body.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+    match part.split_once('-') {
+      Some((a, b)) => {
+        let (a, b) = (page(a)?, page(b)?);
+        if a <= b { out.extend(a..=b); } else { out.extend((b..=a).rev()); }
+      }
+      None => out.push(page(part)?),
+    }
+  }
+  if let Some(keep) = parity {
+    out = out.into_iter().enumerate().filter(|(i, _)| i % 2 == keep).map(|(_, n)| n).collect();
+  }
+  if out.is_empty() { return Err(format!("页范围为空：{spec}")); }
+  Ok(out)
+}
+
+pub(crate) fn hex_sha256(data: &[u8]) -> String {
+  Sha256::digest(data).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// ---------- qpdf ----------
+pub(crate) fn find_qpdf(app: &AppHandle) -> Option<(PathBuf, PathBuf)> {
+  let dev_root = PathBuf::from("src-tauri").join("binaries");
+  let res_root = app.path().resolve("binaries", tauri::path::BaseDirectory::Resource).ok();
+
+  for root in [Some(dev_root), res_root].into_iter().flatten() {
+    let direct = [
+      root.join("qpdf").join("bin").join("qpdf.exe"),
+      root.join("qpdf").join("qpdf.exe"),
+      root.join("qpdf.exe"),
+    ];
+    for p in direct {
+      if p.exists() { return Some((p.parent()?.to_path_buf(), p)); }
+    }
+    if let Ok(iter) = fs::read_dir(&root) {
+      for ent in iter.flatten() {
+        let p = ent.path();
+        if p.is_dir() && p.file_name().and_then(OsStr::to_str).unwrap_or("").to_lowercase().contains("qpdf") {
+          let cand = p.join("bin").join("qpdf.exe");
+          if cand.exists() { return Some((cand.parent()?.to_path_buf(), cand)); }
+        }
+      }
+    }
+  }
+  None
+}
+
+pub(crate) fn verify_qpdf(exe: &Path, bin_dir: &Path) -> Result<(), String> {
+  let out = Command::new(exe).arg("--version").current_dir(bin_dir).output()
+    .map_err(|e| format!("qpdf 校验失败：{e}（exe: {}）", exe.display()))?;
+  if !out.status.success() { return Err(format!("qpdf --version 非0：{}", String::from_utf8_lossy(&out.stderr))); }
+  let s = String::from_utf8_lossy(&out.stdout).to_ascii_lowercase();
+  if !s.contains("qpdf") { return Err(format!("检测到的不是 qpdf CLI（stdout: {}）", s.trim())); }
+  Ok(())
+}
+
+/// 查找 + 校验 qpdf，返回 (bin_dir, exe)
+pub(crate) fn qpdf_exe(app: &AppHandle) -> Result<(PathBuf, PathBuf), String> {
+  let (bin_dir, exe) = find_qpdf(app).ok_or_else(|| "未找到 qpdf：请把 qpdf/bin/qpdf.exe 放到 binaries 目录树".to_string())?;
+  verify_qpdf(&exe, &bin_dir)?;
+  Ok((bin_dir, exe))
+}
+
+/// 执行 qpdf，非0 视为失败
+pub(crate) fn run_qpdf(app: &AppHandle, args: &[String]) -> Result<std::process::Output, String> {
+  let (bin_dir, exe) = qpdf_exe(app)?;
+  let out = run_with_env(&bin_dir, &exe, args, &[])?;
+  if out.status.success() { Ok(out) } else {
+    Err(format!("qpdf 执行失败：{}", String::from_utf8_lossy(&out.stderr)))
+  }
+}
+
+/// 大文档默认页数上限
+const DEFAULT_PAGE_LIMIT: u32 = 2000;
+
+/// 逐页渲染 / 单页拆分类操作的页数保护：超过上限且未 force 时拒绝执行，
+/// 避免误开几千页的文档时拉起成千上万个进程或图片塞满磁盘
+#[derive(Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PageGuard {
+  /// 确认后忽略上限
+  pub force: bool,
+  /// 页数上限，默认 2000
+  pub limit: Option<u32>,
+}
+
+impl PageGuard {
+  /// 通过时返回页数
+  pub(crate) fn check(&self, app: &AppHandle, input: &str) -> Result<u32, String> {
+    let total = page_count(app, input)?;
+    let limit = self.limit.unwrap_or(DEFAULT_PAGE_LIMIT);
+    if total > limit && !self.force {
+      return Err(format!("文档共 {total} 页，超过上限 {limit} 页；该操作逐页处理，确认继续请传 force: true"));
+    }
+    Ok(total)
+  }
+}
+
+/// qpdf --show-npages
+pub(crate) fn page_count(app: &AppHandle, input: &str) -> Result<u32, String> {
+  page_count_with(app, input, None)
+}
+
+/// 加密输入带 --password
+pub(crate) fn page_count_with(app: &AppHandle, input: &str, password: Option<&str>) -> Result<u32, String> {
+  let mut args: Vec<String> = password.map(|p| format!("--password={p}")).into_iter().collect();
+  args.extend(["--show-npages".into(), input.into()]);
+  let out = run_qpdf(app, &args)?;
+  String::from_utf8_lossy(&out.stdout).trim().parse::<u32>()
+    .map_err(|e| format!("无法解析页数：{e}"))
+}
+
+/// 按页片段拼装：qpdf primary --pages f1 r1 f2 r2 ... -- output（以 primary 保留文档级信息）
+pub(crate) fn assemble_pages(app: &AppHandle, primary: &str, segments: &[(String, String)], output: &str) -> Result<(), String> {
+  let mut args: Vec<String> = vec![primary.into(), "--pages".into()];
+  for (file, range) in segments { args.push(file.clone()); args.push(range.clone()); }
+  args.push("--".into());
+  args.push(output.into());
+  run_qpdf(app, &args).map(|_| ())
+}
+
+/// 把 (文件, 页码) 序列压成 qpdf 片段：相邻同文件且页码连续的合并为 "a-b"
+pub(crate) fn page_runs(pages: &[(String, u32)]) -> Vec<(String, String)> {
+  let mut runs: Vec<(String, u32, u32)> = Vec::new();
+  for (file, p) in pages {
+    match runs.last_mut() {
+      Some((f, _, end)) if f == file && *end + 1 == *p => *end = *p,
+      _ => runs.push((file.clone(), *p, *p)),
+    }
+  }
+  runs.into_iter()
+    .map(|(f, a, b)| (f, if a == b { a.to_string() } else { format!("{a}-{b}") }))
+    .collect()
+}
+
+// ---------- Ghostscript ----------
+pub(crate) fn find_gs(app: &AppHandle) -> Option<(PathBuf, PathBuf, Vec<(&'static str, String)>)> {
+  // 根：binaries/ghostscript/
+  let dev_root = PathBuf::from("src-tauri").join("binaries").join("ghostscript");
+  let res_root = app.path().resolve("binaries/ghostscript", tauri::path::BaseDirectory::Resource).ok();
+
+  for root in [Some(dev_root), res_root].into_iter().flatten() {
+    // ① 无版本目录（你现在的布局）
+    let bin = root.join("bin");
+    let exe = bin.join("gswin64c.exe");
+    if exe.exists() {
+      let lib = root.join("lib");
+      let resource = root.join("Resource");
+      let fonts = root.join("fonts");
+      if lib.is_dir() && resource.is_dir() {
+        let mut envs = vec![("GS_LIB", format!("{};{}", lib.display(), resource.display()))];
+        if fonts.is_dir() { envs.push(("GS_FONTPATH", fonts.display().to_string())); }
+        return Some((bin, exe, envs));
+      }
+    }
+
+    // ② 兼容：若有人放了版本目录，自动扫描
+    if let Ok(iter) = fs::read_dir(&root) {
+      for ent in iter.flatten() {
+        let vdir = ent.path();
+        if !vdir.is_dir() { continue; }
+        let bin = vdir.join("bin");
+        let exe = bin.join("gswin64c.exe");
+        if exe.exists() {
+          let lib = vdir.join("lib");
+          let resource = vdir.join("Resource");
+          let fonts = vdir.join("fonts");
+          if lib.is_dir() && resource.is_dir() {
+            let mut envs = vec![("GS_LIB", format!("{};{}", lib.display(), resource.display()))];
+            if fonts.is_dir() { envs.push(("GS_FONTPATH", fonts.display().to_string())); }
+            return Some((bin, exe, envs));
+          }
+        }
+      }
+    }
+  }
+  None
+}
+
+pub(crate) fn verify_gs(bin_dir: &Path, exe: &Path, envs: &[(&str, String)]) -> Result<(), String> {
+  let out = run_with_env(bin_dir, exe, &vec!["-v".into()], envs)?;
+  if !out.status.success() {
+    return Err(format!("Ghostscript 启动失败：{}", String::from_utf8_lossy(&out.stderr)));
+  }
+  Ok(())
+}
+
+/// 查找 + 校验 Ghostscript，返回 (bin_dir, exe, envs)
+pub(crate) fn gs_exe(app: &AppHandle) -> Result<(PathBuf, PathBuf, Vec<(&'static str, String)>), String> {
+  let (bin_dir, exe, envs) = find_gs(app).ok_or_else(|| "未找到 Ghostscript：请把 bin/lib/Resource 放到 binaries/ghostscript/".to_string())?;
+  verify_gs(&bin_dir, &exe, &envs)?;
+  Ok((bin_dir, exe, envs))
+}
+
+/// 执行 Ghostscript，非0 视为失败
+pub(crate) fn run_gs(app: &AppHandle, args: &[String]) -> Result<std::process::Output, String> {
+  let (bin_dir, exe, envs) = gs_exe(app)?;
+  let out = run_with_env(&bin_dir, &exe, args, &envs)?;
+  if out.status.success() { Ok(out) } else {
+    Err(format!("Ghostscript 执行失败：{}", String::from_utf8_lossy(&out.stderr)))
+  }
+}
+
+// ---------- Tesseract（OSD 方向检测） ----------
+/// 布局：binaries/tesseract/{tesseract.exe, tessdata/osd.traineddata}
+pub(crate) fn find_tesseract(app: &AppHandle) -> Option<(PathBuf, PathBuf, Vec<(&'static str, String)>)> {
+  let dev_root = PathBuf::from("src-tauri").join("binaries").join("tesseract");
+  let res_root = app.path().resolve("binaries/tesseract", tauri::path::BaseDirectory::Resource).ok();
+
+  for root in [Some(dev_root), res_root].into_iter().flatten() {
+    let exe = root.join("tesseract.exe");
+    let tessdata = root.join("tessdata");
+    if exe.exists() && tessdata.join("osd.traineddata").exists() {
+      return Some((root, exe, vec![("TESSDATA_PREFIX", tessdata.display().to_string())]));
+    }
+  }
+  None
+}
+
+/// 查找 + 校验 Tesseract，返回 (bin_dir, exe, envs)
+pub(crate) fn tesseract_exe(app: &AppHandle) -> Result<(PathBuf, PathBuf, Vec<(&'static str, String)>), String> {
+  let (bin_dir, exe, envs) = find_tesseract(app)
+    .ok_or_else(|| "未找到 Tesseract：请把 tesseract.exe 和 tessdata/osd.traineddata 放到 binaries/tesseract/".to_string())?;
+  let out = run_with_env(&bin_dir, &exe, &["--version".into()], &envs)?;
+  if !out.status.success() {
+    return Err(format!("Tesseract 启动失败：{}", String::from_utf8_lossy(&out.stderr)));
+  }
+  Ok((bin_dir, exe, envs))
+}
+
+// ---------- OCRmyPDF / jbig2（扫描件优化） ----------
+/// 在 binaries/<tool>/ 或名称含 tool 的子目录（如 binaries/ocrmypdf-16.0/）下找 exe，返回 (bin_dir, exe)
+fn find_sidecar(app: &AppHandle, tool: &str, exe_name: &str) -> Option<(PathBuf, PathBuf)> {
+  let dev_root = PathBuf::from("src-tauri").join("binaries");
+  let res_root = app.path().resolve("binaries", tauri::path::BaseDirectory::Resource).ok();
+
+  for root in [Some(dev_root), res_root].into_iter().flatten() {
+    for p in [root.join(tool).join("bin").join(exe_name), root.join(tool).join(exe_name)] {
+      if p.exists() { return Some((p.parent()?.to_path_buf(), p)); }
+    }
+    if let Ok(iter) = fs::read_dir(&root) {
+      for ent in iter.flatten() {
+        let p = ent.path();
+        if p.is_dir() && p.file_name().and_then(OsStr::to_str).unwrap_or("").to_lowercase().contains(tool) {
+          for cand in [p.join("bin").join(exe_name), p.join(exe_name)] {
+            if cand.exists() { return Some((cand.parent()?.to_path_buf(), cand)); }
+          }
+        }
+      }
+    }
+  }
+  None
+}
+
+/// 布局：binaries/ocrmypdf/ocrmypdf.exe（打包好的独立可执行）
+pub(crate) fn find_ocrmypdf(app: &AppHandle) -> Option<(PathBuf, PathBuf)> {
+  find_sidecar(app, "ocrmypdf", "ocrmypdf.exe")
+}
+
+/// 可选：binaries/jbig2/jbig2.exe，存在时 ocrmypdf 用 JBIG2 压缩黑白文字层
+pub(crate) fn find_jbig2(app: &AppHandle) -> Option<(PathBuf, PathBuf)> {
+  find_sidecar(app, "jbig2", "jbig2.exe")
+}
+
+// ---------- 字体 ----------
+/// binaries/fonts/ 下第一个 TTF / OTF（按文件名排序），供生成页面排版中文等非拉丁文字
+pub(crate) fn find_bundled_font(app: &AppHandle) -> Option<PathBuf> {
+  let dev_root = PathBuf::from("src-tauri").join("binaries").join("fonts");
+  let res_root = app.path().resolve("binaries/fonts", tauri::path::BaseDirectory::Resource).ok();
+
+  for root in [Some(dev_root), res_root].into_iter().flatten() {
+    let Ok(iter) = fs::read_dir(&root) else { continue };
+    let mut fonts: Vec<PathBuf> = iter.flatten().map(|e| e.path())
+      .filter(|p| p.extension().and_then(OsStr::to_str).is_some_and(|x| x.eq_ignore_ascii_case("ttf") || x.eq_ignore_ascii_case("otf")))
+      .collect();
+    fonts.sort();
+    if let Some(f) = fonts.into_iter().next() { return Some(f); }
+  }
+  None
+}
+
+// ---------- 通用 ----------
+pub(crate) fn run_with_env(bin_dir: &Path, exe: &Path, args: &[String], extra_env: &[(&str, String)]) -> Result<std::process::Output, String> {
+  let env_path = format!("{};{}", bin_dir.display(), std::env::var("PATH").unwrap_or_default());
+  let mut cmd = Command::new(exe);
+  cmd.args(args).current_dir(bin_dir).env("PATH", env_path);
+  for (k, v) in extra_env { cmd.env(k, v); }
+  cmd.output().map_err(|e| format!("执行失败：{e}（exe: {}）", exe.display()))
+}
+
+pub(crate) fn sanitize(name: &str) -> String {
+  name.chars().map(|c| match c { '/'|'\\'|':'|'*'|'?'|'"'|'<'|'>'|'|' => '_', _ => c }).collect()
+}
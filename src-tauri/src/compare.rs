@@ -0,0 +1,199 @@
+//! Side-by-side compare — render two revisions, place matching pages left / right, outline pixel-diff regions (printpdf)
+use tauri::AppHandle;
+use image::{codecs::jpeg::JpegEncoder, Rgb, RgbImage};
+use printpdf::{
+  Color, ColorBits, ColorSpace, Image, ImageFilter, ImageTransform, ImageXObject, Line, Mm, PdfDocument,
+  PdfDocumentReference, PdfLayerReference, Point, Px,
+};
+use serde::Serialize;
+use std::{
+  fs,
+  path::{Path, PathBuf},
+};
+
+use crate::common::{assert_output_not_same, ensure_parent_dir, stage_input, work_dir, InputOne, PageGuard};
+use crate::naming::resolve_output;
+use crate::render::{render_pages, RenderMode};
+
+const MIN_DPI: u32 = 36;
+const MAX_DPI: u32 = 200;
+/// 左右两页之间的间隔
+const GAP_MM: f32 = 6.0;
+/// 通道差超过该值的像素算"变化"，忽略抗锯齿噪点
+const PIXEL_THRESHOLD: u8 = 48;
+/// 格子内变化像素达到该数量才标记，忽略孤立噪点
+const CELL_MIN_CHANGED: usize = 3;
+const JPEG_QUALITY: u8 = 80;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompareResult {
+  pub path: String,
+  /// 输出页数（两份中较多的页数）
+  pub pages: u32,
+  /// 有差异的页（1 基）；页数不等时多出来的页也算
+  pub changed_pages: Vec<u32>,
+}
+
+/// 第 n 页 a 在左、b 在右；差异区域在两侧同位置以红框标出。按 a 的页面尺寸比对，b 尺寸不同时按左上角对齐
+#[tauri::command]
+pub async fn compare_side_by_side(app: AppHandle, a: InputOne, b: InputOne, output: String, dpi: u32, guard: Option<PageGuard>) -> Result<CompareResult, String> {
+  let output = resolve_output(&output, "compare", &[(&a).into(), (&b).into()])?;
+  if !(MIN_DPI..=MAX_DPI).contains(&dpi) {
+    return Err(format!("分辨率需在 {MIN_DPI}–{MAX_DPI} dpi 之间：{dpi}"));
+  }
+  ensure_parent_dir(&output)?;
+  let work = work_dir(&app, "compare")?;
+  let res = run_compare(&app, &work, &a, &b, &output, dpi, guard.unwrap_or_default());
+  let _ = fs::remove_dir_all(&work);
+  res.map(|(pages, changed_pages)| CompareResult { path: output, pages, changed_pages })
+}
+
+fn run_compare(app: &AppHandle, work: &Path, a: &InputOne, b: &InputOne, output: &str, dpi: u32, guard: PageGuard) -> Result<(u32, Vec<u32>), String> {
+  // 两份可能同名（同一文件的两个版本），分目录落盘
+  let (dir_a, dir_b) = (work.join("a"), work.join("b"));
+  fs::create_dir_all(&dir_a).and_then(|_| fs::create_dir_all(&dir_b)).map_err(|e| format!("创建临时目录失败：{e}"))?;
+  let (path_a, path_b) = (stage_input(&dir_a, a)?, stage_input(&dir_b, b)?);
+  assert_output_not_same(&path_a, output)?;
+  assert_output_not_same(&path_b, output)?;
+  guard.check(app, &path_a)?;
+  guard.check(app, &path_b)?;
+  let pngs_a = render_pages(app, &path_a, &dir_a.join("render"), dpi, RenderMode::Rgb, None)?;
+  let pngs_b = render_pages(app, &path_b, &dir_b.join("render"), dpi, RenderMode::Rgb, None)?;
+  let total = pngs_a.len().max(pngs_b.len());
+  if total == 0 { return Err("没有可渲染的页面".into()); }
+
+  let load = |p: Option<&PathBuf>| -> Result<Option<RgbImage>, String> {
+    p.map(|p| image::open(p).map(|i| i.to_rgb8()).map_err(|e| format!("读取渲染结果失败：{e}"))).transpose()
+  };
+  let px_to_mm = |px: u32| px as f32 / dpi as f32 * 25.4;
+  let cell = (dpi / 12).max(4);
+
+  let mut doc: Option<(PdfDocumentReference, PdfLayerReference)> = None;
+  let mut changed = Vec::new();
+  for i in 0..total {
+    let (img_a, img_b) = (load(pngs_a.get(i))?, load(pngs_b.get(i))?);
+    // 缺页一侧以同尺寸白页代替
+    let (w, h) = img_a.as_ref().or(img_b.as_ref()).map(|im| im.dimensions()).unwrap_or((1, 1));
+    let blank = || RgbImage::from_pixel(w, h, Rgb([255, 255, 255]));
+    let (img_a, img_b) = (img_a.unwrap_or_else(blank), img_b.unwrap_or_else(blank));
+    let regions = diff_regions(&img_a, &img_b, cell);
+    if !regions.is_empty() { changed.push(i as u32 + 1); }
+
+    let (wa, ha) = (px_to_mm(img_a.width()), px_to_mm(img_a.height()));
+    let (wb, hb) = (px_to_mm(img_b.width()), px_to_mm(img_b.height()));
+    let (page_w, page_h) = (wa + GAP_MM + wb, ha.max(hb));
+    let layer = match doc.as_ref() {
+      None => {
+        let (d, page, layer) = PdfDocument::new("side-by-side compare", Mm(page_w), Mm(page_h), "Layer 1");
+        let layer = d.get_page(page).get_layer(layer);
+        doc = Some((d, layer.clone()));
+        layer
+      }
+      Some((d, _)) => {
+        let (page, layer) = d.add_page(Mm(page_w), Mm(page_h), "Layer 1");
+        d.get_page(page).get_layer(layer)
+      }
+    };
+
+    place_image(&layer, &img_a, 0.0, page_h - ha, dpi)?;
+    place_image(&layer, &img_b, wa + GAP_MM, page_h - hb, dpi)?;
+    layer.set_outline_color(Color::Rgb(printpdf::Rgb::new(0.9, 0.1, 0.1, None)));
+    layer.set_outline_thickness(1.0);
+    for &(x0, y0, x1, y1) in &regions {
+      for left in [0.0, wa + GAP_MM] {
+        // 像素坐标原点在左上，PDF 在左下
+        let (l, r) = (left + px_to_mm(x0), left + px_to_mm(x1));
+        let (t, btm) = (page_h - px_to_mm(y0), page_h - px_to_mm(y1));
+        layer.add_line(Line {
+          points: vec![
+            (Point::new(Mm(l), Mm(btm)), false),
+            (Point::new(Mm(r), Mm(btm)), false),
+            (Point::new(Mm(r), Mm(t)), false),
+            (Point::new(Mm(l), Mm(t)), false),
+          ],
+          is_closed: true,
+        });
+      }
+    }
+  }
+
+  let (doc, _) = doc.ok_or("没有可渲染的页面")?;
+  let bytes = doc.save_to_bytes().map_err(|e| format!("生成对比文件失败：{e}"))?;
+  fs::write(output, bytes).map_err(|e| format!("写出失败：{e}（{output}）"))?;
+  Ok((total as u32, changed))
+}
+
+/// 以 cell×cell 像素为格比对，相连的变化格合并为外框 (x0, y0, x1, y1)（像素，左上原点）；
+/// 尺寸不同时超出对方范围的部分都算变化
+fn diff_regions(a: &RgbImage, b: &RgbImage, cell: u32) -> Vec<(u32, u32, u32, u32)> {
+  let (w, h) = (a.width().max(b.width()), a.height().max(b.height()));
+  let (cols, rows) = (w.div_ceil(cell) as usize, h.div_ceil(cell) as usize);
+  let mut grid = vec![false; cols * rows];
+  for cy in 0..rows {
+    for cx in 0..cols {
+      let mut n = 0;
+      'cell: for y in (cy as u32 * cell)..((cy as u32 + 1) * cell).min(h) {
+        for x in (cx as u32 * cell)..((cx as u32 + 1) * cell).min(w) {
+          let differs = match (a.get_pixel_checked(x, y), b.get_pixel_checked(x, y)) {
+            (Some(p), Some(q)) => p.0.iter().zip(q.0.iter()).any(|(u, v)| u.abs_diff(*v) > PIXEL_THRESHOLD),
+            (None, None) => false,
+            _ => true,
+          };
+          if differs {
+            n += 1;
+            if n >= CELL_MIN_CHANGED { break 'cell; }
+          }
+        }
+      }
+      grid[cy * cols + cx] = n >= CELL_MIN_CHANGED;
+    }
+  }
+
+  // 8 邻接连通块 → 外框
+  let mut seen = vec![false; grid.len()];
+  let mut boxes = Vec::new();
+  for start in 0..grid.len() {
+    if !grid[start] || seen[start] { continue; }
+    seen[start] = true;
+    let mut stack = vec![start];
+    let (mut x0, mut y0, mut x1, mut y1) = (usize::MAX, usize::MAX, 0, 0);
+    while let Some(i) = stack.pop() {
+      let (cx, cy) = (i % cols, i / cols);
+      (x0, y0, x1, y1) = (x0.min(cx), y0.min(cy), x1.max(cx), y1.max(cy));
+      for ny in cy.saturating_sub(1)..=(cy + 1).min(rows - 1) {
+        for nx in cx.saturating_sub(1)..=(cx + 1).min(cols - 1) {
+          let j = ny * cols + nx;
+          if grid[j] && !seen[j] { seen[j] = true; stack.push(j); }
+        }
+      }
+    }
+    let px = |c: usize, max: u32| (c as u32 * cell).min(max);
+    boxes.push((px(x0, w), px(y0, h), px(x1 + 1, w), px(y1 + 1, h)));
+  }
+  boxes
+}
+
+/// 以 JPEG 嵌入，左下角放在 (x, y) mm，按渲染 dpi 还原原尺寸
+fn place_image(layer: &PdfLayerReference, img: &RgbImage, x: f32, y: f32, dpi: u32) -> Result<(), String> {
+  let mut jpeg = Vec::new();
+  JpegEncoder::new_with_quality(&mut jpeg, JPEG_QUALITY).encode_image(img).map_err(|e| format!("编码页面图像失败：{e}"))?;
+  let xobj = ImageXObject {
+    width: Px(img.width() as usize),
+    height: Px(img.height() as usize),
+    color_space: ColorSpace::Rgb,
+    bits_per_component: ColorBits::Bit8,
+    interpolate: true,
+    image_data: jpeg,
+    image_filter: Some(ImageFilter::DCT),
+    smask: None,
+    clipping_bbox: None,
+  };
+  Image::from(xobj).add_to_layer(layer.clone(), ImageTransform {
+    translate_x: Some(Mm(x)),
+    translate_y: Some(Mm(y)),
+    dpi: Some(dpi as f32),
+    ..Default::default()
+  });
+  Ok(())
+}
@@ -1,87 +1,327 @@
-//! Compress PDF — prefer Ghostscript (lossy) & fallback qpdf (lossless).
+//! Compress PDF — prefer Ghostscript (lossy) & fallback qpdf (lossless); QpdfMax = qpdf-only max flate; target-size DPI search; per-range presets.
 //! Layout: binaries/ghostscript/{bin,lib,Resource[,fonts]}  +  binaries/qpdf/bin
-use tauri::{AppHandle, Manager};
-use serde::Deserialize;
+use tauri::{AppHandle, Emitter};
+use serde::{Deserialize, Serialize};
 use std::{
-  fs, ffi::OsStr,
+  collections::BTreeSet,
+  fs,
   path::{Path, PathBuf},
-  process::Command,
-  time::{SystemTime, UNIX_EPOCH},
 };
 
+use crate::common::{
+  assemble_pages, assert_input_not_output, assert_output_not_same, gs_exe, in_place_path, page_count, page_runs,
+  parse_range, qpdf_exe, run_with_env, stage_input, work_dir, write_temp_pdf, AtomicOutput, InputOne,
+};
+use crate::limits::check_input;
+use crate::naming::resolve_output;
+use crate::producer::{producer_label, rewrite_producer};
+use crate::render::{render_pages, similarity, RenderMode};
+use crate::sign::{read_head_tail, validate_pdf};
+
 #[derive(Deserialize, Clone)]
-pub struct PdfIn { pub name: String, pub data: Vec<u8> }
+#[serde(rename_all = "lowercase")]
+pub enum CompressPreset { Lossless, Small, Smaller, Tiny, QpdfMax }
+
+/// Ghostscript 降采样插值方式
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum DownsampleType { Bicubic, Average, Subsample }
 
-#[derive(Deserialize)]
-#[serde(untagged)]
-pub enum InputOne {
-  Path(String),
-  Bytes(PdfIn),
+impl DownsampleType {
+  fn gs_name(self) -> &'static str {
+    match self { DownsampleType::Bicubic => "/Bicubic", DownsampleType::Average => "/Average", DownsampleType::Subsample => "/Subsample" }
+  }
 }
 
+/// compress 可选参数（均可省略，省略即保持原默认行为）
 #[derive(Deserialize, Clone)]
-#[serde(rename_all = "lowercase")]
-pub enum CompressPreset { Lossless, Small, Smaller, Tiny }
+#[serde(rename_all = "camelCase", default)]
+pub struct CompressOptions {
+  /// Ghostscript -dCompatibilityLevel（1.3–2.0），默认 1.4 兼容性最好
+  pub compat_level: Option<String>,
+  /// 只降采样分辨率超过 目标 × 阈值 的图片（Ghostscript 要求 ≥ 1.0，其默认 1.5）
+  pub downsample_threshold: Option<f32>,
+  /// 彩色 / 灰度 / 黑白图统一使用的降采样方式；省略时彩色灰度 Bicubic、黑白 Subsample
+  pub interpolation: Option<DownsampleType>,
+  /// 就地压缩：忽略 output，结果覆盖原文件（仅路径入参），覆盖前备份为 <原文件>.bak。
+  /// 有意绕过"输出不能等于输入"的保护；备份会被下一次就地操作覆盖
+  pub in_place: bool,
+  /// Ghostscript 输出再做头尾标记 + qpdf --check 校验，不通过则改走 qpdf 无损（默认开启）
+  pub verify_output: bool,
+  /// 首 / 中 / 末页低分辨率渲染前后对比，相似度低于阈值（内容丢失）则改走 qpdf 无损
+  pub verify_visual: bool,
+  /// Ghostscript 内存上限（MB），映射到 -dMaxBitmap / -dBufferSpace；内存受限的容器里用
+  pub max_memory_mb: Option<u64>,
+  /// Ghostscript -dNumRenderingThreads
+  pub threads: Option<u32>,
+  /// 输出的 /Producer /Creator（含 XMP）改为该值，空串时用应用名；省略则保留 Ghostscript / qpdf 写入的值
+  pub producer: Option<String>,
+  /// 只对这些页（qpdf 页范围，可多段）做 Ghostscript 压缩，其余页由 qpdf 原样拼回；仅 small / smaller / tiny（compress_to_target 忽略）
+  pub pages: Option<Vec<String>>,
+}
+
+impl Default for CompressOptions {
+  fn default() -> Self {
+    CompressOptions {
+      compat_level: None,
+      downsample_threshold: None,
+      interpolation: None,
+      in_place: false,
+      verify_output: true,
+      verify_visual: false,
+      max_memory_mb: None,
+      threads: None,
+      producer: None,
+      pages: None,
+    }
+  }
+}
+
+const DEFAULT_COMPAT_LEVEL: &str = "1.4";
+const COMPAT_LEVELS: [&str; 6] = ["1.3", "1.4", "1.5", "1.6", "1.7", "2.0"];
+const MEMORY_MB_RANGE: std::ops::RangeInclusive<u64> = 64..=65536;
+const THREADS_RANGE: std::ops::RangeInclusive<u32> = 1..=64;
+
+impl CompressOptions {
+  pub(crate) fn validate(&self) -> Result<(), String> {
+    if let Some(v) = self.compat_level.as_deref() {
+      if !COMPAT_LEVELS.contains(&v.trim()) {
+        return Err(format!("兼容级别无效：{v}（可选 {}）", COMPAT_LEVELS.join(" / ")));
+      }
+    }
+    if let Some(t) = self.downsample_threshold {
+      if !(t.is_finite() && t >= 1.0) { return Err(format!("降采样阈值无效：{t}（需 ≥ 1.0）")); }
+    }
+    if let Some(mb) = self.max_memory_mb {
+      if !MEMORY_MB_RANGE.contains(&mb) {
+        return Err(format!("内存上限无效：{mb} MB（需 {}–{} MB）", MEMORY_MB_RANGE.start(), MEMORY_MB_RANGE.end()));
+      }
+    }
+    if let Some(n) = self.threads {
+      if !THREADS_RANGE.contains(&n) {
+        return Err(format!("线程数无效：{n}（需 {}–{}）", THREADS_RANGE.start(), THREADS_RANGE.end()));
+      }
+    }
+    Ok(())
+  }
+
+  /// 内存 / 线程限制参数：整页位图上限取全部预算，分带缓冲取四分之一，其余留给解释器本身
+  fn resource_args(&self) -> Vec<String> {
+    let mut args = Vec::new();
+    if let Some(mb) = self.max_memory_mb {
+      let bytes = mb * 1024 * 1024;
+      args.push(format!("-dMaxBitmap={bytes}"));
+      args.push(format!("-dBufferSpace={}", bytes / 4));
+    }
+    if let Some(n) = self.threads { args.push(format!("-dNumRenderingThreads={n}")); }
+    args
+  }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompressResult {
+  pub path: String,
+  /// 来源页范围（拆分类操作才有）
+  pub range: Option<String>,
+  pub bytes_before: u64,
+  pub bytes_after: u64,
+  /// Ghostscript 失败或输出未通过校验，结果来自 qpdf 无损
+  pub fell_back: bool,
+  /// 开启 verify_visual 时各抽样页的相似度（针对 Ghostscript 输出，回退后仍保留以说明原因）
+  pub visual_scores: Option<Vec<VisualScore>>,
+  /// 指定 pages 时实际重新压缩的页（其余页原样保留）
+  pub recompressed_pages: Option<Vec<u32>>,
+}
+
+#[derive(Debug, Serialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct VisualScore {
+  pub page: u32,
+  /// 0–1，1 为完全一致
+  pub score: f32,
+}
+
+/// run_path 的结果
+pub(crate) struct Compressed {
+  pub fell_back: bool,
+  pub visual_scores: Option<Vec<VisualScore>>,
+  pub recompressed_pages: Option<Vec<u32>>,
+}
+
+impl CompressResult {
+  pub(crate) fn measure(input: &str, output: &str, range: Option<&str>, c: Compressed) -> Self {
+    let size = |p: &str| fs::metadata(p).map(|m| m.len()).unwrap_or(0);
+    CompressResult {
+      path: output.to_string(),
+      range: range.map(String::from),
+      bytes_before: size(input),
+      bytes_after: size(output),
+      fell_back: c.fell_back,
+      visual_scores: c.visual_scores,
+      recompressed_pages: c.recompressed_pages,
+    }
+  }
+}
 
 #[tauri::command]
-pub async fn compress(app: AppHandle, input: InputOne, output: String, preset: CompressPreset) -> Result<String, String> {
-  ensure_parent_dir(&output)?;
-  match input {
-    InputOne::Path(p) => { assert_output_not_same(&p, &output)?; run_path(&app, &p, &output, &preset).await?; Ok(output) }
+pub async fn compress(app: AppHandle, input: InputOne, output: String, preset: CompressPreset, options: Option<CompressOptions>) -> Result<CompressResult, String> {
+  check_input(&input)?;
+  let opts = options.unwrap_or_default();
+  opts.validate()?;
+  if opts.in_place {
+    let path = in_place_path(&input)?.to_string();
+    let out = AtomicOutput::new(&path)?;
+    let c = run_path(&app, &path, &out.path(), &preset, &opts).await?;
+    let res = CompressResult::measure(&path, &out.path(), None, c);
+    out.commit_in_place()?;
+    return Ok(CompressResult { path, ..res });
+  }
+  let output = resolve_output(&output, "compress", &[(&input).into()])?;
+  let out = AtomicOutput::new(&output)?;
+  let res = match input {
+    InputOne::Path(p) => {
+      assert_output_not_same(&p, &output)?;
+      let c = run_path(&app, &p, &out.path(), &preset, &opts).await?;
+      CompressResult::measure(&p, &out.path(), None, c)
+    }
     InputOne::Bytes(pdf) => {
-      let (work, in_path) = write_temp_pdf(&app, &pdf)?;
+      let (work, in_path) = write_temp_pdf(&app, &pdf, "compress")?;
       assert_output_not_same(&in_path, &output)?;
-      let res = run_path(&app, &in_path, &output, &preset).await;
+      let res = run_path(&app, &in_path, &out.path(), &preset, &opts).await
+        .map(|c| CompressResult::measure(&in_path, &out.path(), None, c));
       let _ = fs::remove_dir_all(&work);
-      res.map(|_| output)
+      res?
     }
+  };
+  out.commit()?;
+  Ok(CompressResult { path: output, ..res })
+}
+
+/// 返回是否回退到了 qpdf 无损（及视觉比对得分）
+pub(crate) async fn run_path(app: &AppHandle, input: &str, output: &str, preset: &CompressPreset, opts: &CompressOptions) -> Result<Compressed, String> {
+  let c = match opts.pages.as_deref() {
+    Some(ranges) => run_selected(app, input, output, preset, opts, ranges).await?,
+    None => run_engine(app, input, output, preset, opts).await?,
+  };
+  if let Some(p) = opts.producer.as_deref() { rewrite_producer(app, output, &producer_label(app, Some(p)))?; }
+  Ok(c)
+}
+
+/// 只压缩选中的页：拆出 → 压缩 → 与其余原页按原顺序拼回（以原文件为主，书签等文档级信息保留）
+async fn run_selected(app: &AppHandle, input: &str, output: &str, preset: &CompressPreset, opts: &CompressOptions, ranges: &[String]) -> Result<Compressed, String> {
+  if matches!(preset, CompressPreset::Lossless | CompressPreset::QpdfMax) {
+    return Err("pages 只适用于 Ghostscript 预设（small / smaller / tiny）".into());
   }
+  let work = work_dir(app, "compress_pages")?;
+  let res = compress_selected(app, &work, input, output, preset, opts, ranges).await;
+  let _ = fs::remove_dir_all(&work);
+  res
 }
 
-async fn run_path(app: &AppHandle, input: &str, output: &str, preset: &CompressPreset) -> Result<(), String> {
+async fn compress_selected(app: &AppHandle, work: &Path, input: &str, output: &str, preset: &CompressPreset, opts: &CompressOptions, ranges: &[String]) -> Result<Compressed, String> {
+  let total = page_count(app, input)?;
+  let mut selected = BTreeSet::new();
+  for r in ranges { selected.extend(parse_range(r, total)?); }
+  if selected.is_empty() { return Err("pages 未选中任何页".into()); }
+
+  let picked: Vec<(String, u32)> = selected.iter().map(|&p| (input.to_string(), p)).collect();
+  let sel = work.join("selected.pdf").to_string_lossy().to_string();
+  assemble_pages(app, input, &page_runs(&picked), &sel)?;
+  let packed = work.join("selected_compressed.pdf").to_string_lossy().to_string();
+  let c = run_engine(app, &sel, &packed, preset, opts).await?;
+
+  // 原页序：选中的第 k 页取自压缩结果的第 k 页
+  let mut k = 0;
+  let order: Vec<(String, u32)> = (1..=total).map(|p| {
+    if selected.contains(&p) { k += 1; (packed.clone(), k) } else { (input.to_string(), p) }
+  }).collect();
+  assemble_pages(app, input, &page_runs(&order), output)?;
+  Ok(Compressed { recompressed_pages: Some(selected.into_iter().collect()), ..c })
+}
+
+async fn run_engine(app: &AppHandle, input: &str, output: &str, preset: &CompressPreset, opts: &CompressOptions) -> Result<Compressed, String> {
+  let lossless = |fell_back| Compressed { fell_back, visual_scores: None, recompressed_pages: None };
   match preset {
-    CompressPreset::Lossless => qpdf_lossless(app, input, output).await,
-    _ => match gs_lossy(app, input, output, preset).await {
-      Ok(()) => Ok(()),
-      Err(e) => { eprintln!("[compress] Ghostscript 失败/缺失：{e}；回退 qpdf 无损"); qpdf_lossless(app, input, output).await }
+    CompressPreset::Lossless => qpdf_lossless(app, input, output).await.map(|_| lossless(false)),
+    CompressPreset::QpdfMax => qpdf_max(app, input, output).await.map(|_| lossless(false)),
+    _ => {
+      let mut visual_scores = None;
+      let res = gs_lossy(app, input, output, preset, opts).await
+        .and_then(|_| if opts.verify_output { verify_pdf(app, output) } else { Ok(()) })
+        .and_then(|_| {
+          if !opts.verify_visual { return Ok(()); }
+          let scores = visual_compare(app, input, output)?;
+          let worst = scores.iter().map(|s| s.score).fold(1.0, f32::min);
+          visual_scores = Some(scores);
+          if worst < VISUAL_MIN_SIMILARITY { Err(format!("视觉比对相似度 {worst:.3} 低于 {VISUAL_MIN_SIMILARITY}")) } else { Ok(()) }
+        });
+      let fell_back = match res {
+        Ok(()) => false,
+        Err(e) => {
+          eprintln!("[compress] Ghostscript 失败/缺失或输出校验未通过：{e}；回退 qpdf 无损");
+          qpdf_lossless(app, input, output).await?;
+          true
+        }
+      };
+      Ok(Compressed { fell_back, visual_scores, recompressed_pages: None })
     }
   }
 }
 
-fn ensure_parent_dir(output: &str) -> Result<(), String> {
-  if let Some(parent) = Path::new(output).parent() {
-    fs::create_dir_all(parent).map_err(|e| format!("创建输出目录失败：{e}"))?;
-  }
-  Ok(())
-}
+/// 视觉比对的渲染分辨率：只看版面与内容有无，低分辨率也抹平了降采样本身带来的差异
+const VISUAL_DPI: u32 = 36;
+/// 任一抽样页低于该相似度即视为内容丢失
+const VISUAL_MIN_SIMILARITY: f32 = 0.85;
 
-fn assert_output_not_same(input: &str, output: &str) -> Result<(), String> {
-  let ic = PathBuf::from(input).canonicalize().unwrap_or_else(|_| PathBuf::from(input));
-  let oc = PathBuf::from(output).canonicalize().unwrap_or_else(|_| PathBuf::from(output));
-  if ic == oc { return Err(format!("输出路径不能与输入文件相同：{}", input)); }
-  Ok(())
+/// 抽样首 / 中 / 末页，分别渲染输入与输出并打分；页数不一致直接判失败
+fn visual_compare(app: &AppHandle, input: &str, output: &str) -> Result<Vec<VisualScore>, String> {
+  let total = page_count(app, input)?;
+  if page_count(app, output)? != total { return Err("压缩前后页数不一致".into()); }
+  let mut pages = vec![1, total.div_ceil(2), total];
+  pages.dedup();
+  let work = work_dir(app, "verify_visual")?;
+  let res = pages.into_iter().map(|page| {
+    let render = |src: &str, dir: &str| -> Result<PathBuf, String> {
+      let out = work.join(format!("{dir}_{page}"));
+      render_pages(app, src, &out, VISUAL_DPI, RenderMode::Gray, Some((page, page)))?.into_iter().next().ok_or(format!("第 {page} 页渲染失败"))
+    };
+    Ok(VisualScore { page, score: similarity(&render(input, "in")?, &render(output, "out")?)? })
+  }).collect();
+  let _ = fs::remove_dir_all(&work);
+  res
 }
 
-fn write_temp_pdf(app: &AppHandle, p: &PdfIn) -> Result<(PathBuf, String), String> {
-  let mut work = std::env::temp_dir();
-  work.push(app.config().identifier.replace('.', "_"));
-  let ts = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
-  work.push(format!("compress_{ts}"));
-  fs::create_dir_all(&work).map_err(|e| format!("创建临时目录失败：{e}"))?;
-  let mut path = work.clone();
-  path.push(sanitize(&p.name));
-  fs::write(&path, &p.data).map_err(|e| format!("写入临时文件失败：{e}"))?;
-  Ok((work, path.to_string_lossy().to_string()))
+/// 头尾标记 + qpdf --check（退出码 3 仅警告，文件仍可打开）
+pub(crate) fn verify_pdf(app: &AppHandle, path: &str) -> Result<(), String> {
+  let (head, tail) = read_head_tail(Path::new(path)).map_err(|e| format!("读取输出失败：{e}"))?;
+  validate_pdf(&head, &tail)?;
+  let (bin_dir, exe) = qpdf_exe(app)?;
+  let out = run_with_env(&bin_dir, &exe, &["--check".into(), path.into()], &[])?;
+  match out.status.code() {
+    Some(0) | Some(3) => Ok(()),
+    _ => Err(format!("qpdf --check 未通过：{}", String::from_utf8_lossy(&out.stderr))),
+  }
 }
 
 // ---------- Ghostscript（有损，根目录优先，版本目录兼容） ----------
-async fn gs_lossy(app: &AppHandle, input: &str, output: &str, preset: &CompressPreset) -> Result<(), String> {
-  let (bin_dir, exe, envs) = find_gs(app).ok_or_else(|| "未找到 Ghostscript：请把 bin/lib/Resource 放到 binaries/ghostscript/".to_string())?;
-  verify_gs(&bin_dir, &exe, &envs)?; // 防呆校验
+async fn gs_lossy(app: &AppHandle, input: &str, output: &str, preset: &CompressPreset, opts: &CompressOptions) -> Result<(), String> {
+  let (settings, dpi) = match preset {
+    CompressPreset::Small   => ("/ebook", 150),
+    CompressPreset::Smaller => ("/screen", 96),
+    CompressPreset::Tiny    => ("/screen", 72),
+    CompressPreset::Lossless | CompressPreset::QpdfMax => unreachable!(),
+  };
+  gs_pdfwrite(app, input, output, settings, dpi, opts)
+}
+
+/// pdfwrite 重写，三类图片统一降采样到 dpi
+fn gs_pdfwrite(app: &AppHandle, input: &str, output: &str, settings: &str, dpi: u32, opts: &CompressOptions) -> Result<(), String> {
+  let (bin_dir, exe, envs) = gs_exe(app)?; // 查找 + 防呆校验
 
   let mut args: Vec<String> = vec![
     "-sDEVICE=pdfwrite".into(),
-    "-dCompatibilityLevel=1.4".into(),
+    format!("-dCompatibilityLevel={}", opts.compat_level.as_deref().map(str::trim).unwrap_or(DEFAULT_COMPAT_LEVEL)),
     "-dDetectDuplicateImages=true".into(),
     "-dEncodeColorImages=true".into(),
     "-dEncodeGrayImages=true".into(),
@@ -89,18 +329,18 @@ async fn gs_lossy(app: &AppHandle, input: &str, output: &str, preset: &CompressP
     "-dDownsampleColorImages=true".into(),
     "-dDownsampleGrayImages=true".into(),
     "-dDownsampleMonoImages=true".into(),
-    "-dColorImageDownsampleType=/Bicubic".into(),
-    "-dGrayImageDownsampleType=/Bicubic".into(),
-    "-dMonoImageDownsampleType=/Subsample".into(),
+    format!("-dColorImageDownsampleType={}", opts.interpolation.unwrap_or(DownsampleType::Bicubic).gs_name()),
+    format!("-dGrayImageDownsampleType={}", opts.interpolation.unwrap_or(DownsampleType::Bicubic).gs_name()),
+    format!("-dMonoImageDownsampleType={}", opts.interpolation.unwrap_or(DownsampleType::Subsample).gs_name()),
     "-dNOPAUSE".into(), "-dQUIET".into(), "-dBATCH".into(),
     format!("-sOutputFile={}", output),
+    format!("-dPDFSETTINGS={settings}"),
   ];
-  match preset {
-    CompressPreset::Small   => { args.push("-dPDFSETTINGS=/ebook".into());  args.push("-dColorImageResolution=150".into()); args.push("-dGrayImageResolution=150".into()); args.push("-dMonoImageResolution=150".into()); }
-    CompressPreset::Smaller => { args.push("-dPDFSETTINGS=/screen".into()); args.push("-dColorImageResolution=96".into());  args.push("-dGrayImageResolution=96".into());  args.push("-dMonoImageResolution=96".into());  }
-    CompressPreset::Tiny    => { args.push("-dPDFSETTINGS=/screen".into()); args.push("-dColorImageResolution=72".into());  args.push("-dGrayImageResolution=72".into());  args.push("-dMonoImageResolution=72".into());  }
-    CompressPreset::Lossless => unreachable!(),
+  for kind in ["Color", "Gray", "Mono"] { args.push(format!("-d{kind}ImageResolution={dpi}")); }
+  if let Some(t) = opts.downsample_threshold {
+    for kind in ["Color", "Gray", "Mono"] { args.push(format!("-d{kind}ImageDownsampleThreshold={t}")); }
   }
+  args.extend(opts.resource_args());
   args.push(input.into());
 
   let out = run_with_env(&bin_dir, &exe, &args, &envs)?;
@@ -109,58 +349,194 @@ async fn gs_lossy(app: &AppHandle, input: &str, output: &str, preset: &CompressP
   }
 }
 
-fn find_gs(app: &AppHandle) -> Option<(PathBuf, PathBuf, Vec<(&'static str, String)>)> {
-  // 根：binaries/ghostscript/
-  let dev_root = PathBuf::from("src-tauri").join("binaries").join("ghostscript");
-  let res_root = app.path().resolve("binaries/ghostscript", tauri::path::BaseDirectory::Resource).ok();
-
-  for root in [Some(dev_root), res_root].into_iter().flatten() {
-    // ① 无版本目录（你现在的布局）
-    let bin = root.join("bin");
-    let exe = bin.join("gswin64c.exe");
-    if exe.exists() {
-      let lib = root.join("lib");
-      let resource = root.join("Resource");
-      let fonts = root.join("fonts");
-      if lib.is_dir() && resource.is_dir() {
-        let mut envs = vec![("GS_LIB", format!("{};{}", lib.display(), resource.display()))];
-        if fonts.is_dir() { envs.push(("GS_FONTPATH", fonts.display().to_string())); }
-        return Some((bin, exe, envs));
-      }
+/// pdfwrite 重写但不降采样、不改图片编码：只折叠重复图片、合并字体子集（同模板文件合并后的去重）
+pub(crate) fn gs_dedupe(app: &AppHandle, input: &str, output: &str) -> Result<(), String> {
+  let (bin_dir, exe, envs) = gs_exe(app)?;
+  let mut args: Vec<String> = vec![
+    "-sDEVICE=pdfwrite".into(),
+    "-dDetectDuplicateImages=true".into(),
+    "-dCompressFonts=true".into(),
+    "-dSubsetFonts=true".into(),
+    "-dPassThroughJPEGImages=true".into(),
+    "-dPassThroughJPXImages=true".into(),
+  ];
+  for kind in ["Color", "Gray", "Mono"] { args.push(format!("-dDownsample{kind}Images=false")); }
+  for kind in ["Color", "Gray"] {
+    args.push(format!("-dAutoFilter{kind}Images=false"));
+    args.push(format!("-d{kind}ImageFilter=/FlateEncode"));
+  }
+  args.extend(["-dNOPAUSE".into(), "-dQUIET".into(), "-dBATCH".into(), format!("-sOutputFile={output}"), input.into()]);
+  let out = run_with_env(&bin_dir, &exe, &args, &envs)?;
+  if out.status.success() { Ok(()) } else {
+    Err(String::from_utf8_lossy(&out.stderr).to_string())
+  }
+}
+
+// ---------- 目标体积：按分辨率二分搜索 ----------
+const TARGET_PROGRESS_EVT: &str = "compress:progress";
+const TARGET_MIN_DPI: u32 = 36;
+const TARGET_MAX_DPI: u32 = 300;
+const TARGET_MAX_PASSES: u32 = 7;
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct TargetPass { pass: u32, dpi: u32, resulting_bytes: u64 }
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TargetCompressResult {
+  pub path: String,
+  pub bytes_before: u64,
+  pub bytes_after: u64,
+  /// 最终采用的图片分辨率
+  pub dpi: u32,
+  /// 实际尝试次数
+  pub passes: u32,
+  /// 为 false 表示最低分辨率仍超出目标，输出为尝试中最小的结果
+  pub met_target: bool,
+}
+
+/// 在 36–300 dpi 间二分，保留不超过 target_bytes 的最高分辨率结果；每次尝试发送 compress:progress {pass, dpi, resultingBytes}
+#[tauri::command]
+pub async fn compress_to_target(app: AppHandle, input: InputOne, output: String, target_bytes: u64, options: Option<CompressOptions>) -> Result<TargetCompressResult, String> {
+  if target_bytes == 0 { return Err("目标体积需大于 0".into()); }
+  check_input(&input)?;
+  let opts = options.unwrap_or_default();
+  opts.validate()?;
+  let output = resolve_output(&output, "compress", &[(&input).into()])?;
+  assert_input_not_output(&input, &output)?;
+  let out = AtomicOutput::new(&output)?;
+  let work = work_dir(&app, "compress_target")?;
+  let res = stage_input(&work, &input).and_then(|in_path| search_target(&app, &work, &in_path, &out.path(), target_bytes, &opts));
+  let _ = fs::remove_dir_all(&work);
+  let res = res?;
+  out.commit()?;
+  Ok(TargetCompressResult { path: output, ..res })
+}
+
+fn search_target(app: &AppHandle, work: &Path, input: &str, output: &str, target: u64, opts: &CompressOptions) -> Result<TargetCompressResult, String> {
+  let (mut lo, mut hi) = (TARGET_MIN_DPI, TARGET_MAX_DPI);
+  // (dpi, bytes, 文件)：best 为达标的最高分辨率，smallest 兜底
+  let mut best: Option<(u32, u64, PathBuf)> = None;
+  let mut smallest: Option<(u32, u64, PathBuf)> = None;
+  let mut passes = 0;
+  while lo <= hi && passes < TARGET_MAX_PASSES {
+    passes += 1;
+    // 首次从常用的 150 dpi 起步，之后取区间中点
+    let dpi = if passes == 1 { 150 } else { (lo + hi) / 2 };
+    let path = work.join(format!("pass_{passes}_{dpi}.pdf"));
+    gs_pdfwrite(app, input, &path.to_string_lossy(), "/ebook", dpi, opts)?;
+    let bytes = fs::metadata(&path).map(|m| m.len()).map_err(|e| format!("读取压缩结果失败：{e}"))?;
+    let _ = app.emit(TARGET_PROGRESS_EVT, TargetPass { pass: passes, dpi, resulting_bytes: bytes });
+
+    if smallest.as_ref().is_none_or(|s| bytes < s.1) { smallest = Some((dpi, bytes, path.clone())); }
+    if bytes <= target {
+      if best.as_ref().is_none_or(|b| dpi > b.0) { best = Some((dpi, bytes, path)); }
+      lo = dpi + 1;
+    } else {
+      hi = dpi.saturating_sub(1);
     }
+  }
 
-    // ② 兼容：若有人放了版本目录，自动扫描
-    if let Ok(iter) = fs::read_dir(&root) {
-      for ent in iter.flatten() {
-        let vdir = ent.path();
-        if !vdir.is_dir() { continue; }
-        let bin = vdir.join("bin");
-        let exe = bin.join("gswin64c.exe");
-        if exe.exists() {
-          let lib = vdir.join("lib");
-          let resource = vdir.join("Resource");
-          let fonts = vdir.join("fonts");
-          if lib.is_dir() && resource.is_dir() {
-            let mut envs = vec![("GS_LIB", format!("{};{}", lib.display(), resource.display()))];
-            if fonts.is_dir() { envs.push(("GS_FONTPATH", fonts.display().to_string())); }
-            return Some((bin, exe, envs));
-          }
-        }
-      }
+  let met_target = best.is_some();
+  let (dpi, mut bytes_after, path) = best.or(smallest).ok_or("未产生任何压缩结果")?;
+  fs::copy(&path, output).map_err(|e| format!("写入输出失败：{e}"))?;
+  if let Some(p) = opts.producer.as_deref() {
+    rewrite_producer(app, output, &producer_label(app, Some(p)))?;
+    bytes_after = fs::metadata(output).map(|m| m.len()).unwrap_or(bytes_after);
+  }
+  let bytes_before = fs::metadata(input).map(|m| m.len()).unwrap_or(0);
+  Ok(TargetCompressResult { path: output.to_string(), bytes_before, bytes_after, dpi, passes, met_target })
+}
+
+// ---------- 分段预设 ----------
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RangePart {
+  pub range: String,
+  /// 该段拆出后压缩前 / 后的体积
+  pub bytes_before: u64,
+  pub bytes_after: u64,
+  pub fell_back: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RangesCompressResult {
+  pub path: String,
+  pub bytes_before: u64,
+  pub bytes_after: u64,
+  /// 与 rules 顺序一致
+  pub parts: Vec<RangePart>,
+}
+
+/// 每条规则 (页范围, 预设) 单独拆出压缩，再按原页序拼回一个文件；范围须不重叠且覆盖全部页
+#[tauri::command]
+pub async fn compress_ranges(app: AppHandle, input: InputOne, output: String, rules: Vec<(String, CompressPreset)>) -> Result<RangesCompressResult, String> {
+  if rules.is_empty() { return Err("请至少提供一条压缩规则".into()); }
+  check_input(&input)?;
+  let output = resolve_output(&output, "compress", &[(&input).into()])?;
+  assert_input_not_output(&input, &output)?;
+  let out = AtomicOutput::new(&output)?;
+  let work = work_dir(&app, "compress_ranges")?;
+  let res = match stage_input(&work, &input) {
+    Ok(in_path) => compress_by_rules(&app, &work, &in_path, &out.path(), &rules).await,
+    Err(e) => Err(e),
+  };
+  let _ = fs::remove_dir_all(&work);
+  let res = res?;
+  out.commit()?;
+  Ok(RangesCompressResult { path: output, ..res })
+}
+
+async fn compress_by_rules(app: &AppHandle, work: &Path, input: &str, output: &str, rules: &[(String, CompressPreset)]) -> Result<RangesCompressResult, String> {
+  let total = page_count(app, input)?;
+  let pages = rule_pages(rules, total)?;
+  let size = |p: &str| fs::metadata(p).map(|m| m.len()).unwrap_or(0);
+  let opts = CompressOptions::default();
+
+  // 第 p 页来自 (第 i 段压缩结果, 段内第 k 页)
+  let mut source = vec![(String::new(), 0u32); total as usize];
+  let mut parts = Vec::with_capacity(rules.len());
+  for (i, ((range, preset), selected)) in rules.iter().zip(&pages).enumerate() {
+    let picked: Vec<(String, u32)> = selected.iter().map(|&p| (input.to_string(), p)).collect();
+    let part = work.join(format!("part_{i}.pdf")).to_string_lossy().to_string();
+    assemble_pages(app, input, &page_runs(&picked), &part)?;
+    let packed = work.join(format!("part_{i}_compressed.pdf")).to_string_lossy().to_string();
+    let c = run_path(app, &part, &packed, preset, &opts).await?;
+    for (k, &p) in selected.iter().enumerate() { source[p as usize - 1] = (packed.clone(), k as u32 + 1); }
+    parts.push(RangePart { range: range.clone(), bytes_before: size(&part), bytes_after: size(&packed), fell_back: c.fell_back });
+  }
+
+  assemble_pages(app, input, &page_runs(&source), output)?;
+  Ok(RangesCompressResult { path: output.to_string(), bytes_before: size(input), bytes_after: size(output), parts })
+}
+
+/// 各规则选中的页（升序）；有页被两条规则选中或没有被任何规则选中时报错
+fn rule_pages(rules: &[(String, CompressPreset)], total: u32) -> Result<Vec<BTreeSet<u32>>, String> {
+  let mut owner: Vec<Option<&str>> = vec![None; total as usize];
+  let mut pages = Vec::with_capacity(rules.len());
+  for (range, _) in rules {
+    let set: BTreeSet<u32> = parse_range(range, total)?.into_iter().collect();
+    if set.is_empty() { return Err(format!("规则 {range} 未选中任何页")); }
+    for &p in &set {
+      if let Some(prev) = owner[p as usize - 1].replace(range) { return Err(format!("第 {p} 页同时出现在规则 {prev} 与 {range} 中")); }
     }
+    pages.push(set);
   }
-  None
+  let missing: Vec<String> = (1..=total).filter(|p| owner[*p as usize - 1].is_none()).map(|p| p.to_string()).collect();
+  if !missing.is_empty() { return Err(format!("以下页未被任何规则覆盖：{}", missing.join(", "))); }
+  Ok(pages)
 }
 
 // ---------- qpdf（无损回退） ----------
 async fn qpdf_lossless(app: &AppHandle, input: &str, output: &str) -> Result<(), String> {
-  let (bin_dir, exe) = find_qpdf(app).ok_or_else(|| "未找到 qpdf：请把 qpdf/bin/qpdf.exe 放到 binaries 目录树".to_string())?;
-  verify_qpdf(&exe, &bin_dir)?;
+  let (bin_dir, exe) = qpdf_exe(app)?;
   let args = vec![
     "--object-streams=generate".into(),
     "--stream-data=compress".into(),
     "--recompress-flate".into(),
-    "--compress-strings=y".into(),
     "--linearize".into(),
     input.into(),
     output.into(),
@@ -171,58 +547,22 @@ async fn qpdf_lossless(app: &AppHandle, input: &str, output: &str) -> Result<(),
   }
 }
 
-// ---------- 共用工具 ----------
-fn find_qpdf(app: &AppHandle) -> Option<(PathBuf, PathBuf)> {
-  let dev_root = PathBuf::from("src-tauri").join("binaries");
-  let res_root = app.path().resolve("binaries", tauri::path::BaseDirectory::Resource).ok();
-
-  for root in [Some(dev_root), res_root].into_iter().flatten() {
-    let direct = [
-      root.join("qpdf").join("bin").join("qpdf.exe"),
-      root.join("qpdf").join("qpdf.exe"),
-      root.join("qpdf.exe"),
-    ];
-    for p in direct {
-      if p.exists() { return Some((p.parent()?.to_path_buf(), p)); }
-    }
-    if let Ok(iter) = fs::read_dir(&root) {
-      for ent in iter.flatten() {
-        let p = ent.path();
-        if p.is_dir() && p.file_name().and_then(OsStr::to_str).unwrap_or("").to_lowercase().contains("qpdf") {
-          let cand = p.join("bin").join("qpdf.exe");
-          if cand.exists() { return Some((cand.parent()?.to_path_buf(), cand)); }
-        }
-      }
-    }
-  }
-  None
-}
-
-fn verify_qpdf(exe: &Path, bin_dir: &Path) -> Result<(), String> {
-  let out = Command::new(exe).arg("--version").current_dir(bin_dir).output()
-    .map_err(|e| format!("qpdf 校验失败：{e}（exe: {}）", exe.display()))?;
-  if !out.status.success() { return Err(format!("qpdf --version 非0：{}", String::from_utf8_lossy(&out.stderr))); }
-  let s = String::from_utf8_lossy(&out.stdout).to_ascii_lowercase();
-  if !s.contains("qpdf") { return Err(format!("检测到的不是 qpdf CLI（stdout: {}）", s.trim())); }
-  Ok(())
-}
-
-fn verify_gs(bin_dir: &Path, exe: &Path, envs: &[(&str, String)]) -> Result<(), String> {
-  let out = run_with_env(bin_dir, exe, &vec!["-v".into()], envs)?;
-  if !out.status.success() {
-    return Err(format!("Ghostscript 启动失败：{}", String::from_utf8_lossy(&out.stderr)));
+// ---------- qpdf（无 Ghostscript 的最大无损压缩） ----------
+// generalized：解开 LZW / RunLength / ASCII 编码并统一重压为 Flate 9 级；DCT/JPX 等有损编码原样保留
+async fn qpdf_max(app: &AppHandle, input: &str, output: &str) -> Result<(), String> {
+  let (bin_dir, exe) = qpdf_exe(app)?;
+  let args = vec![
+    "--object-streams=generate".into(),
+    "--stream-data=compress".into(),
+    "--decode-level=generalized".into(),
+    "--recompress-flate".into(),
+    "--compression-level=9".into(),
+    "--remove-unreferenced-resources=yes".into(),
+    input.into(),
+    output.into(),
+  ];
+  let out = run_with_env(&bin_dir, &exe, &args, &[])?;
+  if out.status.success() { Ok(()) } else {
+    Err(format!("qpdf 失败：{}", String::from_utf8_lossy(&out.stderr)))
   }
-  Ok(())
-}
-
-fn run_with_env(bin_dir: &Path, exe: &Path, args: &[String], extra_env: &[(&str, String)]) -> Result<std::process::Output, String> {
-  let env_path = format!("{};{}", bin_dir.display(), std::env::var("PATH").unwrap_or_default());
-  let mut cmd = Command::new(exe);
-  cmd.args(args).current_dir(bin_dir).env("PATH", env_path);
-  for (k, v) in extra_env { cmd.env(k, v); }
-  cmd.output().map_err(|e| format!("执行失败：{e}（exe: {}）", exe.display()))
-}
-
-fn sanitize(name: &str) -> String {
-  name.chars().map(|c| match c { '/'|'\\'|':'|'*'|'?'|'"'|'<'|'>'|'|' => '_', _ => c }).collect()
 }
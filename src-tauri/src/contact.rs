@@ -0,0 +1,191 @@
+//! Contact sheet — Ghostscript thumbnails laid out cols × rows per A4 sheet with page numbers (printpdf);
+//! sprite sheet — the same thumbnails packed into one image grid for web viewers
+use tauri::AppHandle;
+use image::{imageops::{self, FilterType}, Rgb, RgbImage};
+use printpdf::{
+  BuiltinFont, ColorBits, ColorSpace, Image, ImageFilter, ImageTransform, ImageXObject, Mm, PdfDocument, Px,
+};
+use serde::Serialize;
+use std::{fs, path::Path};
+
+use crate::common::{assert_output_not_same, ensure_parent_dir, stage_input, work_dir, InputOne, PageGuard};
+use crate::naming::resolve_output;
+use crate::render::{render_pages_with, RenderMode};
+
+const SHEET_W_MM: f32 = 210.0;
+const SHEET_H_MM: f32 = 297.0;
+const MARGIN_MM: f32 = 10.0;
+const GAP_MM: f32 = 4.0;
+/// 页码一行的高度
+const LABEL_MM: f32 = 5.0;
+const LABEL_PT: f32 = 8.0;
+/// 缩略图按格子宽度折算的目标清晰度
+const THUMB_DPI: f32 = 150.0;
+const MAX_GRID: u32 = 12;
+/// 雪碧图单格边长与整图边长上限（px；多数图片格式 / 浏览器解码的安全范围）
+const MIN_SPRITE_THUMB: u32 = 16;
+const MAX_SPRITE_THUMB: u32 = 1024;
+const MAX_SPRITE_SIDE: u64 = 16_384;
+
+/// 缩略图总览每张的行列数
+#[derive(Clone, Copy)]
+struct Grid {
+  cols: u32,
+  rows: u32,
+}
+
+/// 雪碧图的列数与单格边长（px）
+#[derive(Clone, Copy)]
+struct SpriteLayout {
+  cols: u32,
+  thumb_px: u32,
+}
+
+#[tauri::command]
+pub async fn contact_sheet(
+  app: AppHandle,
+  input: InputOne,
+  output: String,
+  cols: u32,
+  rows: u32,
+  guard: Option<PageGuard>,
+  render_with_annotations: Option<bool>,
+) -> Result<String, String> {
+  let output = resolve_output(&output, "contact", &[(&input).into()])?;
+  if !(1..=MAX_GRID).contains(&cols) || !(1..=MAX_GRID).contains(&rows) {
+    return Err(format!("行列数需在 1–{MAX_GRID} 之间：{cols}×{rows}"));
+  }
+  ensure_parent_dir(&output)?;
+  let work = work_dir(&app, "contact")?;
+  let res = run_contact(&app, &work, &input, &output, Grid { cols, rows }, guard.unwrap_or_default(), render_with_annotations.unwrap_or(true));
+  let _ = fs::remove_dir_all(&work);
+  res.map(|_| output)
+}
+
+fn run_contact(app: &AppHandle, work: &Path, input: &InputOne, output: &str, Grid { cols, rows }: Grid, guard: PageGuard, annotations: bool) -> Result<(), String> {
+  let in_path = stage_input(work, input)?;
+  guard.check(app, &in_path)?;
+  assert_output_not_same(&in_path, output)?;
+
+  let cell_w = (SHEET_W_MM - 2.0 * MARGIN_MM) / cols as f32;
+  let cell_h = (SHEET_H_MM - 2.0 * MARGIN_MM) / rows as f32;
+  // 以 A4 宽度的原页为基准，按格子占比折算渲染分辨率
+  let dpi = (THUMB_DPI * cell_w / SHEET_W_MM).clamp(20.0, THUMB_DPI).round();
+  let thumbs = render_pages_with(app, &in_path, &work.join("thumbs"), dpi as u32, RenderMode::Jpeg, None, annotations)?;
+  if thumbs.is_empty() { return Err("没有可渲染的页面".into()); }
+
+  let (doc, first_page, first_layer) = PdfDocument::new("contact sheet", Mm(SHEET_W_MM), Mm(SHEET_H_MM), "Layer 1");
+  let font = doc.add_builtin_font(BuiltinFont::Helvetica).map_err(|e| format!("加载字体失败：{e}"))?;
+  let per_sheet = (cols * rows) as usize;
+  let mut layer = doc.get_page(first_page).get_layer(first_layer);
+
+  for (i, thumb) in thumbs.iter().enumerate() {
+    if i > 0 && i % per_sheet == 0 {
+      let (page, l) = doc.add_page(Mm(SHEET_W_MM), Mm(SHEET_H_MM), "Layer 1");
+      layer = doc.get_page(page).get_layer(l);
+    }
+    let pos = (i % per_sheet) as u32;
+    let (col, row) = (pos % cols, pos / cols);
+    let left = MARGIN_MM + col as f32 * cell_w;
+    let bottom = SHEET_H_MM - MARGIN_MM - (row + 1) as f32 * cell_h;
+
+    let jpeg = fs::read(thumb).map_err(|e| format!("读取缩略图失败：{e}"))?;
+    let (px_w, px_h) = image::image_dimensions(thumb).map_err(|e| format!("读取缩略图尺寸失败：{e}"))?;
+    let (nat_w, nat_h) = (px_w as f32 / dpi * 25.4, px_h as f32 / dpi * 25.4);
+    let (box_w, box_h) = (cell_w - GAP_MM, cell_h - GAP_MM - LABEL_MM);
+    let scale = (box_w / nat_w).min(box_h / nat_h);
+    let (img_w, img_h) = (nat_w * scale, nat_h * scale);
+
+    let xobj = ImageXObject {
+      width: Px(px_w as usize),
+      height: Px(px_h as usize),
+      color_space: ColorSpace::Rgb,
+      bits_per_component: ColorBits::Bit8,
+      interpolate: true,
+      image_data: jpeg,
+      image_filter: Some(ImageFilter::DCT),
+      smask: None,
+      clipping_bbox: None,
+    };
+    Image::from(xobj).add_to_layer(layer.clone(), ImageTransform {
+      translate_x: Some(Mm(left + (cell_w - img_w) / 2.0)),
+      translate_y: Some(Mm(bottom + LABEL_MM + (box_h - img_h) / 2.0 + GAP_MM / 2.0)),
+      scale_x: Some(scale),
+      scale_y: Some(scale),
+      dpi: Some(dpi),
+      ..Default::default()
+    });
+
+    // Helvetica 数字宽约 0.556em，按此居中
+    let label = (i + 1).to_string();
+    let label_w = label.len() as f32 * 0.556 * LABEL_PT * 25.4 / 72.0;
+    layer.use_text(label, LABEL_PT, Mm(left + (cell_w - label_w) / 2.0), Mm(bottom + 1.0), &font);
+  }
+
+  let bytes = doc.save_to_bytes().map_err(|e| format!("生成缩略图总览失败：{e}"))?;
+  fs::write(output, bytes).map_err(|e| format!("写出失败：{e}（{output}）"))
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpriteMeta {
+  pub cols: u32,
+  pub rows: u32,
+  pub thumb_px: u32,
+  pub count: u32,
+}
+
+/// 每页缩放进 thumb_px × thumb_px 的白底方格（等比居中），按行优先排成 cols 列；
+/// 第 i 页（0 起）偏移为 ((i % cols) × thumb_px, (i / cols) × thumb_px)。格式按 out_image 扩展名（png / jpg）
+#[tauri::command]
+pub async fn sprite_sheet(
+  app: AppHandle,
+  input: InputOne,
+  out_image: String,
+  cols: u32,
+  thumb_px: u32,
+  guard: Option<PageGuard>,
+  render_with_annotations: Option<bool>,
+) -> Result<SpriteMeta, String> {
+  if cols == 0 { return Err("列数至少为 1".into()); }
+  if !(MIN_SPRITE_THUMB..=MAX_SPRITE_THUMB).contains(&thumb_px) {
+    return Err(format!("缩略图边长需在 {MIN_SPRITE_THUMB}–{MAX_SPRITE_THUMB} px 之间：{thumb_px}"));
+  }
+  ensure_parent_dir(&out_image)?;
+  let work = work_dir(&app, "sprite")?;
+  let res = run_sprite(&app, &work, &input, &out_image, SpriteLayout { cols, thumb_px }, guard.unwrap_or_default(), render_with_annotations.unwrap_or(true));
+  let _ = fs::remove_dir_all(&work);
+  res
+}
+
+fn run_sprite(app: &AppHandle, work: &Path, input: &InputOne, out_image: &str, SpriteLayout { cols, thumb_px }: SpriteLayout, guard: PageGuard, annotations: bool) -> Result<SpriteMeta, String> {
+  let in_path = stage_input(work, input)?;
+  // 先按页数算出整图尺寸，超限时不必渲染
+  let count = guard.check(app, &in_path)?;
+  if count == 0 { return Err("没有可渲染的页面".into()); }
+  let cols = cols.min(count);
+  let rows = count.div_ceil(cols);
+  let (w, h) = (cols as u64 * thumb_px as u64, rows as u64 * thumb_px as u64);
+  if w > MAX_SPRITE_SIDE || h > MAX_SPRITE_SIDE {
+    return Err(format!("雪碧图尺寸 {w}×{h} px 超出上限 {MAX_SPRITE_SIDE} px，请减小缩略图或调整列数"));
+  }
+
+  // A4 宽度（8.27 英寸）正好铺满一格的分辨率，其余尺寸再缩放
+  let dpi = (thumb_px as f32 / 8.27).ceil().clamp(10.0, 300.0) as u32;
+  let pages = render_pages_with(app, &in_path, &work.join("thumbs"), dpi, RenderMode::Rgb, None, annotations)?;
+  if pages.len() as u32 != count { return Err(format!("渲染页数与文档页数不符：{} / {count}", pages.len())); }
+
+  let mut sheet = RgbImage::from_pixel(w as u32, h as u32, Rgb([255, 255, 255]));
+  for (i, page) in pages.iter().enumerate() {
+    let img = image::open(page).map_err(|e| format!("读取缩略图失败：{e}"))?.to_rgb8();
+    let scale = (thumb_px as f32 / img.width() as f32).min(thumb_px as f32 / img.height() as f32);
+    let (tw, th) = (((img.width() as f32 * scale).round() as u32).max(1), ((img.height() as f32 * scale).round() as u32).max(1));
+    let thumb = imageops::resize(&img, tw, th, FilterType::Triangle);
+    let (col, row) = (i as u32 % cols, i as u32 / cols);
+    let x = col * thumb_px + (thumb_px - tw.min(thumb_px)) / 2;
+    let y = row * thumb_px + (thumb_px - th.min(thumb_px)) / 2;
+    imageops::replace(&mut sheet, &thumb, x as i64, y as i64);
+  }
+  sheet.save(out_image).map_err(|e| format!("写出雪碧图失败：{e}（{out_image}）"))?;
+  Ok(SpriteMeta { cols, rows, thumb_px, count })
+}
@@ -0,0 +1,91 @@
+//! Cover page — one-page A4 title page (title / subtitle / date, centered) generated with printpdf
+use tauri::AppHandle;
+use printpdf::{BuiltinFont, IndirectFontRef, Mm, PdfDocument};
+use serde::Deserialize;
+use std::{fs, io::Cursor};
+
+use crate::common::find_bundled_font;
+use crate::stamp::helvetica_width;
+
+const PAGE_W_MM: f32 = 210.0;
+const PAGE_H_MM: f32 = 297.0;
+/// 文字可用宽度，超出时缩小字号
+const TEXT_W_MM: f32 = 170.0;
+const TITLE_PT: f32 = 28.0;
+const SUBTITLE_PT: f32 = 16.0;
+const DATE_PT: f32 = 12.0;
+/// 标题基线距页面底部的比例（略高于中线）
+const TITLE_BASELINE: f32 = 0.6;
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverSpec {
+  pub title: String,
+  pub subtitle: Option<String>,
+  /// 原样显示的日期文字，如 "2025-09-07"
+  pub date: Option<String>,
+}
+
+/// 文字宽度（em）
+enum Measure {
+  Ttf(Vec<u8>),
+  Helvetica,
+}
+
+impl Measure {
+  fn em_width(&self, s: &str) -> f32 {
+    match self {
+      Measure::Helvetica => helvetica_width(s),
+      Measure::Ttf(data) => {
+        let Ok(face) = ttf_parser::Face::parse(data, 0) else { return helvetica_width(s) };
+        let upem = face.units_per_em() as f32;
+        s.chars()
+          .filter_map(|c| face.glyph_index(c))
+          .filter_map(|g| face.glyph_hor_advance(g))
+          .map(|a| a as f32 / upem)
+          .sum()
+      }
+    }
+  }
+}
+
+/// 生成封面到 output；有 binaries/fonts/ 下的字体时用它（可排中文），否则退回 Helvetica（仅 ASCII）
+pub(crate) fn render_cover(app: &AppHandle, spec: &CoverSpec, output: &str) -> Result<(), String> {
+  let title = spec.title.trim();
+  if title.is_empty() { return Err("封面标题不能为空".into()); }
+  let lines: Vec<(&str, f32)> = [Some(title), spec.subtitle.as_deref(), spec.date.as_deref()]
+    .into_iter()
+    .zip([TITLE_PT, SUBTITLE_PT, DATE_PT])
+    .filter_map(|(t, pt)| t.map(str::trim).filter(|t| !t.is_empty()).map(|t| (t, pt)))
+    .collect();
+
+  let (doc, page, layer) = PdfDocument::new(title, Mm(PAGE_W_MM), Mm(PAGE_H_MM), "Layer 1");
+  let (font, measure): (IndirectFontRef, Measure) = match find_bundled_font(app) {
+    Some(path) => {
+      let data = fs::read(&path).map_err(|e| format!("读取字体失败：{e}（{}）", path.display()))?;
+      let font = doc.add_external_font(Cursor::new(data.clone())).map_err(|e| format!("加载字体失败：{e}"))?;
+      (font, Measure::Ttf(data))
+    }
+    None => {
+      if lines.iter().any(|(t, _)| !t.is_ascii()) {
+        return Err("封面含非 ASCII 文字：请在 binaries/fonts/ 放置 TTF/OTF 字体".into());
+      }
+      let font = doc.add_builtin_font(BuiltinFont::Helvetica).map_err(|e| format!("加载字体失败：{e}"))?;
+      (font, Measure::Helvetica)
+    }
+  };
+
+  let layer = doc.get_page(page).get_layer(layer);
+  let mut baseline = PAGE_H_MM * TITLE_BASELINE;
+  for (text, pt) in lines {
+    let em = measure.em_width(text).max(f32::EPSILON);
+    // pt → mm：1pt = 25.4/72 mm
+    let pt = pt.min(TEXT_W_MM / (em * 25.4 / 72.0));
+    let width_mm = em * pt * 25.4 / 72.0;
+    layer.use_text(text, pt, Mm((PAGE_W_MM - width_mm) / 2.0), Mm(baseline), &font);
+    baseline -= pt * 25.4 / 72.0 * 1.8;
+  }
+
+  let bytes = doc.save_to_bytes().map_err(|e| format!("生成封面失败：{e}"))?;
+  fs::write(output, bytes).map_err(|e| format!("写出封面失败：{e}（{output}）"))
+}
@@ -0,0 +1,104 @@
+//! Diagnose — qpdf --check output → structured findings (recoverable warnings vs. fatal errors)
+use tauri::AppHandle;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::fs;
+
+use crate::common::{qpdf_exe, run_with_env, stage_input, work_dir, InputOne};
+
+/// qpdf 报告的位置："(object 12 0, offset 3456): " 或 "(offset 3456): "
+static LOCATION: Lazy<Regex> = Lazy::new(|| Regex::new(r"\((?:object (\d+ \d+), )?offset (\d+)\): ").unwrap());
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum FindingKind { Xref, Trailer, MissingEof, DamagedObject, Stream, Encryption, Other }
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Severity {
+  /// qpdf 能自动修复（警告，退出码 3）
+  Recoverable,
+  /// qpdf 无法继续（错误，退出码 2）
+  Fatal,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Finding {
+  pub kind: FindingKind,
+  pub severity: Severity,
+  /// 对象号，如 "12 0"
+  pub object: Option<String>,
+  pub offset: Option<u64>,
+  /// qpdf 原始输出行
+  pub line: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnosis {
+  /// 没有任何警告或错误
+  pub ok: bool,
+  pub fatal: bool,
+  pub pdf_version: Option<String>,
+  pub encrypted: Option<bool>,
+  pub linearized: Option<bool>,
+  pub findings: Vec<Finding>,
+}
+
+#[tauri::command]
+pub async fn diagnose(app: AppHandle, input: InputOne) -> Result<Diagnosis, String> {
+  let work = work_dir(&app, "diagnose")?;
+  let res = stage_input(&work, &input).and_then(|p| run_check(&app, &p));
+  let _ = fs::remove_dir_all(&work);
+  res
+}
+
+fn run_check(app: &AppHandle, path: &str) -> Result<Diagnosis, String> {
+  let (bin_dir, exe) = qpdf_exe(app)?;
+  let out = run_with_env(&bin_dir, &exe, &["--check".into(), path.into()], &[])?;
+  let stdout = String::from_utf8_lossy(&out.stdout);
+  let stderr = String::from_utf8_lossy(&out.stderr);
+  let fatal = out.status.code() == Some(2);
+  if !matches!(out.status.code(), Some(0) | Some(2) | Some(3)) {
+    return Err(format!("qpdf --check 异常退出：{stderr}"));
+  }
+
+  let mut findings = Vec::new();
+  // 警告和错误都在 stderr；部分结构错误也会出现在 stdout 的 "WARNING:" 行。
+  // 退出码 3 时 qpdf 总会在最后补一行 "operation succeeded with warnings"，不算发现的问题
+  let lines = stderr.lines().chain(stdout.lines().filter(|l| l.starts_with("WARNING:"))).map(str::trim);
+  for line in lines.filter(|l| !l.is_empty() && !l.contains("operation succeeded with warnings")) {
+    // 只有退出码 2 时非 WARNING 行才是无法恢复的错误
+    let severity = if fatal && !line.starts_with("WARNING:") { Severity::Fatal } else { Severity::Recoverable };
+    let caps = LOCATION.captures(line);
+    findings.push(Finding {
+      kind: classify(line),
+      severity,
+      object: caps.as_ref().and_then(|c| c.get(1)).map(|m| m.as_str().to_string()),
+      offset: caps.as_ref().and_then(|c| c.get(2)).and_then(|m| m.as_str().parse().ok()),
+      line: line.to_string(),
+    });
+  }
+  // 退出码 2 但 stderr 没有非 WARNING 行时补一条
+  if fatal && !findings.iter().any(|f| f.severity == Severity::Fatal) {
+    findings.push(Finding { kind: FindingKind::Other, severity: Severity::Fatal, object: None, offset: None, line: "qpdf 报告了无法恢复的错误".into() });
+  }
+
+  let pdf_version = stdout.lines().find_map(|l| l.strip_prefix("PDF Version: ")).map(|v| v.trim().to_string());
+  let encrypted = if stdout.contains("File is not encrypted") { Some(false) } else if stdout.contains("R = ") { Some(true) } else { None };
+  let linearized = if stdout.contains("File is not linearized") { Some(false) } else if stdout.contains("File is linearized") { Some(true) } else { None };
+  Ok(Diagnosis { ok: findings.is_empty(), fatal, pdf_version, encrypted, linearized, findings })
+}
+
+fn classify(line: &str) -> FindingKind {
+  let l = line.to_ascii_lowercase();
+  if l.contains("password") || l.contains("encrypt") { FindingKind::Encryption }
+  else if l.contains("eof") || l.contains("end of file") { FindingKind::MissingEof }
+  else if l.contains("xref") || l.contains("cross-reference") { FindingKind::Xref }
+  else if l.contains("trailer") { FindingKind::Trailer }
+  else if l.contains("stream") || l.contains("decod") || l.contains("filter") { FindingKind::Stream }
+  else if l.contains("object") { FindingKind::DamagedObject }
+  else { FindingKind::Other }
+}
@@ -0,0 +1,83 @@
+//! Fingerprint — SHA-256 of the page content streams, stored in a custom XMP namespace so a file can be recognised regardless of name or metadata
+use lopdf::{dictionary, Document, Object, Stream};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+
+use crate::common::{assert_input_not_output, catalog_id, hex_sha256, load_pdf, save_pdf, InputOne};
+
+const NS_URI: &str = "http://ns.lumen.pdfstudio/fingerprint/1.0/";
+static FINGERPRINT_TAG: Lazy<Regex> = Lazy::new(|| Regex::new(r"<lumen:Fingerprint>\s*([0-9a-fA-F]{64})\s*</lumen:Fingerprint>").unwrap());
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FingerprintInfo {
+  /// XMP 中记录的指纹；未写入过为 None
+  pub stored: Option<String>,
+  /// 按当前内容重新计算的指纹
+  pub current: String,
+  /// stored 存在且与 current 一致
+  pub matches: bool,
+}
+
+/// 计算内容指纹写入 XMP（lumen:Fingerprint），已有 XMP 时原位更新，返回指纹
+#[tauri::command]
+pub async fn embed_fingerprint(input: InputOne, output: String) -> Result<String, String> {
+  assert_input_not_output(&input, &output)?;
+  let mut doc = load_pdf(&input)?;
+  let fp = content_fingerprint(&doc)?;
+  let xmp = match read_xmp(&doc) {
+    Some(x) if FINGERPRINT_TAG.is_match(&x) => FINGERPRINT_TAG.replace(&x, format!("<lumen:Fingerprint>{fp}</lumen:Fingerprint>").as_str()).into_owned(),
+    Some(x) if x.contains("</rdf:RDF>") => x.replacen("</rdf:RDF>", &format!("{}</rdf:RDF>", description(&fp)), 1),
+    _ => packet(&fp),
+  };
+  let meta = doc.add_object(Stream::new(dictionary! { "Type" => "Metadata", "Subtype" => "XML" }, xmp.into_bytes()));
+  let cat = catalog_id(&doc)?;
+  match doc.get_object_mut(cat) {
+    Ok(Object::Dictionary(c)) => c.set("Metadata", meta),
+    _ => return Err("文档目录不是字典".into()),
+  }
+  doc.prune_objects(); // 丢掉被替换的旧 XMP 流
+  save_pdf(&mut doc, &output)?;
+  Ok(fp)
+}
+
+#[tauri::command]
+pub async fn read_fingerprint(input: InputOne) -> Result<FingerprintInfo, String> {
+  let doc = load_pdf(&input)?;
+  let current = content_fingerprint(&doc)?;
+  let stored = read_xmp(&doc).and_then(|x| FINGERPRINT_TAG.captures(&x).map(|c| c[1].to_ascii_lowercase()));
+  Ok(FingerprintInfo { matches: stored.as_deref() == Some(current.as_str()), stored, current })
+}
+
+/// 按页序拼接各页解码后的内容流（页间以页号分隔）再取 SHA-256；与元数据、文件结构、对象编号无关
+fn content_fingerprint(doc: &Document) -> Result<String, String> {
+  let mut data = Vec::new();
+  for (no, page_id) in doc.get_pages() {
+    data.extend_from_slice(format!("\n%page {no}\n").as_bytes());
+    data.extend(doc.get_page_content(page_id).map_err(|e| format!("读取第 {no} 页内容失败：{e}"))?);
+  }
+  Ok(hex_sha256(&data))
+}
+
+/// Catalog /Metadata 的 XMP 文本（压缩的先解压）
+fn read_xmp(doc: &Document) -> Option<String> {
+  let id = doc.catalog().ok()?.get(b"Metadata").ok()?.as_reference().ok()?;
+  let Ok(Object::Stream(s)) = doc.get_object(id) else { return None };
+  let bytes = s.decompressed_content().unwrap_or_else(|_| s.content.clone());
+  Some(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+fn description(fp: &str) -> String {
+  format!(r#"<rdf:Description rdf:about="" xmlns:lumen="{NS_URI}"><lumen:Fingerprint>{fp}</lumen:Fingerprint></rdf:Description>"#)
+}
+
+/// 文档原本没有 XMP 时的最小数据包
+fn packet(fp: &str) -> String {
+  format!(
+    "<?xpacket begin=\"\u{feff}\" id=\"W5M0MpCehiHzreSzNTczkc9d\"?>\n\
+     <x:xmpmeta xmlns:x=\"adobe:ns:meta/\"><rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\">{}</rdf:RDF></x:xmpmeta>\n\
+     <?xpacket end=\"w\"?>",
+    description(fp)
+  )
+}
@@ -0,0 +1,99 @@
+//! Flatten to images — Ghostscript renders every page, printpdf rebuilds one JPEG per page (no text / vector content left)
+use tauri::AppHandle;
+use printpdf::{ColorBits, ColorSpace, Image, ImageFilter, ImageTransform, ImageXObject, Mm, PdfDocument, Px};
+use serde::Serialize;
+use std::{
+  fs,
+  path::{Path, PathBuf},
+};
+
+use crate::common::{assert_output_not_same, ensure_parent_dir, stage_input, work_dir, InputOne, PageGuard};
+use crate::naming::resolve_output;
+use crate::render::{render_pages_with, RenderMode};
+
+const MIN_DPI: u32 = 72;
+const MAX_DPI: u32 = 600;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlattenResult {
+  pub path: String,
+  pub pages: u32,
+  pub bytes_before: u64,
+  pub bytes_after: u64,
+  /// 给界面展示的提示：不可检索 / 体积变大
+  pub warnings: Vec<String>,
+}
+
+/// 每页渲染成图片后重建 PDF：文字不可选、不可检索，链接 / 书签 / 表单全部丢失，体积通常明显变大
+#[tauri::command]
+pub async fn flatten_to_images(
+  app: AppHandle,
+  input: InputOne,
+  output: String,
+  dpi: u32,
+  guard: Option<PageGuard>,
+  render_with_annotations: Option<bool>,
+) -> Result<FlattenResult, String> {
+  if !(MIN_DPI..=MAX_DPI).contains(&dpi) { return Err(format!("分辨率需在 {MIN_DPI}–{MAX_DPI} dpi 之间：{dpi}")); }
+  let output = resolve_output(&output, "flatten", &[(&input).into()])?;
+  ensure_parent_dir(&output)?;
+  let work = work_dir(&app, "flatten")?;
+  let res = run_flatten(&app, &work, &input, &output, dpi, guard.unwrap_or_default(), render_with_annotations.unwrap_or(true));
+  let _ = fs::remove_dir_all(&work);
+  res
+}
+
+fn run_flatten(app: &AppHandle, work: &Path, input: &InputOne, output: &str, dpi: u32, guard: PageGuard, annotations: bool) -> Result<FlattenResult, String> {
+  let in_path = stage_input(work, input)?;
+  guard.check(app, &in_path)?;
+  assert_output_not_same(&in_path, output)?;
+  let pages = render_pages_with(app, &in_path, &work.join("pages"), dpi, RenderMode::Jpeg, None, annotations)?;
+  if pages.is_empty() { return Err("没有可渲染的页面".into()); }
+  jpegs_to_pdf(&pages, dpi as f32, "flattened", output)?;
+
+  let size = |p: &str| fs::metadata(p).map(|m| m.len()).unwrap_or(0);
+  let (bytes_before, bytes_after) = (size(&in_path), size(output));
+  let mut warnings = vec!["输出为纯图片：文字无法选中或检索，链接、书签与表单均已移除".to_string()];
+  if bytes_after > bytes_before {
+    warnings.push(format!("体积由 {bytes_before} 字节增至 {bytes_after} 字节"));
+  }
+  Ok(FlattenResult { path: output.to_string(), pages: pages.len() as u32, bytes_before, bytes_after, warnings })
+}
+
+/// 每张 JPEG 一页，页面尺寸按 dpi 由像素折算（与渲染分辨率一致即还原原页尺寸）
+pub(crate) fn jpegs_to_pdf(jpegs: &[PathBuf], dpi: f32, title: &str, output: &str) -> Result<(), String> {
+  let mm = |px: u32| px as f32 / dpi * 25.4;
+  let mut doc = None;
+  for jpeg in jpegs {
+    let (w, h) = image::image_dimensions(jpeg).map_err(|e| format!("读取图像尺寸失败：{e}（{}）", jpeg.display()))?;
+    let data = fs::read(jpeg).map_err(|e| format!("读取图像失败：{e}"))?;
+    let layer = match &doc {
+      None => {
+        let (d, page, layer) = PdfDocument::new(title, Mm(mm(w)), Mm(mm(h)), "Layer 1");
+        let layer = d.get_page(page).get_layer(layer);
+        doc = Some(d);
+        layer
+      }
+      Some(d) => {
+        let (page, layer) = d.add_page(Mm(mm(w)), Mm(mm(h)), "Layer 1");
+        d.get_page(page).get_layer(layer)
+      }
+    };
+    let xobj = ImageXObject {
+      width: Px(w as usize),
+      height: Px(h as usize),
+      color_space: ColorSpace::Rgb,
+      bits_per_component: ColorBits::Bit8,
+      interpolate: false,
+      image_data: data,
+      image_filter: Some(ImageFilter::DCT),
+      smask: None,
+      clipping_bbox: None,
+    };
+    Image::from(xobj).add_to_layer(layer, ImageTransform { dpi: Some(dpi), ..Default::default() });
+  }
+  let doc = doc.ok_or("没有图像")?;
+  let bytes = doc.save_to_bytes().map_err(|e| format!("生成 PDF 失败：{e}"))?;
+  fs::write(output, bytes).map_err(|e| format!("写出失败：{e}（{output}）"))
+}
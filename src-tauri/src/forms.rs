@@ -0,0 +1,537 @@
+//! Forms — AcroForm field listing (text / checkbox / radio / choice) and filling / flattening via lopdf
+use lopdf::{dictionary, Dictionary, Document, Object, ObjectId, Stream};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::common::{assert_input_not_output, catalog_id, decode_text, encode_text, load_pdf, save_pdf, InputOne};
+use crate::inspect::{matrix, resolve, IDENTITY};
+use crate::naming::resolve_output;
+use crate::stamp::{add_resource, append_page_content, helvetica_width, import_form, pdf_string};
+
+/// Ff 标志位
+const FF_MULTILINE: i64 = 1 << 12;
+const FF_RADIO: i64 = 1 << 15;
+const FF_PUSHBUTTON: i64 = 1 << 16;
+const FF_COMBO: i64 = 1 << 17;
+const FF_EDIT: i64 = 1 << 18;
+/// 注释 /F：隐藏
+const ANNOT_HIDDEN: i64 = 1 << 1;
+/// 字段与 AcroForm 都没有 /DA 时的默认外观
+const DEFAULT_DA: &str = "/Helv 0 Tf 0 g";
+/// 字段树最大深度（防止异常文档的环）
+const MAX_DEPTH: usize = 32;
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum FieldKind { Text, Checkbox, Radio, Choice, PushButton, Signature }
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormField {
+  /// 完整名称（各级 /T 以 "." 连接），如 "applicant.name"
+  pub name: String,
+  pub kind: FieldKind,
+  /// 当前值：文本 / 选项为字符串；复选 / 单选为选中状态名（如 "Yes"、"Off"）；多选以 ", " 连接
+  pub value: Option<String>,
+  /// 选项：下拉 / 列表为导出值；复选 / 单选为各控件的选中状态名
+  pub options: Vec<String>,
+}
+
+/// 终端字段（带值的那一级）及其控件注释
+pub(crate) struct FieldNode {
+  pub id: ObjectId,
+  pub name: String,
+  pub kind: FieldKind,
+  pub flags: i64,
+  pub value: Option<String>,
+  pub options: Vec<String>,
+  /// 控件注释：字段与控件合一时即字段本身
+  pub widgets: Vec<ObjectId>,
+  /// 默认外观（可继承），如 "/Helv 10 Tf 0 g"
+  pub da: Option<String>,
+  /// 对齐：0 左 / 1 中 / 2 右
+  pub quadding: i64,
+}
+
+#[tauri::command]
+pub async fn list_form_fields(input: InputOne) -> Result<Vec<FormField>, String> {
+  let doc = load_pdf(&input)?;
+  Ok(collect_fields(&doc).into_iter().map(|f| FormField { name: f.name, kind: f.kind, value: f.value, options: f.options }).collect())
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RejectedValue {
+  pub name: String,
+  pub reason: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FillReport {
+  pub path: String,
+  /// 成功写入的字段（按名称排序）
+  pub filled: Vec<String>,
+  /// 文档中不存在的字段名
+  pub unmatched: Vec<String>,
+  /// 字段存在但值不可用（选项不存在、按钮 / 签名字段等）
+  pub rejected: Vec<RejectedValue>,
+  /// 有字段没能生成外观、改由阅读器按 NeedAppearances 重绘（非 ASCII 文本、列表框）
+  pub needs_appearances: bool,
+}
+
+/// 按完整字段名写值。文本 / 下拉框重新生成外观流（Helvetica 近似排版，仅 ASCII）；
+/// 复选 / 单选切换 /AS 到对应状态，值可为状态名，或 "true"/"false" 等表示选中首个状态 / Off。
+/// flatten 时把所有控件的当前外观画进页面内容并删除 AcroForm，此时非 ASCII 文本值会被拒绝
+#[tauri::command]
+pub async fn fill_form(input: InputOne, output: String, values: HashMap<String, String>, flatten: bool) -> Result<FillReport, String> {
+  let output = resolve_output(&output, "fill_form", &[(&input).into()])?;
+  assert_input_not_output(&input, &output)?;
+  let mut doc = load_pdf(&input)?;
+  if doc.is_encrypted() { return Err("加密文档请先解密再填写表单".into()); }
+  let fields = collect_fields(&doc);
+  if fields.is_empty() { return Err("文档没有表单字段".into()); }
+  let by_name: HashMap<&str, &FieldNode> = fields.iter().map(|f| (f.name.as_str(), f)).collect();
+
+  let mut report = FillReport { path: output.clone(), filled: Vec::new(), unmatched: Vec::new(), rejected: Vec::new(), needs_appearances: false };
+  let sorted: BTreeMap<&String, &String> = values.iter().collect();
+  for (name, value) in sorted {
+    let Some(field) = by_name.get(name.as_str()) else { report.unmatched.push(name.clone()); continue };
+    match set_field(&mut doc, field, value, flatten) {
+      Ok(drawn) => {
+        report.needs_appearances |= !drawn;
+        report.filled.push(name.clone());
+      }
+      Err(reason) => report.rejected.push(RejectedValue { name: name.clone(), reason }),
+    }
+  }
+
+  if flatten {
+    flatten_fields(&mut doc, &fields)?;
+  } else if report.needs_appearances {
+    if let Some(form) = acroform_mut(&mut doc)? { form.set("NeedAppearances", true); }
+  }
+  save_pdf(&mut doc, &output)?;
+  Ok(report)
+}
+
+/// 写入一个字段；返回是否已生成外观
+fn set_field(doc: &mut Document, field: &FieldNode, value: &str, flatten: bool) -> Result<bool, String> {
+  match field.kind {
+    FieldKind::Text | FieldKind::Choice => {
+      if field.kind == FieldKind::Choice && field.flags & FF_EDIT == 0 && !field.options.is_empty() && !field.options.iter().any(|o| o == value) {
+        return Err(format!("不是可选项（可选：{}）", field.options.join(", ")));
+      }
+      let drawable = value.is_ascii() && (field.kind == FieldKind::Text || field.flags & FF_COMBO != 0);
+      if flatten && !drawable { return Err("无法为该值生成外观（非 ASCII 或列表框），不能展平".into()); }
+      let field_dict = dict_mut(doc, field.id)?;
+      field_dict.set("V", encode_text(value));
+      field_dict.remove(b"I");
+      if !drawable { return Ok(false); }
+      for &w in &field.widgets { draw_text_appearance(doc, field, w, value)?; }
+      Ok(true)
+    }
+    FieldKind::Checkbox | FieldKind::Radio => {
+      let state = match value.to_ascii_lowercase().as_str() {
+        "off" | "false" | "no" | "0" | "" => "Off".to_string(),
+        "on" | "true" | "yes" | "1" if !field.options.iter().any(|o| o == value) => {
+          field.options.first().cloned().ok_or("控件没有选中状态外观")?
+        }
+        _ if field.options.iter().any(|o| o == value) => value.to_string(),
+        _ => return Err(format!("不是可选状态（可选：Off, {}）", field.options.join(", "))),
+      };
+      dict_mut(doc, field.id)?.set("V", Object::Name(state.clone().into_bytes()));
+      for &w in &field.widgets {
+        // 单选组各控件只有自己的选中状态，其余置 Off
+        let shown = if on_states(doc, &[w]).contains(&state) { state.as_str() } else { "Off" };
+        dict_mut(doc, w)?.set("AS", Object::Name(shown.as_bytes().to_vec()));
+      }
+      Ok(true)
+    }
+    FieldKind::PushButton => Err("按钮字段没有值".into()),
+    FieldKind::Signature => Err("签名字段不能直接写值".into()),
+  }
+}
+
+fn dict_mut(doc: &mut Document, id: ObjectId) -> Result<&mut Dictionary, String> {
+  doc.get_object_mut(id).ok().and_then(|o| o.as_dict_mut().ok()).ok_or_else(|| format!("对象 {} {} 不是字典", id.0, id.1))
+}
+
+fn widget_rect(doc: &Document, widget: ObjectId) -> Option<[f32; 4]> {
+  let r = doc.get_dictionary(widget).ok()?.get(b"Rect").ok()?;
+  let v: Vec<f32> = resolve(doc, r).as_array().ok()?.iter().filter_map(|o| resolve(doc, o).as_float().ok()).collect();
+  let [a, b, c, d] = v[..] else { return None };
+  Some([a.min(c), b.min(d), a.max(c), b.max(d)])
+}
+
+/// 按 /DA 的字体与颜色重画文本外观；字号 0 为自动（按控件高度）
+fn draw_text_appearance(doc: &mut Document, field: &FieldNode, widget: ObjectId, value: &str) -> Result<(), String> {
+  let Some([x0, y0, x1, y1]) = widget_rect(doc, widget) else { return Ok(()) };
+  let (w, h) = (x1 - x0, y1 - y0);
+  let da = field.da.clone().unwrap_or_else(|| DEFAULT_DA.into());
+  let mut tokens: Vec<String> = da.split_whitespace().map(String::from).collect();
+  let tf = tokens.iter().position(|t| t == "Tf").filter(|&i| i >= 2);
+  let font = tf.map(|i| tokens[i - 2].trim_start_matches('/').to_string()).unwrap_or_else(|| "Helv".into());
+  let multiline = field.flags & FF_MULTILINE != 0;
+  let lines: Vec<&str> = if multiline { value.lines().collect() } else { vec![value] };
+  let mut size = tf.and_then(|i| tokens[i - 1].parse::<f32>().ok()).unwrap_or(0.0);
+  if size <= 0.0 { size = if multiline { 10.0 } else { ((h - 4.0) * 0.75).clamp(4.0, 12.0) }; }
+  match tf {
+    Some(i) => tokens[i - 1] = size.to_string(),
+    None => tokens.extend([format!("/{font}"), size.to_string(), "Tf".into()]),
+  }
+  let da = tokens.join(" ");
+
+  let mut ops = format!("/Tx BMC\nq 1 1 {} {} re W n\nBT {da}\n", w - 2.0, h - 2.0);
+  let leading = size * 1.15;
+  for (i, line) in lines.iter().enumerate() {
+    let tw = helvetica_width(line) * size;
+    let x = match field.quadding { 1 => (w - tw) / 2.0, 2 => w - 2.0 - tw, _ => 2.0 };
+    let y = if multiline { h - 2.0 - size - i as f32 * leading } else { (h - size) / 2.0 + 0.22 * size };
+    ops.push_str(&format!("1 0 0 1 {x} {y} Tm ({}) Tj\n", pdf_string(line)));
+  }
+  ops.push_str("ET\nQ\nEMC\n");
+
+  let font_ref = form_font(doc, &font);
+  let mut fonts = Dictionary::new();
+  fonts.set(font.as_str(), font_ref);
+  let stream = Stream::new(dictionary! {
+    "Type" => "XObject",
+    "Subtype" => "Form",
+    "BBox" => [0.0, 0.0, w, h].iter().map(|v| Object::Real(*v)).collect::<Vec<_>>(),
+    "Resources" => dictionary! { "Font" => fonts },
+  }, ops.into_bytes());
+  let ap = doc.add_object(stream);
+  dict_mut(doc, widget)?.set("AP", dictionary! { "N" => ap });
+  Ok(())
+}
+
+/// AcroForm /DR 里的同名字体；没有时新建 Helvetica
+fn form_font(doc: &mut Document, name: &str) -> Object {
+  let existing = acroform(doc)
+    .and_then(|f| f.get(b"DR").ok())
+    .and_then(|o| resolve(doc, o).as_dict().ok())
+    .and_then(|dr| dr.get(b"Font").ok())
+    .and_then(|o| resolve(doc, o).as_dict().ok())
+    .and_then(|fonts| fonts.get(name.as_bytes()).ok())
+    .cloned();
+  existing.unwrap_or_else(|| {
+    Object::Reference(doc.add_object(dictionary! { "Type" => "Font", "Subtype" => "Type1", "BaseFont" => "Helvetica", "Encoding" => "WinAnsiEncoding" }))
+  })
+}
+
+/// 注释对象 → (页码, 页面对象)，按各页 /Annots 建立
+fn annotation_pages(doc: &Document) -> HashMap<ObjectId, (u32, ObjectId)> {
+  let mut map = HashMap::new();
+  for (no, page_id) in doc.get_pages() {
+    let annots = doc.get_dictionary(page_id).ok()
+      .and_then(|p| p.get(b"Annots").ok())
+      .and_then(|o| resolve(doc, o).as_array().ok());
+    for a in annots.into_iter().flatten().filter_map(|o| o.as_reference().ok()) { map.insert(a, (no, page_id)); }
+  }
+  map
+}
+
+/// 控件当前外观画进所在页面：/AP /N 为状态字典时取 /AS 对应项；隐藏控件与没有外观的控件直接丢弃
+fn flatten_fields(doc: &mut Document, fields: &[FieldNode]) -> Result<(), String> {
+  let annot_pages = annotation_pages(doc);
+
+  let mut ops: BTreeMap<ObjectId, Vec<u8>> = BTreeMap::new();
+  let mut removed: HashSet<ObjectId> = HashSet::new();
+  for &w in fields.iter().flat_map(|f| &f.widgets) {
+    removed.insert(w);
+    let Some(&(_, page_id)) = annot_pages.get(&w) else { continue };
+    let Ok(widget) = doc.get_dictionary(w) else { continue };
+    if widget.get(b"F").ok().and_then(|o| o.as_i64().ok()).is_some_and(|f| f & ANNOT_HIDDEN != 0) { continue; }
+    let Some(ap) = normal_appearance(doc, widget) else { continue };
+    let Some([rx0, ry0, rx1, ry1]) = widget_rect(doc, w) else { continue };
+    let Ok(Object::Stream(form)) = doc.get_object(ap) else { continue };
+    let bbox: Vec<f32> = form.dict.get(b"BBox").ok().and_then(|o| o.as_array().ok())
+      .map(|a| a.iter().filter_map(|v| v.as_float().ok()).collect()).unwrap_or_default();
+    let [bx0, by0, bx1, by1] = bbox[..] else { continue };
+    let m = form.dict.get(b"Matrix").ok().and_then(|o| o.as_array().ok()).and_then(|a| matrix(a)).unwrap_or(IDENTITY);
+    // BBox 经 /Matrix 变换后的外框映射到 /Rect（PDF 规范 12.5.5 的算法）
+    let pts = [(bx0, by0), (bx1, by0), (bx1, by1), (bx0, by1)].map(|(x, y)| (x * m[0] + y * m[2] + m[4], x * m[1] + y * m[3] + m[5]));
+    let (tx0, tx1) = pts.iter().fold((f32::MAX, f32::MIN), |(a, b), p| (a.min(p.0), b.max(p.0)));
+    let (ty0, ty1) = pts.iter().fold((f32::MAX, f32::MIN), |(a, b), p| (a.min(p.1), b.max(p.1)));
+    if tx1 - tx0 <= 0.0 || ty1 - ty0 <= 0.0 { continue; }
+    let (sx, sy) = ((rx1 - rx0) / (tx1 - tx0), (ry1 - ry0) / (ty1 - ty0));
+    let name = format!("Flat{}_{}", w.0, w.1);
+    add_resource(doc, page_id, b"XObject", &name, ap)?;
+    ops.entry(page_id).or_default()
+      .extend(format!("q {sx} 0 0 {sy} {} {} cm /{name} Do Q\n", rx0 - sx * tx0, ry0 - sy * ty0).into_bytes());
+  }
+  for (page_id, ops) in ops { append_page_content(doc, page_id, ops)?; }
+
+  let pages: HashSet<ObjectId> = removed.iter().filter_map(|w| annot_pages.get(w).map(|&(_, p)| p)).collect();
+  for page_id in pages {
+    let kept: Vec<Object> = doc.get_dictionary(page_id).ok()
+      .and_then(|p| p.get(b"Annots").ok())
+      .and_then(|o| resolve(doc, o).as_array().ok())
+      .map(|a| a.iter().filter(|o| !o.as_reference().is_ok_and(|id| removed.contains(&id))).cloned().collect())
+      .unwrap_or_default();
+    dict_mut(doc, page_id)?.set("Annots", kept);
+  }
+  let cat = catalog_id(doc)?;
+  dict_mut(doc, cat)?.remove(b"AcroForm");
+  doc.prune_objects();
+  Ok(())
+}
+
+fn normal_appearance(doc: &Document, widget: &Dictionary) -> Option<ObjectId> {
+  let ap = resolve(doc, widget.get(b"AP").ok()?).as_dict().ok()?;
+  let n = ap.get(b"N").ok()?;
+  if let Ok(id) = n.as_reference() {
+    if matches!(doc.get_object(id), Ok(Object::Stream(_))) { return Some(id); }
+  }
+  let state = widget.get(b"AS").ok()?.as_name().ok()?;
+  resolve(doc, n).as_dict().ok()?.get(state).ok()?.as_reference().ok()
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SigField {
+  pub name: String,
+  /// 控件所在页（1 基）；控件不在任何页面的 /Annots 中时为 None
+  pub page: Option<u32>,
+  /// 控件矩形 [x0, y0, x1, y1]（pt，PDF 坐标）
+  pub rect: Option<[f32; 4]>,
+  /// 已有签名值（/V 签名字典）；false 为待签的空占位
+  pub signed: bool,
+}
+
+/// 每个签名字段的每个控件一项（通常一个字段只有一个控件）
+#[tauri::command]
+pub async fn find_signature_fields(input: InputOne) -> Result<Vec<SigField>, String> {
+  let doc = load_pdf(&input)?;
+  let pages = annotation_pages(&doc);
+  let mut out = Vec::new();
+  for field in collect_fields(&doc).into_iter().filter(|f| f.kind == FieldKind::Signature) {
+    let signed = doc.get_dictionary(field.id).ok()
+      .and_then(|d| d.get(b"V").ok())
+      .is_some_and(|v| resolve(&doc, v).as_dict().is_ok_and(|sig| sig.has(b"Contents")));
+    for w in &field.widgets {
+      out.push(SigField { name: field.name.clone(), page: pages.get(w).map(|&(no, _)| no), rect: widget_rect(&doc, *w), signed });
+    }
+  }
+  Ok(out)
+}
+
+/// appearance 取第一页，等比缩放居中放进签名控件矩形，写成控件的 /AP /N（签署前预览、签署后仍显示）。
+/// 已签名的字段拒绝修改：整体重写会破坏原签名
+#[tauri::command]
+pub async fn set_signature_appearance(input: InputOne, output: String, field_name: String, appearance: InputOne) -> Result<String, String> {
+  let output = resolve_output(&output, "signature", &[(&input).into()])?;
+  assert_input_not_output(&input, &output)?;
+  let mut doc = load_pdf(&input)?;
+  if doc.is_encrypted() { return Err("加密文档请先解密".into()); }
+  let fields = collect_fields(&doc);
+  let field = fields.iter().find(|f| f.name == field_name).ok_or_else(|| format!("找不到字段：{field_name}"))?;
+  if field.kind != FieldKind::Signature { return Err(format!("{field_name} 不是签名字段")); }
+  if doc.get_dictionary(field.id).is_ok_and(|d| d.has(b"V")) { return Err(format!("{field_name} 已签名，修改外观会使签名失效")); }
+  let widget = *field.widgets.first().ok_or_else(|| format!("{field_name} 没有控件"))?;
+  let [x0, y0, x1, y1] = widget_rect(&doc, widget).ok_or_else(|| format!("{field_name} 缺少控件矩形"))?;
+  let (w, h) = (x1 - x0, y1 - y0);
+  if w <= 0.0 || h <= 0.0 { return Err(format!("{field_name} 的控件矩形为空（不可见签名）")); }
+
+  let form = import_form(&mut doc, load_pdf(&appearance)?)?;
+  let [bx0, by0, bx1, by1] = form.bbox;
+  let s = (w / (bx1 - bx0)).min(h / (by1 - by0));
+  let (tx, ty) = ((w - (bx1 - bx0) * s) / 2.0 - bx0 * s, (h - (by1 - by0) * s) / 2.0 - by0 * s);
+  let wrapper = Stream::new(dictionary! {
+    "Type" => "XObject",
+    "Subtype" => "Form",
+    "BBox" => [0.0, 0.0, w, h].iter().map(|v| Object::Real(*v)).collect::<Vec<_>>(),
+    "Resources" => dictionary! { "XObject" => dictionary! { "SigAp" => form.id } },
+  }, format!("q {s} 0 0 {s} {tx} {ty} cm /SigAp Do Q\n").into_bytes());
+  let ap = doc.add_object(wrapper);
+  dict_mut(&mut doc, widget)?.set("AP", dictionary! { "N" => ap });
+  doc.prune_objects(); // 丢掉随外观文档带进来的页树 / Catalog
+  save_pdf(&mut doc, &output)?;
+  Ok(output)
+}
+
+/// AcroForm /Fields 下的全部终端字段；不是表单时为空
+pub(crate) fn collect_fields(doc: &Document) -> Vec<FieldNode> {
+  let mut out = Vec::new();
+  let Some(fields) = acroform(doc).and_then(|f| f.get(b"Fields").ok()).and_then(|o| resolve(doc, o).as_array().ok()) else { return out };
+  let mut seen = HashSet::new();
+  // AcroForm 级 /DA、/Q 作为最外层默认值
+  let form = acroform(doc);
+  let root = Inherited {
+    da: form.and_then(|f| f.get(b"DA").ok()).and_then(|o| resolve(doc, o).as_str().ok()),
+    q: form.and_then(|f| f.get(b"Q").ok()).and_then(|o| resolve(doc, o).as_i64().ok()),
+    ..Inherited::default()
+  };
+  for f in fields {
+    if let Ok(id) = f.as_reference() { walk(doc, id, "", &root, 0, &mut seen, &mut out); }
+  }
+  out
+}
+
+pub(crate) fn acroform(doc: &Document) -> Option<&Dictionary> {
+  let catalog = doc.get_dictionary(catalog_id(doc).ok()?).ok()?;
+  resolve(doc, catalog.get(b"AcroForm").ok()?).as_dict().ok()
+}
+
+/// AcroForm 字典的可变引用（间接对象或直接内嵌在 Catalog 中）
+fn acroform_mut(doc: &mut Document) -> Result<Option<&mut Dictionary>, String> {
+  let cat = catalog_id(doc)?;
+  let form_id = doc.get_dictionary(cat).ok().and_then(|c| c.get(b"AcroForm").ok()).and_then(|o| o.as_reference().ok());
+  Ok(match form_id {
+    Some(id) => doc.get_object_mut(id).ok().and_then(|o| o.as_dict_mut().ok()),
+    None => doc.get_object_mut(cat).ok()
+      .and_then(|o| o.as_dict_mut().ok())
+      .and_then(|c| c.get_mut(b"AcroForm").ok())
+      .and_then(|o| o.as_dict_mut().ok()),
+  })
+}
+
+/// 拆分后的文档只保留控件仍在本文档页面上的字段（Kids / Fields / CO 同步裁剪，控件 /P 指回所在页）；
+/// 一个字段都不剩时删除 AcroForm
+pub(crate) fn prune_acroform(doc: &mut Document) -> Result<(), String> {
+  let Some(fields) = acroform(doc).and_then(|f| f.get(b"Fields").ok()).and_then(|o| resolve(doc, o).as_array().ok()).cloned() else {
+    return Ok(());
+  };
+  let on_pages = annotation_pages(doc);
+
+  let mut kids_updates = Vec::new();
+  let mut seen = HashSet::new();
+  let kept: Vec<Object> = fields.into_iter()
+    .filter(|f| f.as_reference().is_ok_and(|id| keep_field(doc, id, &on_pages, &mut kids_updates, &mut seen, 0)))
+    .collect();
+  for (id, kids) in kids_updates { dict_mut(doc, id)?.set("Kids", kids); }
+  for (&w, &(_, page_id)) in &on_pages {
+    if seen.contains(&w) { dict_mut(doc, w)?.set("P", page_id); }
+  }
+
+  if kept.is_empty() {
+    let cat = catalog_id(doc)?;
+    dict_mut(doc, cat)?.remove(b"AcroForm");
+    return Ok(());
+  }
+  let Some(form) = acroform_mut(doc)? else { return Ok(()) };
+  form.set("Fields", kept);
+  // 计算顺序只保留还在的字段
+  if let Ok(Object::Array(co)) = form.get_mut(b"CO") {
+    co.retain(|o| match o.as_reference() { Ok(id) => seen.contains(&id), Err(_) => true });
+  }
+  Ok(())
+}
+
+/// 控件在页面上、或任一子节点保留时保留；有 Kids 的节点记下裁剪后的 Kids
+fn keep_field(doc: &Document, id: ObjectId, on_pages: &HashMap<ObjectId, (u32, ObjectId)>, updates: &mut Vec<(ObjectId, Vec<Object>)>, seen: &mut HashSet<ObjectId>, depth: usize) -> bool {
+  if depth > MAX_DEPTH || seen.contains(&id) { return false; }
+  let Ok(dict) = doc.get_dictionary(id) else { return false };
+  let Some(kids) = dict.get(b"Kids").ok().and_then(|o| resolve(doc, o).as_array().ok()).cloned() else {
+    let keep = on_pages.contains_key(&id);
+    if keep { seen.insert(id); }
+    return keep;
+  };
+  let kept: Vec<Object> = kids.into_iter()
+    .filter(|k| k.as_reference().is_ok_and(|k| keep_field(doc, k, on_pages, updates, seen, depth + 1)))
+    .collect();
+  if kept.is_empty() { return false; }
+  seen.insert(id);
+  updates.push((id, kept));
+  true
+}
+
+/// 可继承的字段属性
+#[derive(Default, Clone)]
+struct Inherited<'a> {
+  ft: Option<&'a [u8]>,
+  ff: Option<i64>,
+  v: Option<&'a Object>,
+  opt: Option<&'a Object>,
+  da: Option<&'a [u8]>,
+  q: Option<i64>,
+}
+
+fn walk<'a>(doc: &'a Document, id: ObjectId, parent: &str, inh: &Inherited<'a>, depth: usize, seen: &mut HashSet<ObjectId>, out: &mut Vec<FieldNode>) {
+  if depth > MAX_DEPTH || !seen.insert(id) { return; }
+  let Ok(dict) = doc.get_dictionary(id) else { return };
+  let name = match dict.get(b"T").ok().and_then(|o| resolve(doc, o).as_str().ok()).map(decode_text) {
+    Some(t) if parent.is_empty() => t,
+    Some(t) => format!("{parent}.{t}"),
+    None => parent.to_string(),
+  };
+  let inh = Inherited {
+    ft: dict.get(b"FT").ok().and_then(|o| resolve(doc, o).as_name().ok()).or(inh.ft),
+    ff: dict.get(b"Ff").ok().and_then(|o| resolve(doc, o).as_i64().ok()).or(inh.ff),
+    v: dict.get(b"V").ok().map(|o| resolve(doc, o)).or(inh.v),
+    opt: dict.get(b"Opt").ok().map(|o| resolve(doc, o)).or(inh.opt),
+    da: dict.get(b"DA").ok().and_then(|o| resolve(doc, o).as_str().ok()).or(inh.da),
+    q: dict.get(b"Q").ok().and_then(|o| resolve(doc, o).as_i64().ok()).or(inh.q),
+  };
+
+  let kids: Vec<ObjectId> = dict.get(b"Kids").ok()
+    .and_then(|o| resolve(doc, o).as_array().ok())
+    .map(|a| a.iter().filter_map(|k| k.as_reference().ok()).collect())
+    .unwrap_or_default();
+  // 带 /T 的子节点是下级字段，否则是本字段的控件
+  let (children, widgets): (Vec<ObjectId>, Vec<ObjectId>) = kids.into_iter()
+    .partition(|k| doc.get_dictionary(*k).is_ok_and(|d| d.has(b"T")));
+  for child in children { walk(doc, child, &name, &inh, depth + 1, seen, out); }
+  if name.is_empty() || inh.ft.is_none() { return; }
+  // 只有下级字段的中间节点不算字段
+  if widgets.is_empty() && !dict.has(b"Subtype") && dict.has(b"Kids") { return; }
+  let widgets = if widgets.is_empty() { vec![id] } else { widgets };
+
+  let flags = inh.ff.unwrap_or(0);
+  let kind = match inh.ft {
+    Some(b"Tx") => FieldKind::Text,
+    Some(b"Ch") => FieldKind::Choice,
+    Some(b"Sig") => FieldKind::Signature,
+    Some(b"Btn") if flags & FF_PUSHBUTTON != 0 => FieldKind::PushButton,
+    Some(b"Btn") if flags & FF_RADIO != 0 => FieldKind::Radio,
+    Some(b"Btn") => FieldKind::Checkbox,
+    _ => return,
+  };
+  let value = inh.v.and_then(|v| value_string(doc, v));
+  let options = match kind {
+    FieldKind::Choice => choice_options(doc, inh.opt),
+    FieldKind::Checkbox | FieldKind::Radio => on_states(doc, &widgets),
+    _ => Vec::new(),
+  };
+  let da = inh.da.map(|d| String::from_utf8_lossy(d).to_string());
+  out.push(FieldNode { id, name, kind, flags, value, options, widgets, da, quadding: inh.q.unwrap_or(0) });
+}
+
+fn value_string(doc: &Document, v: &Object) -> Option<String> {
+  match v {
+    Object::String(s, _) => Some(decode_text(s)),
+    Object::Name(n) => Some(String::from_utf8_lossy(n).to_string()),
+    Object::Array(items) => {
+      let parts: Vec<String> = items.iter().filter_map(|i| value_string(doc, resolve(doc, i))).collect();
+      (!parts.is_empty()).then(|| parts.join(", "))
+    }
+    _ => None,
+  }
+}
+
+/// /Opt 项为导出值串，或 [导出值, 显示文字] 对
+fn choice_options(doc: &Document, opt: Option<&Object>) -> Vec<String> {
+  let Some(Ok(items)) = opt.map(|o| o.as_array()) else { return Vec::new() };
+  items.iter().filter_map(|item| match resolve(doc, item) {
+    Object::Array(pair) => pair.first().and_then(|o| resolve(doc, o).as_str().ok()).map(decode_text),
+    other => other.as_str().ok().map(decode_text),
+  }).collect()
+}
+
+/// 控件 /AP /N 下除 Off 外的外观名（去重，保持出现顺序）
+pub(crate) fn on_states(doc: &Document, widgets: &[ObjectId]) -> Vec<String> {
+  let mut states: Vec<String> = Vec::new();
+  for w in widgets {
+    let normal = doc.get_dictionary(*w).ok()
+      .and_then(|d| d.get(b"AP").ok())
+      .and_then(|o| resolve(doc, o).as_dict().ok())
+      .and_then(|ap| ap.get(b"N").ok())
+      .and_then(|o| resolve(doc, o).as_dict().ok());
+    for (name, _) in normal.into_iter().flat_map(|n| n.iter()) {
+      let s = String::from_utf8_lossy(name).to_string();
+      if s != "Off" && !states.contains(&s) { states.push(s); }
+    }
+  }
+  states
+}
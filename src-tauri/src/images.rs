@@ -0,0 +1,181 @@
+//! Images — enumerate image XObjects (with content hash), export a single image & strip a recurring image by hash
+use tauri::AppHandle;
+use base64::{engine::general_purpose, Engine as _};
+use lopdf::{content::Content, dictionary, Document, Object, Stream};
+use serde::Serialize;
+use std::{fs, path::Path};
+
+use crate::common::{assert_input_not_output, catalog_id, hex_sha256, load_pdf, save_pdf, work_dir, InputOne};
+use crate::inspect::page_images;
+use crate::naming::resolve_output;
+use crate::render::{render_pages, RenderMode};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageInfo {
+  pub page: u32,
+  /// 资源名（如 "Im0"）
+  pub name: String,
+  pub width: i64,
+  pub height: i64,
+  /// 首个滤镜名（DCTDecode / FlateDecode ...），无滤镜为 None
+  pub filter: Option<String>,
+  /// 流原始字节数
+  pub bytes: usize,
+  /// 解码后字节的 SHA-256（无法解码的滤镜按原始字节），用于 remove_image 定位
+  pub hash: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoveReport {
+  pub path: String,
+  /// 删除的绘制次数（Do 操作数）
+  pub removed: u32,
+  pub pages: Vec<u32>,
+}
+
+#[tauri::command]
+pub async fn list_images(input: InputOne) -> Result<Vec<ImageInfo>, String> {
+  let doc = load_pdf(&input)?;
+  let mut out = Vec::new();
+  for (no, page_id) in doc.get_pages() {
+    for (name, id) in page_images(&doc, page_id) {
+      let Ok(Object::Stream(s)) = doc.get_object(id) else { continue };
+      out.push(ImageInfo {
+        page: no,
+        name: String::from_utf8_lossy(&name).to_string(),
+        width: s.dict.get(b"Width").and_then(|o| o.as_i64()).unwrap_or(0),
+        height: s.dict.get(b"Height").and_then(|o| o.as_i64()).unwrap_or(0),
+        filter: first_filter(s),
+        bytes: s.content.len(),
+        hash: image_digest(s),
+      });
+    }
+  }
+  Ok(out)
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageDto {
+  pub base64: String,
+  /// image/jpeg（DCT 原样导出）或 image/png
+  pub mime: &'static str,
+  pub width: u32,
+  pub height: u32,
+}
+
+/// 导出第 page 页的第 image_index 张图片（0 起，顺序同 list_images）。
+/// 纯 DCT 的 RGB / 灰度 JPEG 原样返回；其余（Flate / CCITT / JBIG2 / JPX、CMYK、Decode 数组等）
+/// 把该图单独放进一页、Ghostscript 以 72 dpi 1:1 渲染为 PNG，交给 gs 处理所有滤镜与色彩空间（软蒙版按白底合成）
+#[tauri::command]
+pub async fn get_image(app: AppHandle, input: InputOne, page: u32, image_index: u32) -> Result<ImageDto, String> {
+  let mut doc = load_pdf(&input)?;
+  let page_id = *doc.get_pages().get(&page).ok_or(format!("页码越界：{page}"))?;
+  let id = *page_images(&doc, page_id).values().nth(image_index as usize).ok_or(format!("第 {page} 页没有第 {image_index} 张图片"))?;
+  let Ok(Object::Stream(s)) = doc.get_object(id) else { return Err("图片对象不是流".into()) };
+  let dim = |key: &[u8]| s.dict.get(key).and_then(|o| o.as_i64()).ok().filter(|&v| v > 0).map(|v| v as u32);
+  let (Some(width), Some(height)) = (dim(b"Width"), dim(b"Height")) else { return Err("图片缺少有效的 Width / Height".into()) };
+
+  if is_plain_jpeg(s) {
+    return Ok(ImageDto { base64: general_purpose::STANDARD.encode(&s.content), mime: "image/jpeg", width, height });
+  }
+  let work = work_dir(&app, "get_image")?;
+  let res = render_image(&app, &work, &mut doc, id, width, height);
+  let _ = fs::remove_dir_all(&work);
+  let png = res?;
+  Ok(ImageDto { base64: general_purpose::STANDARD.encode(png), mime: "image/png", width, height })
+}
+
+/// 单一 DCTDecode、设备 RGB / 灰度、无 Decode 数组：浏览器可直接显示
+fn is_plain_jpeg(s: &Stream) -> bool {
+  let single_dct = match s.dict.get(b"Filter") {
+    Ok(Object::Name(n)) => n == b"DCTDecode",
+    Ok(Object::Array(a)) => a.len() == 1 && a[0].as_name().is_ok_and(|n| n == b"DCTDecode"),
+    _ => false,
+  };
+  let device_cs = s.dict.get(b"ColorSpace").and_then(|o| o.as_name()).is_ok_and(|n| n == b"DeviceRGB" || n == b"DeviceGray");
+  single_dct && device_cs && s.dict.get(b"Decode").is_err()
+}
+
+/// 文档改成只有一页（页面尺寸 = 图片像素数 pt，整页画这张图），其余对象由 prune_objects 清掉
+fn render_image(app: &AppHandle, work: &Path, doc: &mut Document, image: lopdf::ObjectId, width: u32, height: u32) -> Result<Vec<u8>, String> {
+  let content = doc.add_object(Stream::new(dictionary! {}, format!("q {width} 0 0 {height} 0 0 cm /Im0 Do Q").into_bytes()));
+  let pages = doc.new_object_id();
+  let page = doc.add_object(dictionary! {
+    "Type" => "Page",
+    "Parent" => pages,
+    "MediaBox" => vec![Object::Integer(0), Object::Integer(0), Object::Integer(width as i64), Object::Integer(height as i64)],
+    "Resources" => dictionary! { "XObject" => dictionary! { "Im0" => image } },
+    "Contents" => content,
+  });
+  doc.objects.insert(pages, Object::Dictionary(dictionary! { "Type" => "Pages", "Kids" => vec![page.into()], "Count" => Object::Integer(1) }));
+  let cat = catalog_id(doc)?;
+  doc.objects.insert(cat, Object::Dictionary(dictionary! { "Type" => "Catalog", "Pages" => pages }));
+  doc.prune_objects();
+
+  let single = work.join("image.pdf").to_string_lossy().to_string();
+  save_pdf(doc, &single)?;
+  let png = render_pages(app, &single, &work.join("render"), 72, RenderMode::Rgb, None)?.into_iter().next().ok_or("渲染图片失败")?;
+  fs::read(&png).map_err(|e| format!("读取渲染结果失败：{e}"))
+}
+
+/// 删除页面内容流中对目标图片的 Do 绘制（哈希来自 list_images）。
+/// 仅处理页面级内容流；嵌套在 Form XObject 里的绘制不受影响。
+#[tauri::command]
+pub async fn remove_image(input: InputOne, output: String, image_hash: String) -> Result<RemoveReport, String> {
+  let output = resolve_output(&output, "remove_image", &[(&input).into()])?;
+  assert_input_not_output(&input, &output)?;
+  let mut doc = load_pdf(&input)?;
+  let target = image_hash.trim().to_ascii_lowercase();
+
+  let mut removed = 0u32;
+  let mut pages = Vec::new();
+  for (no, page_id) in doc.get_pages() {
+    let names: Vec<Vec<u8>> = page_images(&doc, page_id).into_iter()
+      .filter(|(_, id)| matches!(doc.get_object(*id), Ok(Object::Stream(s)) if image_digest(s) == target))
+      .map(|(name, _)| name)
+      .collect();
+    if names.is_empty() { continue; }
+
+    let n = strip_do(&mut doc, page_id, &names)?;
+    if n > 0 { removed += n; pages.push(no); }
+  }
+  if removed == 0 { return Err(format!("未找到哈希为 {target} 的图片绘制")); }
+
+  save_pdf(&mut doc, &output)?;
+  Ok(RemoveReport { path: output, removed, pages })
+}
+
+/// 从页面内容流删除指定资源名的 Do 操作，返回删除数
+fn strip_do(doc: &mut Document, page_id: lopdf::ObjectId, names: &[Vec<u8>]) -> Result<u32, String> {
+  let mut content = doc.get_and_decode_page_content(page_id).map_err(|e| format!("解析内容流失败：{e}"))?;
+  let before = content.operations.len();
+  content.operations.retain(|op| {
+    !(op.operator == "Do" && op.operands.first().and_then(|o| o.as_name().ok()).is_some_and(|n| names.iter().any(|x| x.as_slice() == n)))
+  });
+  let n = (before - content.operations.len()) as u32;
+  if n > 0 { write_content(doc, page_id, &content)?; }
+  Ok(n)
+}
+
+pub(crate) fn write_content(doc: &mut Document, page_id: lopdf::ObjectId, content: &Content) -> Result<(), String> {
+  let bytes = content.encode().map_err(|e| format!("编码内容流失败：{e}"))?;
+  doc.change_page_content(page_id, bytes).map_err(|e| format!("写回内容流失败：{e}"))
+}
+
+pub(crate) fn image_digest(s: &Stream) -> String {
+  let data = s.decompressed_content().unwrap_or_else(|_| s.content.clone());
+  hex_sha256(&data)
+}
+
+fn first_filter(s: &Stream) -> Option<String> {
+  let f = s.dict.get(b"Filter").ok()?;
+  let name = match f {
+    Object::Name(n) => n.clone(),
+    Object::Array(a) => a.first()?.as_name().ok()?.to_vec(),
+    _ => return None,
+  };
+  Some(String::from_utf8_lossy(&name).to_string())
+}
@@ -0,0 +1,92 @@
+//! Imposition — lay source pages out as Form XObjects on new sheets (booklet / N-up) via lopdf
+use lopdf::{dictionary, Dictionary, Document, Object, ObjectId, Stream};
+use std::collections::{btree_map::Entry, BTreeMap};
+
+use crate::common::{assert_input_not_output, catalog_id, load_pdf, save_pdf, InputOne};
+use crate::naming::resolve_output;
+use crate::pages::PageSize;
+use crate::stamp::{page_form, ImportedForm};
+
+/// 骑马钉小册子：补空白页到 4 的倍数，每张纸正反两面各放两页（横向纸张，左右对开）
+#[tauri::command]
+pub async fn make_booklet(input: InputOne, output: String, paper: PageSize) -> Result<String, String> {
+  let output = resolve_output(&output, "booklet", &[(&input).into()])?;
+  let (w, h) = paper.dims_pt();
+  if !(w > 0.0 && h > 0.0) { return Err(format!("无效纸张尺寸：{w}×{h} pt")); }
+  assert_input_not_output(&input, &output)?;
+  let mut doc = load_pdf(&input)?;
+  let total = doc.get_pages().len() as u32;
+  if total == 0 { return Err("文档没有页面".into()); }
+
+  let sheets: Vec<Vec<Option<u32>>> = booklet_order(total).chunks(2).map(|c| c.to_vec()).collect();
+  impose(&mut doc, &sheets, 2, 1, (w.max(h), w.min(h)))?;
+  save_pdf(&mut doc, &output)?;
+  Ok(output)
+}
+
+/// 骑马钉页序：第 i 张纸正面 [m-2i, 2i+1]、背面 [2i+2, m-2i-1]；超出原页数的位置为空白（None）
+pub(crate) fn booklet_order(total: u32) -> Vec<Option<u32>> {
+  let m = total.div_ceil(4) * 4;
+  let slot = |p: u32| (p <= total).then_some(p);
+  (0..m / 4).flat_map(|i| [
+    slot(m - 2 * i), slot(2 * i + 1),
+    slot(2 * i + 2), slot(m - 2 * i - 1),
+  ]).collect()
+}
+
+/// 用新版面替换整棵页树：sheets 每项是一面纸上按行优先排列的 cols×rows 个槽位（1 基原页码，None 留白）。
+/// 原页面等比缩放居中到各自格子；书签、结构树、表单等引用旧页面的对象一并移除。
+pub(crate) fn impose(doc: &mut Document, sheets: &[Vec<Option<u32>>], cols: u32, rows: u32, sheet: (f32, f32)) -> Result<(), String> {
+  let pages = doc.get_pages();
+  let cat = catalog_id(doc)?;
+  let root = doc.get_dictionary(cat).ok()
+    .and_then(|c| c.get(b"Pages").ok())
+    .and_then(|o| o.as_reference().ok())
+    .ok_or("缺少页树（/Pages）")?;
+
+  let (sw, sh) = sheet;
+  let (cell_w, cell_h) = (sw / cols as f32, sh / rows as f32);
+  let mut forms: BTreeMap<u32, ImportedForm> = BTreeMap::new();
+  let mut kids = Vec::with_capacity(sheets.len());
+  for slots in sheets {
+    let mut ops = String::new();
+    let mut xobjects = Dictionary::new();
+    for (i, no) in slots.iter().enumerate() {
+      let Some(no) = *no else { continue };
+      let page_id = *pages.get(&no).ok_or(format!("页码越界：{no}"))?;
+      if let Entry::Vacant(e) = forms.entry(no) { e.insert(page_form(doc, page_id)?); }
+      let form = &forms[&no];
+
+      let (col, row) = (i as u32 % cols, i as u32 / cols);
+      let (bw, bh) = (form.bbox[2] - form.bbox[0], form.bbox[3] - form.bbox[1]);
+      let s = (cell_w / bw).min(cell_h / bh);
+      let x = col as f32 * cell_w + (cell_w - bw * s) / 2.0 - form.bbox[0] * s;
+      let y = sh - (row + 1) as f32 * cell_h + (cell_h - bh * s) / 2.0 - form.bbox[1] * s;
+      let name = format!("Pg{no}");
+      ops.push_str(&format!("q {s} 0 0 {s} {x} {y} cm /{name} Do Q\n"));
+      xobjects.set(name, form.id);
+    }
+    let contents = doc.add_object(Stream::new(Dictionary::new(), ops.into_bytes()));
+    kids.push(doc.add_object(dictionary! {
+      "Type" => "Page",
+      "Parent" => root,
+      "MediaBox" => vec![Object::Integer(0), Object::Integer(0), Object::Real(sw), Object::Real(sh)],
+      "Resources" => dictionary! { "XObject" => xobjects },
+      "Contents" => contents,
+    }));
+  }
+
+  replace_page_tree(doc, cat, root, kids)
+}
+
+fn replace_page_tree(doc: &mut Document, cat: ObjectId, root: ObjectId, kids: Vec<ObjectId>) -> Result<(), String> {
+  let Ok(Object::Dictionary(tree)) = doc.get_object_mut(root) else { return Err("页树（/Pages）不是字典".into()) };
+  tree.set("Count", kids.len() as i64);
+  tree.set("Kids", kids.into_iter().map(Object::Reference).collect::<Vec<_>>());
+  for key in [&b"Rotate"[..], b"MediaBox", b"CropBox", b"Resources"] { tree.remove(key); }
+  if let Ok(Object::Dictionary(c)) = doc.get_object_mut(cat) {
+    for key in [&b"Outlines"[..], b"StructTreeRoot", b"OpenAction", b"PageLabels", b"AcroForm", b"Dests"] { c.remove(key); }
+  }
+  doc.prune_objects();
+  Ok(())
+}
@@ -0,0 +1,424 @@
+//! Inspect PDF — read-only analysis via lopdf (page boxes, sizes, images, text, fonts, image density)
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::common::{load_pdf, InputOne};
+use crate::pages::PageSize;
+
+/// 每页非空白字符达到该数量才算"有文字"
+const MIN_TEXT_CHARS: usize = 20;
+/// 图片覆盖页面面积比例达到该值视为整页图（扫描件）
+const FULL_PAGE_RATIO: f32 = 0.8;
+/// 图片面积占比达到该值视为照片页
+const PHOTO_DENSITY: f32 = 0.5;
+/// 页面尺寸聚类容差（pt）
+const SIZE_TOLERANCE_PT: f32 = 1.0;
+/// 标准 14 字体：阅读器自带替代字形，不嵌入也能显示
+const BASE14_FONTS: [&str; 14] = [
+  "Times-Roman", "Times-Bold", "Times-Italic", "Times-BoldItalic",
+  "Helvetica", "Helvetica-Bold", "Helvetica-Oblique", "Helvetica-BoldOblique",
+  "Courier", "Courier-Bold", "Courier-Oblique", "Courier-BoldOblique",
+  "Symbol", "ZapfDingbats",
+];
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PdfKind {
+  ImageOnly,
+  Mixed,
+  TextBased,
+  /// 既没有文字层也没有整页图：空白或纯矢量图形，OCR 无从识别
+  NoText,
+}
+
+#[tauri::command]
+pub async fn classify_pdf(input: InputOne) -> Result<PdfKind, String> {
+  let doc = load_pdf(&input)?;
+  let (mut text_pages, mut scan_pages) = (0usize, 0usize);
+  for (no, page_id) in doc.get_pages() {
+    let has_text = page_text(&doc, no).chars().filter(|c| !c.is_whitespace()).count() >= MIN_TEXT_CHARS;
+    if has_text { text_pages += 1; } else if has_full_page_image(&doc, page_id) { scan_pages += 1; }
+  }
+  Ok(match (text_pages, scan_pages) {
+    (0, 0) => PdfKind::NoText,
+    (0, _) => PdfKind::ImageOnly,
+    (_, 0) => PdfKind::TextBased,
+    _ => PdfKind::Mixed,
+  })
+}
+
+/// 可识别字符占比低于该值的页判为乱码
+const GARBLED_SCORE: f32 = 0.7;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageTextQuality {
+  pub page: u32,
+  /// 非空白字符数
+  pub chars: u32,
+  /// 可识别字符占比 0–1；文字太少（不足 20 个字符）为 None
+  pub score: Option<f32>,
+  pub garbled: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextQuality {
+  pub pages: Vec<PageTextQuality>,
+  /// 有文字层但大概率提取为乱码、需要 OCR 的页
+  pub garbled_pages: Vec<u32>,
+}
+
+/// 按提取文字中常见文字系统的字母 / 数字与常用标点的占比打分；
+/// 自定义编码且缺 ToUnicode 的字体通常提取为私用区、替换符或杂乱符号，得分明显偏低
+#[tauri::command]
+pub async fn text_quality(input: InputOne) -> Result<TextQuality, String> {
+  let doc = load_pdf(&input)?;
+  let mut pages = Vec::new();
+  let mut garbled_pages = Vec::new();
+  for no in doc.get_pages().into_keys() {
+    let text = page_text(&doc, no);
+    let chars: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+    let score = (chars.len() >= MIN_TEXT_CHARS)
+      .then(|| chars.iter().filter(|&&c| is_known_char(c)).count() as f32 / chars.len() as f32);
+    let garbled = score.is_some_and(|s| s < GARBLED_SCORE);
+    if garbled { garbled_pages.push(no); }
+    pages.push(PageTextQuality { page: no, chars: chars.len() as u32, score, garbled });
+  }
+  Ok(TextQuality { pages, garbled_pages })
+}
+
+/// 常见文字系统的字母 / 数字，或常用标点
+fn is_known_char(c: char) -> bool {
+  if c.is_ascii_alphanumeric() || ".,;:!?'\"()[]-/%&+=@#*$€£¥…–—‘’“”，。、；：！？「」『』（）《》·".contains(c) { return true; }
+  if !c.is_alphanumeric() { return false; }
+  matches!(c as u32,
+    0x00C0..=0x024F // 拉丁扩展
+    | 0x0370..=0x03FF // 希腊
+    | 0x0400..=0x04FF // 西里尔
+    | 0x0590..=0x06FF // 希伯来 / 阿拉伯
+    | 0x0900..=0x097F // 天城文
+    | 0x0E00..=0x0E7F // 泰文
+    | 0x3040..=0x30FF // 假名
+    | 0x3400..=0x4DBF | 0x4E00..=0x9FFF // 汉字
+    | 0xAC00..=0xD7AF // 韩文
+    | 0xFF10..=0xFF19 | 0xFF21..=0xFF3A | 0xFF41..=0xFF5A // 全角字母数字
+  )
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SizeGroup {
+  pub width_pt: f32,
+  pub height_pt: f32,
+  /// 匹配的常用纸张名（A4 / Letter ...），横竖版均可匹配
+  pub paper: Option<&'static str>,
+  pub landscape: bool,
+  pub count: u32,
+  pub pages: Vec<u32>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SizeSummary {
+  /// 按页数降序，第一组即主尺寸
+  pub sizes: Vec<SizeGroup>,
+  pub uniform: bool,
+}
+
+/// 按 MediaBox 聚类页面尺寸（±1pt 视为相同）
+#[tauri::command]
+pub async fn size_summary(input: InputOne) -> Result<SizeSummary, String> {
+  let doc = load_pdf(&input)?;
+  let mut sizes: Vec<SizeGroup> = Vec::new();
+  for (no, page_id) in doc.get_pages() {
+    let Some(mb) = page_rect(&doc, page_id, b"MediaBox") else { return Err(format!("第 {no} 页缺少 MediaBox")) };
+    let (w, h) = (mb[2] - mb[0], mb[3] - mb[1]);
+    match sizes.iter_mut().find(|g| (g.width_pt - w).abs() <= SIZE_TOLERANCE_PT && (g.height_pt - h).abs() <= SIZE_TOLERANCE_PT) {
+      Some(g) => { g.count += 1; g.pages.push(no); }
+      None => sizes.push(SizeGroup { width_pt: w, height_pt: h, paper: paper_name(w, h), landscape: w > h, count: 1, pages: vec![no] }),
+    }
+  }
+  sizes.sort_by(|a, b| b.count.cmp(&a.count));
+  Ok(SizeSummary { uniform: sizes.len() <= 1, sizes })
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Orientation {
+  pub page: u32,
+  /// 按 /Rotate 转正后的显示尺寸
+  pub width_pt: f32,
+  pub height_pt: f32,
+  /// 规范化到 0 / 90 / 180 / 270
+  pub rotate: i64,
+  /// 建议横向打印（显示宽 > 高；正方形按纵向）
+  pub landscape: bool,
+}
+
+/// 每页建议打印方向：MediaBox 尺寸 + /Rotate，供打印对话框提示"第 3–5 页应横向打印"
+#[tauri::command]
+pub async fn print_orientation_hints(input: InputOne) -> Result<Vec<Orientation>, String> {
+  let doc = load_pdf(&input)?;
+  doc.get_pages().into_iter().map(|(no, page_id)| {
+    let mb = page_rect(&doc, page_id, b"MediaBox").ok_or(format!("第 {no} 页缺少 MediaBox"))?;
+    let rotate = inherited(&doc, page_id, b"Rotate").and_then(|o| o.as_i64().ok()).unwrap_or(0).rem_euclid(360) / 90 * 90;
+    let (w, h) = (mb[2] - mb[0], mb[3] - mb[1]);
+    let (width_pt, height_pt) = if rotate % 180 == 90 { (h, w) } else { (w, h) };
+    Ok(Orientation { page: no, width_pt, height_pt, rotate, landscape: width_pt > height_pt })
+  }).collect()
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FontIssue {
+  /// BaseFont（已去掉子集前缀 ABCDEF+）
+  pub name: String,
+  /// Type1 / TrueType / Type0 ...
+  pub subtype: String,
+  pub base14: bool,
+  /// 用到该字体的页（含页面上 Form XObject 里用到的）
+  pub pages: Vec<u32>,
+}
+
+/// 未嵌入、依赖阅读器替代字形的字体；require_embedded 为 false 时放过标准 14 字体，为 true 时一并报告（严格印刷预检）
+#[tauri::command]
+pub async fn font_compliance(input: InputOne, require_embedded: bool) -> Result<Vec<FontIssue>, String> {
+  let doc = load_pdf(&input)?;
+  let mut issues: Vec<FontIssue> = Vec::new();
+  for (no, page_id) in doc.get_pages() {
+    let mut fonts = Vec::new();
+    if let Some(res) = inherited(&doc, page_id, b"Resources").and_then(|o| o.as_dict().ok()) {
+      collect_fonts(&doc, res, &mut fonts, 0);
+    }
+    for font in fonts {
+      if font_embedded(&doc, font) { continue; }
+      let name = font_name(font);
+      let base14 = BASE14_FONTS.contains(&name.as_str());
+      if base14 && !require_embedded { continue; }
+      match issues.iter_mut().find(|i| i.name == name) {
+        Some(i) => if i.pages.last() != Some(&no) { i.pages.push(no) },
+        None => {
+          let subtype = String::from_utf8_lossy(font.get(b"Subtype").and_then(Object::as_name).unwrap_or_default()).into_owned();
+          issues.push(FontIssue { name, subtype, base14, pages: vec![no] });
+        }
+      }
+    }
+  }
+  Ok(issues)
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageDensity {
+  pub page: u32,
+  /// 图片覆盖可见区域的比例 0–1（重叠部分重复计入后截到 1）
+  pub image_fraction: f32,
+  /// image_fraction 达到 PHOTO_DENSITY，适合有损压缩
+  pub likely_photo: bool,
+}
+
+/// 每页图片面积占比：图片 XObject 经 CTM 变换后的外框与 CropBox 求交（不展开 Form XObject），供分段压缩自动选范围
+#[tauri::command]
+pub async fn content_density(input: InputOne) -> Result<Vec<PageDensity>, String> {
+  let doc = load_pdf(&input)?;
+  Ok(doc.get_pages().into_iter().map(|(no, page_id)| {
+    let image_fraction = page_rect(&doc, page_id, b"CropBox").or_else(|| page_rect(&doc, page_id, b"MediaBox"))
+      .map(|area| image_fraction(&image_placements(&doc, page_id), area))
+      .unwrap_or(0.0);
+    PageDensity { page: no, image_fraction, likely_photo: image_fraction >= PHOTO_DENSITY }
+  }).collect())
+}
+
+fn image_fraction(placements: &[ImagePlacement], [x0, y0, x1, y1]: [f32; 4]) -> f32 {
+  let page_area = (x1 - x0) * (y1 - y0);
+  if page_area <= 0.0 { return 0.0; }
+  let covered: f32 = placements.iter().map(|p| {
+    let [l, b, r, t] = p.bbox();
+    (r.min(x1) - l.max(x0)).max(0.0) * (t.min(y1) - b.max(y0)).max(0.0)
+  }).sum();
+  (covered / page_area).min(1.0)
+}
+
+/// 资源字典里的字体，递归进 Form XObject（页眉 / 表格常放在表单里）
+fn collect_fonts<'a>(doc: &'a Document, res: &'a Dictionary, out: &mut Vec<&'a Dictionary>, depth: u8) {
+  if depth > 8 { return; }
+  if let Some(fonts) = res.get(b"Font").ok().and_then(|o| resolve(doc, o).as_dict().ok()) {
+    out.extend(fonts.iter().filter_map(|(_, f)| resolve(doc, f).as_dict().ok()));
+  }
+  let Some(xobjs) = res.get(b"XObject").ok().and_then(|o| resolve(doc, o).as_dict().ok()) else { return };
+  for (_, x) in xobjs.iter() {
+    let Object::Stream(s) = resolve(doc, x) else { continue };
+    if s.dict.get(b"Subtype").and_then(Object::as_name).ok() != Some(&b"Form"[..]) { continue; }
+    if let Some(r) = s.dict.get(b"Resources").ok().and_then(|o| resolve(doc, o).as_dict().ok()) {
+      collect_fonts(doc, r, out, depth + 1);
+    }
+  }
+}
+
+/// Type3 字形由内容流绘制，视为已嵌入；Type0 看其后代 CIDFont 的 FontDescriptor
+fn font_embedded(doc: &Document, font: &Dictionary) -> bool {
+  let subtype = font.get(b"Subtype").and_then(Object::as_name).unwrap_or_default();
+  if subtype == b"Type3" { return true; }
+  let described = if subtype == b"Type0" {
+    let descendant = font.get(b"DescendantFonts").ok()
+      .and_then(|o| resolve(doc, o).as_array().ok())
+      .and_then(|a| a.first())
+      .and_then(|o| resolve(doc, o).as_dict().ok());
+    match descendant { Some(d) => d, None => return false }
+  } else { font };
+  let Some(fd) = described.get(b"FontDescriptor").ok().and_then(|o| resolve(doc, o).as_dict().ok()) else { return false };
+  [&b"FontFile"[..], b"FontFile2", b"FontFile3"].iter().any(|k| fd.has(k))
+}
+
+fn font_name(font: &Dictionary) -> String {
+  let name = String::from_utf8_lossy(font.get(b"BaseFont").and_then(Object::as_name).unwrap_or(b"(unnamed)")).into_owned();
+  match name.split_once('+') {
+    Some((prefix, rest)) if prefix.len() == 6 && prefix.bytes().all(|b| b.is_ascii_uppercase()) => rest.to_string(),
+    _ => name,
+  }
+}
+
+fn paper_name(w: f32, h: f32) -> Option<&'static str> {
+  const PAPERS: [(&str, PageSize); 5] = [
+    ("A3", PageSize::A3), ("A4", PageSize::A4), ("A5", PageSize::A5), ("Letter", PageSize::Letter), ("Legal", PageSize::Legal),
+  ];
+  let (short, long) = (w.min(h), w.max(h));
+  PAPERS.iter().find(|(_, p)| {
+    let (pw, ph) = p.dims_pt();
+    (pw - short).abs() <= SIZE_TOLERANCE_PT && (ph - long).abs() <= SIZE_TOLERANCE_PT
+  }).map(|(n, _)| *n)
+}
+
+fn has_full_page_image(doc: &Document, page_id: ObjectId) -> bool {
+  let Some(mb) = page_rect(doc, page_id, b"MediaBox") else { return false };
+  let page_area = (mb[2] - mb[0]) * (mb[3] - mb[1]);
+  page_area > 0.0 && image_placements(doc, page_id).iter().any(|p| p.area() >= page_area * FULL_PAGE_RATIO)
+}
+
+// ---------- 共用：页面属性 ----------
+
+/// 解引用一层 Reference
+pub(crate) fn resolve<'a>(doc: &'a Document, obj: &'a Object) -> &'a Object {
+  match obj {
+    Object::Reference(id) => doc.get_object(*id).unwrap_or(obj),
+    _ => obj,
+  }
+}
+
+/// 沿 /Parent 链查找可继承的页面属性（MediaBox / CropBox / Rotate / Resources）
+pub(crate) fn inherited<'a>(doc: &'a Document, page_id: ObjectId, key: &[u8]) -> Option<&'a Object> {
+  let mut cur = doc.get_dictionary(page_id).ok()?;
+  for _ in 0..64 {
+    if let Ok(v) = cur.get(key) { return Some(resolve(doc, v)); }
+    let parent = cur.get(b"Parent").ok()?.as_reference().ok()?;
+    cur = doc.get_dictionary(parent).ok()?;
+  }
+  None
+}
+
+/// 页面矩形 [x0, y0, x1, y1]（已规范化 x0<x1, y0<y1）
+pub(crate) fn page_rect(doc: &Document, page_id: ObjectId, key: &[u8]) -> Option<[f32; 4]> {
+  let arr = inherited(doc, page_id, key)?.as_array().ok()?;
+  rect_from(doc, arr)
+}
+
+fn rect_from(doc: &Document, arr: &[Object]) -> Option<[f32; 4]> {
+  if arr.len() != 4 { return None; }
+  let v: Vec<f32> = arr.iter().filter_map(|o| resolve(doc, o).as_float().ok()).collect();
+  if v.len() != 4 { return None; }
+  Some([v[0].min(v[2]), v[1].min(v[3]), v[0].max(v[2]), v[1].max(v[3])])
+}
+
+/// 提取单页文字；遇到不支持的字体编码时按空处理
+pub(crate) fn page_text(doc: &Document, page_no: u32) -> String {
+  doc.extract_text(&[page_no]).unwrap_or_default()
+}
+
+// ---------- 共用：图片 XObject ----------
+
+/// 页面资源里的图片 XObject：名称 → 对象号（不递归 Form XObject）
+pub(crate) fn page_images(doc: &Document, page_id: ObjectId) -> BTreeMap<Vec<u8>, ObjectId> {
+  let mut out = BTreeMap::new();
+  let Some(res) = inherited(doc, page_id, b"Resources").and_then(|o| o.as_dict().ok()) else { return out };
+  let Some(xobjs) = res.get(b"XObject").ok().and_then(|o| resolve(doc, o).as_dict().ok()) else { return out };
+  for (name, obj) in xobjs.iter() {
+    let Ok(id) = obj.as_reference() else { continue };
+    if let Ok(Object::Stream(s)) = doc.get_object(id) {
+      if s.dict.get(b"Subtype").and_then(|o| o.as_name()).map(|n| n == b"Image").unwrap_or(false) {
+        out.insert(name.clone(), id);
+      }
+    }
+  }
+  out
+}
+
+/// 图片在页面上的一次绘制：绘制时的 CTM
+pub(crate) struct ImagePlacement {
+  pub ctm: [f32; 6],
+}
+
+impl ImagePlacement {
+  /// 单位正方形经 CTM 变换后的面积（pt²）
+  pub fn area(&self) -> f32 {
+    let [a, b, c, d, _, _] = self.ctm;
+    (a * d - b * c).abs()
+  }
+
+  /// 单位正方形经 CTM 变换后的外框 [左, 下, 右, 上]
+  pub fn bbox(&self) -> [f32; 4] {
+    let [a, b, c, d, e, f] = self.ctm;
+    let xs = [e, a + e, c + e, a + c + e];
+    let ys = [f, b + f, d + f, b + d + f];
+    let lo = |v: [f32; 4]| v.into_iter().fold(f32::INFINITY, f32::min);
+    let hi = |v: [f32; 4]| v.into_iter().fold(f32::NEG_INFINITY, f32::max);
+    [lo(xs), lo(ys), hi(xs), hi(ys)]
+  }
+}
+
+pub(crate) const IDENTITY: [f32; 6] = [1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+
+/// 遍历内容流（q/Q/cm/Do），记录每次图片绘制的 CTM
+pub(crate) fn image_placements(doc: &Document, page_id: ObjectId) -> Vec<ImagePlacement> {
+  let images = page_images(doc, page_id);
+  if images.is_empty() { return Vec::new(); }
+  let Ok(content) = doc.get_and_decode_page_content(page_id) else { return Vec::new() };
+
+  let mut out = Vec::new();
+  let mut stack: Vec<[f32; 6]> = Vec::new();
+  let mut ctm = IDENTITY;
+  for op in &content.operations {
+    match op.operator.as_str() {
+      "q" => stack.push(ctm),
+      "Q" => ctm = stack.pop().unwrap_or(IDENTITY),
+      "cm" => if let Some(m) = matrix(&op.operands) { ctm = mul(&m, &ctm); },
+      "Do" => {
+        if op.operands.first().and_then(|o| o.as_name().ok()).is_some_and(|n| images.contains_key(n)) {
+          out.push(ImagePlacement { ctm });
+        }
+      }
+      _ => {}
+    }
+  }
+  out
+}
+
+pub(crate) fn matrix(operands: &[Object]) -> Option<[f32; 6]> {
+  if operands.len() != 6 { return None; }
+  let mut m = [0f32; 6];
+  for (slot, o) in m.iter_mut().zip(operands) { *slot = o.as_float().ok()?; }
+  Some(m)
+}
+
+/// 行向量约定：m × n
+pub(crate) fn mul(m: &[f32; 6], n: &[f32; 6]) -> [f32; 6] {
+  [
+    m[0] * n[0] + m[1] * n[2],
+    m[0] * n[1] + m[1] * n[3],
+    m[2] * n[0] + m[3] * n[2],
+    m[2] * n[1] + m[3] * n[3],
+    m[4] * n[0] + m[5] * n[2] + n[4],
+    m[4] * n[1] + m[5] * n[3] + n[5],
+  ]
+}
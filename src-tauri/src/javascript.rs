@@ -0,0 +1,118 @@
+//! Document JavaScript — list the /Names /JavaScript name tree and remove selected entries via lopdf
+use lopdf::{dictionary, Dictionary, Document, Object, StringFormat};
+use serde::Serialize;
+use std::collections::HashSet;
+
+use crate::common::{assert_input_not_output, catalog_id, decode_text, load_pdf, save_pdf, InputOne};
+use crate::inspect::resolve;
+use crate::naming::resolve_output;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsEntry {
+  pub name: String,
+  /// 动作的 /JS（字符串或流）；不是 JavaScript 动作时为空
+  pub source: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoveJsResult {
+  pub path: String,
+  pub removed: Vec<String>,
+  /// 保留下来的脚本名
+  pub remaining: Vec<String>,
+}
+
+/// 文档级命名脚本（名称树顺序），供用户审阅后决定保留哪些
+#[tauri::command]
+pub async fn list_javascript(input: InputOne) -> Result<Vec<JsEntry>, String> {
+  let doc = load_pdf(&input)?;
+  Ok(js_entries(&doc).iter().map(|(key, action)| JsEntry { name: decode_text(key), source: js_source(&doc, action) }).collect())
+}
+
+/// 只删掉 names 里列出的脚本，其余原样保留；有不存在的名称时报错且不写出
+#[tauri::command]
+pub async fn remove_javascript(input: InputOne, output: String, names: Vec<String>) -> Result<RemoveJsResult, String> {
+  let output = resolve_output(&output, "remove_js", &[(&input).into()])?;
+  if names.is_empty() { return Err("请指定要删除的脚本名".into()); }
+  assert_input_not_output(&input, &output)?;
+  let mut doc = load_pdf(&input)?;
+  let entries = js_entries(&doc);
+  let present: HashSet<String> = entries.iter().map(|(k, _)| decode_text(k)).collect();
+  let missing: Vec<&str> = names.iter().map(String::as_str).filter(|n| !present.contains(*n)).collect();
+  if !missing.is_empty() { return Err(format!("未找到脚本：{}", missing.join("、"))); }
+
+  let targets: HashSet<&str> = names.iter().map(String::as_str).collect();
+  let (mut removed, mut kept) = (Vec::new(), Vec::new());
+  for (key, action) in entries {
+    let name = decode_text(&key);
+    if targets.contains(name.as_str()) { removed.push(name); } else { kept.push((key, action)); }
+  }
+  let remaining = kept.iter().map(|(k, _)| decode_text(k)).collect();
+  write_js_tree(&mut doc, kept)?;
+  doc.prune_objects(); // 删掉的动作及其 /JS 流不再被引用
+  save_pdf(&mut doc, &output)?;
+  Ok(RemoveJsResult { path: output, removed, remaining })
+}
+
+/// 展开 /Names /JavaScript 名称树为 (键, 值) 列表
+fn js_entries(doc: &Document) -> Vec<(Vec<u8>, Object)> {
+  let mut out = Vec::new();
+  let Some(tree) = names_dict(doc).and_then(|n| n.get(b"JavaScript").ok()).and_then(|o| resolve(doc, o).as_dict().ok()) else { return out };
+  collect(doc, tree, &mut out, 0);
+  out
+}
+
+fn collect(doc: &Document, node: &Dictionary, out: &mut Vec<(Vec<u8>, Object)>, depth: u8) {
+  if depth > 32 { return; }
+  if let Ok(names) = node.get(b"Names").and_then(|o| resolve(doc, o).as_array()) {
+    for pair in names.chunks(2) {
+      if let [k, v] = pair {
+        if let Ok(key) = resolve(doc, k).as_str() { out.push((key.to_vec(), v.clone())); }
+      }
+    }
+  }
+  let Ok(kids) = node.get(b"Kids").and_then(|o| resolve(doc, o).as_array()) else { return };
+  for kid in kids.iter().filter_map(|k| resolve(doc, k).as_dict().ok()) { collect(doc, kid, out, depth + 1); }
+}
+
+fn names_dict(doc: &Document) -> Option<&Dictionary> {
+  doc.catalog().ok()?.get(b"Names").ok().and_then(|o| resolve(doc, o).as_dict().ok())
+}
+
+fn js_source(doc: &Document, action: &Object) -> String {
+  let Ok(dict) = resolve(doc, action).as_dict() else { return String::new() };
+  match dict.get(b"JS").map(|o| resolve(doc, o)) {
+    Ok(Object::String(bytes, _)) => decode_text(bytes),
+    Ok(Object::Stream(s)) => decode_text(&s.decompressed_content().unwrap_or_else(|_| s.content.clone())),
+    _ => String::new(),
+  }
+}
+
+/// 用保留的条目重建成单层名称树（键排序）；一个不剩时去掉 /JavaScript，/Names 也空了就一并去掉
+fn write_js_tree(doc: &mut Document, mut kept: Vec<(Vec<u8>, Object)>) -> Result<(), String> {
+  kept.sort_by(|a, b| a.0.cmp(&b.0));
+  let tree = (!kept.is_empty()).then(|| {
+    let names: Vec<Object> = kept.into_iter().flat_map(|(k, v)| [Object::String(k, StringFormat::Literal), v]).collect();
+    Object::Dictionary(dictionary! { "Names" => names })
+  });
+  let cat = catalog_id(doc)?;
+  let names_ref = doc.catalog().ok().and_then(|c| c.get(b"Names").ok()).and_then(|o| o.as_reference().ok());
+  let names = match names_ref {
+    Some(id) => doc.get_object_mut(id).ok().and_then(|o| o.as_dict_mut().ok()),
+    None => match doc.get_object_mut(cat) {
+      Ok(Object::Dictionary(c)) => c.get_mut(b"Names").ok().and_then(|o| o.as_dict_mut().ok()),
+      _ => return Err("文档目录不是字典".into()),
+    },
+  };
+  let Some(names) = names else { return Ok(()) };
+  match tree {
+    Some(t) => names.set("JavaScript", t),
+    None => { names.remove(b"JavaScript"); }
+  }
+  if names.is_empty() {
+    if let Ok(Object::Dictionary(c)) = doc.get_object_mut(cat) { c.remove(b"Names"); }
+  }
+  Ok(())
+}
@@ -0,0 +1,57 @@
+//! Input size limit — shared-install guardrail: reject oversized inputs at the entry of merge / compress / split / sign
+use once_cell::sync::Lazy;
+use std::{
+  fs,
+  sync::atomic::{AtomicU64, Ordering},
+};
+
+use crate::common::InputOne;
+
+/// 启动时读取的环境变量（字节数）；未设置或为 0 表示不限制
+const ENV_MAX_INPUT_BYTES: &str = "LUMENPDF_MAX_INPUT_BYTES";
+
+/// 0 = 不限制
+static MAX_INPUT_BYTES: Lazy<AtomicU64> = Lazy::new(|| {
+  AtomicU64::new(std::env::var(ENV_MAX_INPUT_BYTES).ok().and_then(|v| v.trim().parse().ok()).unwrap_or(0))
+});
+
+/// 当前上限；None 为不限制
+#[tauri::command]
+pub fn get_max_input_bytes() -> Option<u64> {
+  Some(MAX_INPUT_BYTES.load(Ordering::Relaxed)).filter(|&n| n > 0)
+}
+
+/// 运行时修改上限（覆盖环境变量的值，不持久化）；None 或 0 取消限制
+#[tauri::command]
+pub fn set_max_input_bytes(limit: Option<u64>) -> Option<u64> {
+  MAX_INPUT_BYTES.store(limit.unwrap_or(0), Ordering::Relaxed);
+  get_max_input_bytes()
+}
+
+/// 超限时的说明文字
+pub(crate) fn oversize_message(name: &str, bytes: u64) -> Option<String> {
+  get_max_input_bytes().filter(|&max| bytes > max).map(|max| format!("输入 {name} 为 {bytes} 字节，超过上限 {max} 字节"))
+}
+
+/// 超限返回 E_INVALID_ARG 开头的错误信息
+pub(crate) fn check_size(name: &str, bytes: u64) -> Result<(), String> {
+  match oversize_message(name, bytes) {
+    Some(m) => Err(format!("E_INVALID_ARG：{m}")),
+    None => Ok(()),
+  }
+}
+
+/// 路径按文件大小检查（读不到元数据时交给后续步骤报错）
+pub(crate) fn check_path(path: &str) -> Result<(), String> {
+  match fs::metadata(path) {
+    Ok(m) => check_size(path, m.len()),
+    Err(_) => Ok(()),
+  }
+}
+
+pub(crate) fn check_input(input: &InputOne) -> Result<(), String> {
+  match input {
+    InputOne::Path(p) => check_path(p),
+    InputOne::Bytes(pdf) => check_size(&pdf.name, pdf.data.len() as u64),
+  }
+}
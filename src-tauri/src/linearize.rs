@@ -0,0 +1,64 @@
+//! Linearize (fast web view) — qpdf --linearize + --check-linearization verification
+use tauri::AppHandle;
+use serde::Serialize;
+use std::fs;
+
+use crate::common::{
+  assert_input_not_output, in_place_path, qpdf_exe, run_qpdf, run_with_env, stage_input, work_dir, AtomicOutput,
+  InputOne,
+};
+use crate::naming::resolve_output;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinearizeResult {
+  pub path: String,
+  /// 输出经 --check-linearization 校验无错误
+  pub linearized: bool,
+}
+
+/// in_place 为 true 时忽略 output、覆盖原文件（仅路径入参），覆盖前备份为 <原文件>.bak
+#[tauri::command]
+pub async fn linearize(app: AppHandle, input: InputOne, output: String, in_place: Option<bool>) -> Result<LinearizeResult, String> {
+  if in_place.unwrap_or(false) {
+    let path = in_place_path(&input)?.to_string();
+    let out = AtomicOutput::new(&path)?;
+    run_qpdf(&app, &["--linearize".into(), path.clone(), out.path()])?;
+    out.commit_in_place()?;
+    let linearized = is_linearized(&app, &path)?;
+    return Ok(LinearizeResult { path, linearized });
+  }
+  let output = resolve_output(&output, "linearize", &[(&input).into()])?;
+  assert_input_not_output(&input, &output)?;
+  let out = AtomicOutput::new(&output)?;
+  let work = work_dir(&app, "linearize")?;
+  let res = stage_input(&work, &input).and_then(|in_path| {
+    run_qpdf(&app, &["--linearize".into(), in_path, out.path()]).map(|_| ())
+  });
+  let _ = fs::remove_dir_all(&work);
+  res?;
+  out.commit()?;
+  let linearized = is_linearized(&app, &output)?;
+  Ok(LinearizeResult { path: output, linearized })
+}
+
+#[tauri::command]
+pub async fn check_linearized(app: AppHandle, input: InputOne) -> Result<bool, String> {
+  let work = work_dir(&app, "check_linearized")?;
+  let res = stage_input(&work, &input).and_then(|p| is_linearized(&app, &p));
+  let _ = fs::remove_dir_all(&work);
+  res
+}
+
+/// qpdf 对未线性化的文件也返回 0（只打印 "is not linearized"），需结合输出判断；
+/// 线性化但有错误时返回 2，只有警告时返回 3
+pub(crate) fn is_linearized(app: &AppHandle, path: &str) -> Result<bool, String> {
+  let (bin_dir, exe) = qpdf_exe(app)?;
+  let out = run_with_env(&bin_dir, &exe, &["--check-linearization".into(), path.into()], &[])?;
+  let stdout = String::from_utf8_lossy(&out.stdout);
+  match out.status.code() {
+    Some(0) => Ok(stdout.contains("no linearization errors")),
+    Some(2) | Some(3) => Ok(false),
+    _ => Err(format!("qpdf 校验线性化失败：{}", String::from_utf8_lossy(&out.stderr))),
+  }
+}
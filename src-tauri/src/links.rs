@@ -0,0 +1,186 @@
+//! Links — list link annotations (external /URI vs. internal GoTo) & rewrite / strip URI actions via lopdf
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::common::{assert_input_not_output, decode_text, load_pdf, save_pdf, InputOne};
+use crate::inspect::resolve;
+use crate::naming::resolve_output;
+use crate::outline::dest_page;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkInfo {
+  pub page: u32,
+  pub uri: String,
+  /// 注释矩形 [x0, y0, x1, y1]（pt）
+  pub rect: [f32; 4],
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InternalLink {
+  pub page: u32,
+  /// 目标页（1 基）；命名目标解析不到时为 None
+  pub target_page: Option<u32>,
+  pub rect: [f32; 4],
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkList {
+  pub external: Vec<LinkInfo>,
+  pub internal: Vec<InternalLink>,
+}
+
+#[tauri::command]
+pub async fn list_links(input: InputOne) -> Result<LinkList, String> {
+  let doc = load_pdf(&input)?;
+  let page_nos: BTreeMap<ObjectId, u32> = doc.get_pages().into_iter().map(|(no, id)| (id, no)).collect();
+  let mut list = LinkList { external: Vec::new(), internal: Vec::new() };
+  for (no, page_id) in doc.get_pages() {
+    for annot in link_annots(&doc, page_id) {
+      let rect = annot_rect(&doc, annot);
+      let action = annot.get(b"A").ok().and_then(|o| resolve(&doc, o).as_dict().ok());
+      let kind = action.and_then(|a| a.get(b"S").ok()).and_then(|o| o.as_name().ok());
+      match (kind, action) {
+        (Some(b"URI"), Some(a)) => {
+          let Some(uri) = a.get(b"URI").ok().and_then(|o| resolve(&doc, o).as_str().ok()) else { continue };
+          list.external.push(LinkInfo { page: no, uri: decode_text(uri), rect });
+        }
+        (Some(b"GoTo"), Some(a)) => {
+          let target_page = a.get(b"D").ok().and_then(|d| dest_page(&doc, resolve(&doc, d), &page_nos, 0));
+          list.internal.push(InternalLink { page: no, target_page, rect });
+        }
+        (None, _) => {
+          if let Ok(d) = annot.get(b"Dest") {
+            list.internal.push(InternalLink { page: no, target_page: dest_page(&doc, resolve(&doc, d), &page_nos, 0), rect });
+          }
+        }
+        _ => {}
+      }
+    }
+  }
+  Ok(list)
+}
+
+/// URI 替换规则：regex 为 true 时 find 按正则处理，replace 可用 $1 引用分组
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UrlRule {
+  pub find: String,
+  pub replace: String,
+  #[serde(default)]
+  pub regex: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RewriteReport {
+  pub path: String,
+  /// 改写的 URI 动作数
+  pub rewritten: u32,
+  /// 删除的外链注释数（strip_all）
+  pub removed: u32,
+}
+
+enum Matcher { Plain(String), Regex(Regex) }
+
+/// 改写全文所有 /S /URI 动作（含书签、OpenAction 里的）；strip_all 时删除全部外链注释，规则不再生效
+#[tauri::command]
+pub async fn rewrite_links(input: InputOne, output: String, rules: Vec<UrlRule>, strip_all: bool) -> Result<RewriteReport, String> {
+  let output = resolve_output(&output, "links", &[(&input).into()])?;
+  if rules.is_empty() && !strip_all { return Err("请提供至少一条替换规则或开启全部移除".into()); }
+  let matchers: Vec<(Matcher, &str)> = rules.iter().map(|r| {
+    let m = if r.regex {
+      Matcher::Regex(Regex::new(&r.find).map_err(|e| format!("正则无效（{}）：{e}", r.find))?)
+    } else {
+      if r.find.is_empty() { return Err("查找内容不能为空".to_string()); }
+      Matcher::Plain(r.find.clone())
+    };
+    Ok((m, r.replace.as_str()))
+  }).collect::<Result<_, String>>()?;
+  assert_input_not_output(&input, &output)?;
+  let mut doc = load_pdf(&input)?;
+
+  let (mut rewritten, mut removed) = (0u32, 0u32);
+  if strip_all {
+    for page_id in doc.get_pages().into_values() { removed += strip_uri_annots(&mut doc, page_id)?; }
+  } else {
+    for obj in doc.objects.values_mut() { rewrite_uris(obj, &matchers, &mut rewritten); }
+  }
+
+  save_pdf(&mut doc, &output)?;
+  Ok(RewriteReport { path: output, rewritten, removed })
+}
+
+fn rewrite_uris(obj: &mut Object, matchers: &[(Matcher, &str)], count: &mut u32) {
+  match obj {
+    Object::Dictionary(d) => {
+      let is_uri = d.get(b"S").and_then(|o| o.as_name()).map(|n| n == b"URI").unwrap_or(false);
+      if is_uri {
+        if let Ok(old) = d.get(b"URI").and_then(|o| o.as_str()).map(decode_text) {
+          let new = matchers.iter().fold(old.clone(), |acc, (m, rep)| match m {
+            Matcher::Plain(find) => acc.replace(find.as_str(), rep),
+            Matcher::Regex(re) => re.replace_all(&acc, *rep).into_owned(),
+          });
+          if new != old { d.set("URI", Object::string_literal(new)); *count += 1; }
+        }
+      }
+      for (_, v) in d.iter_mut() { rewrite_uris(v, matchers, count); }
+    }
+    Object::Array(a) => for v in a.iter_mut() { rewrite_uris(v, matchers, count); },
+    Object::Stream(s) => for (_, v) in s.dict.iter_mut() { rewrite_uris(v, matchers, count); },
+    _ => {}
+  }
+}
+
+/// 从页面 /Annots 删除 URI 链接注释，返回删除数
+fn strip_uri_annots(doc: &mut Document, page_id: ObjectId) -> Result<u32, String> {
+  let Some(annots) = doc.get_dictionary(page_id).ok()
+    .and_then(|p| p.get(b"Annots").ok())
+    .and_then(|o| resolve(doc, o).as_array().ok()) else { return Ok(0) };
+  let keep: Vec<Object> = annots.iter().filter(|a| !is_uri_link(doc, a)).cloned().collect();
+  let removed = (annots.len() - keep.len()) as u32;
+  if removed == 0 { return Ok(0); }
+  match doc.get_object_mut(page_id) {
+    Ok(Object::Dictionary(page)) => {
+      if keep.is_empty() { page.remove(b"Annots"); } else { page.set("Annots", keep); }
+      Ok(removed)
+    }
+    _ => Err("页面对象不是字典".into()),
+  }
+}
+
+fn is_uri_link(doc: &Document, annot: &Object) -> bool {
+  let Ok(a) = resolve(doc, annot).as_dict() else { return false };
+  a.get(b"Subtype").and_then(|o| o.as_name()).map(|n| n == b"Link").unwrap_or(false)
+    && a.get(b"A").ok()
+      .and_then(|o| resolve(doc, o).as_dict().ok())
+      .and_then(|act| act.get(b"S").ok())
+      .and_then(|o| o.as_name().ok())
+      .is_some_and(|n| n == b"URI")
+}
+
+/// 页面 /Annots 中 Subtype 为 Link 的注释
+pub(crate) fn link_annots(doc: &Document, page_id: ObjectId) -> Vec<&Dictionary> {
+  let Some(annots) = doc.get_dictionary(page_id).ok()
+    .and_then(|p| p.get(b"Annots").ok())
+    .and_then(|o| resolve(doc, o).as_array().ok()) else { return Vec::new() };
+  annots.iter()
+    .filter_map(|a| resolve(doc, a).as_dict().ok())
+    .filter(|a| a.get(b"Subtype").and_then(|o| o.as_name()).map(|n| n == b"Link").unwrap_or(false))
+    .collect()
+}
+
+fn annot_rect(doc: &Document, annot: &Dictionary) -> [f32; 4] {
+  let v: Vec<f32> = annot.get(b"Rect").ok()
+    .and_then(|o| resolve(doc, o).as_array().ok())
+    .map(|a| a.iter().filter_map(|o| resolve(doc, o).as_float().ok()).collect())
+    .unwrap_or_default();
+  match v.as_slice() {
+    [x0, y0, x1, y1] => [x0.min(*x1), y0.min(*y1), x0.max(*x1), y0.max(*y1)],
+    _ => [0.0; 4],
+  }
+}
@@ -1,9 +1,50 @@
 // src-tauri/src/main.rs
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod common;
 mod merge;
 mod compress;
 mod sign; // ✅ 新增：签名导出模块（含 #[tauri::command] sign_and_export_pdf）
+mod pages;
+mod color;
+mod split;
+mod inspect;
+mod images;
+mod outline;
+mod render;
+mod scan;
+mod a11y;
+mod verify;
+mod stamp;
+mod contact;
+mod assemble;
+mod links;
+mod impose;
+mod breakdown;
+mod linearize;
+mod security;
+mod burst;
+mod diagnose;
+mod tiff;
+mod orient;
+mod textlayer;
+mod cover;
+mod phash;
+mod limits;
+mod compare;
+mod redact;
+mod manifest;
+mod forms;
+mod flatten;
+mod barcode;
+mod queue;
+mod fingerprint;
+mod javascript;
+mod producer;
+mod naming;
+mod pagetree;
+mod preview;
+mod pageinfo;
 
 fn main() {
   tauri::Builder::default()
@@ -16,6 +57,82 @@ fn main() {
       merge::merge,
       compress::compress,
       sign::sign_and_export_pdf, // ✅ 注册签名导出命令
+      pages::add_blank_page,
+      color::convert_colorspace,
+      color::embed_icc,
+      color::convert_pdfx,
+      split::split_pdf,
+      split::split_and_compress,
+      inspect::classify_pdf,
+      images::list_images,
+      images::remove_image,
+      scan::detect_inverted,
+      scan::invert_pages,
+      a11y::accessibility_report,
+      a11y::set_a11y_basics,
+      verify::verify_signatures,
+      split::split_by_text,
+      stamp::stamp_pdf,
+      inspect::size_summary,
+      contact::contact_sheet,
+      assemble::assemble,
+      links::list_links,
+      links::rewrite_links,
+      impose::make_booklet,
+      breakdown::size_breakdown,
+      split::split_fixed_named,
+      linearize::linearize,
+      linearize::check_linearized,
+      pages::set_print_boxes,
+      security::encryption_info,
+      burst::burst_statements,
+      diagnose::diagnose,
+      tiff::to_tiff,
+      split::split_by_color,
+      compress::compress_to_target,
+      orient::auto_rotate,
+      textlayer::text_layout,
+      phash::perceptual_hash,
+      limits::get_max_input_bytes,
+      limits::set_max_input_bytes,
+      compare::compare_side_by_side,
+      redact::redact_regions,
+      security::update_permissions,
+      forms::list_form_fields,
+      forms::fill_form,
+      flatten::flatten_to_images,
+      scan::optimize_scan,
+      barcode::stamp_barcode,
+      forms::find_signature_fields,
+      forms::set_signature_appearance,
+      pages::autocrop,
+      contact::sprite_sheet,
+      color::print_estimate,
+      images::get_image,
+      split::split_from_csv,
+      inspect::text_quality,
+      split::split_by_size_change,
+      queue::enqueue,
+      queue::cancel_job,
+      split::split_and_export,
+      fingerprint::embed_fingerprint,
+      fingerprint::read_fingerprint,
+      inspect::print_orientation_hints,
+      javascript::list_javascript,
+      javascript::remove_javascript,
+      assemble::assemble_scans,
+      inspect::font_compliance,
+      producer::set_producer,
+      color::spot_color_report,
+      manifest::manifest_dir,
+      pages::rescale_pages,
+      pagetree::repair_page_tree,
+      outline::export_outline,
+      compress::compress_ranges,
+      inspect::content_density,
+      preview::make_preview,
+      pageinfo::page_manifest,
+      queue::cancel_batch,
     ])
     .run(tauri::generate_context!())
     .expect("error while running tauri application");
@@ -0,0 +1,121 @@
+//! Manifest — manifest.json provenance record next to merge / split outputs (inputs + hashes, outputs, time, engine versions), SHA256SUMS for output directories
+use tauri::AppHandle;
+use serde::Serialize;
+use std::{
+  fs,
+  path::Path,
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::common::{ensure_parent_dir, gs_exe, hex_sha256, page_count, qpdf_exe, run_with_env};
+
+pub(crate) const MANIFEST_NAME: &str = "manifest.json";
+const SUMS_NAME: &str = "SHA256SUMS";
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestFile {
+  /// 输入为原路径或上传文件名；输出为完整路径
+  pub name: String,
+  pub sha256: String,
+  pub bytes: u64,
+  pub page_count: u32,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Engines {
+  pub app: String,
+  /// 如 "qpdf version 11.9.0"
+  pub qpdf: Option<String>,
+  pub ghostscript: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Manifest {
+  /// "merge" / "split"
+  pub operation: &'static str,
+  /// UTC，ISO 8601
+  pub created_at: String,
+  pub inputs: Vec<ManifestFile>,
+  pub outputs: Vec<ManifestFile>,
+  pub engines: Engines,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileHash {
+  /// 目录内的文件名（不含路径）
+  pub filename: String,
+  pub bytes: u64,
+  pub sha256: String,
+}
+
+/// 目录下（不递归）每个 PDF 的大小与 SHA-256，按文件名排序；write_sums 时另写 dir/SHA256SUMS，可用 sha256sum -c 校验
+#[tauri::command]
+pub async fn manifest_dir(dir: String, write_sums: Option<bool>) -> Result<Vec<FileHash>, String> {
+  let entries = fs::read_dir(&dir).map_err(|e| format!("读取目录失败：{e}（{dir}）"))?;
+  let mut files = Vec::new();
+  for entry in entries {
+    let path = entry.map_err(|e| format!("读取目录失败：{e}（{dir}）"))?.path();
+    let is_pdf = path.extension().is_some_and(|x| x.eq_ignore_ascii_case("pdf"));
+    if !is_pdf || !path.is_file() { continue; }
+    let data = fs::read(&path).map_err(|e| format!("读取文件失败：{e}（{}）", path.display()))?;
+    let filename = path.file_name().map(|f| f.to_string_lossy().to_string()).unwrap_or_default();
+    files.push(FileHash { filename, bytes: data.len() as u64, sha256: hex_sha256(&data) });
+  }
+  files.sort_by(|a, b| a.filename.cmp(&b.filename));
+  if write_sums.unwrap_or(false) {
+    let sums: String = files.iter().map(|f| format!("{}  {}\n", f.sha256, f.filename)).collect();
+    let path = Path::new(&dir).join(SUMS_NAME);
+    fs::write(&path, sums).map_err(|e| format!("写入校验文件失败：{e}（{}）", path.display()))?;
+  }
+  Ok(files)
+}
+
+/// name 为写入清单的名字，path 为实际读取的文件（字节版输入即临时文件）
+pub(crate) fn describe(app: &AppHandle, name: &str, path: &str) -> Result<ManifestFile, String> {
+  let data = fs::read(path).map_err(|e| format!("读取文件失败：{e}（{path}）"))?;
+  Ok(ManifestFile { name: name.to_string(), sha256: hex_sha256(&data), bytes: data.len() as u64, page_count: page_count(app, path)? })
+}
+
+/// 写到 dir/manifest.json（已存在则覆盖），返回清单路径
+pub(crate) fn write_manifest(app: &AppHandle, operation: &'static str, inputs: Vec<ManifestFile>, outputs: &[String], dir: &Path) -> Result<String, String> {
+  let outputs = outputs.iter().map(|p| describe(app, p, p)).collect::<Result<Vec<_>, _>>()?;
+  let manifest = Manifest { operation, created_at: utc_now(), inputs, outputs, engines: engines(app) };
+  let path = dir.join(MANIFEST_NAME).to_string_lossy().to_string();
+  ensure_parent_dir(&path)?;
+  let json = serde_json::to_vec_pretty(&manifest).map_err(|e| format!("序列化清单失败：{e}"))?;
+  fs::write(&path, json).map_err(|e| format!("写入清单失败：{e}（{path}）"))?;
+  Ok(path)
+}
+
+/// 引擎缺失时记为 None，不影响清单生成
+fn engines(app: &AppHandle) -> Engines {
+  let qpdf = qpdf_exe(app).ok().and_then(|(bin_dir, exe)| first_line(run_with_env(&bin_dir, &exe, &["--version".into()], &[]).ok()?));
+  let ghostscript = gs_exe(app).ok().and_then(|(bin_dir, exe, envs)| first_line(run_with_env(&bin_dir, &exe, &["--version".into()], &envs).ok()?));
+  Engines { app: env!("CARGO_PKG_VERSION").to_string(), qpdf, ghostscript }
+}
+
+fn first_line(out: std::process::Output) -> Option<String> {
+  if !out.status.success() { return None; }
+  String::from_utf8_lossy(&out.stdout).lines().map(str::trim).find(|l| !l.is_empty()).map(String::from)
+}
+
+/// 当前 UTC 时间，如 2024-05-01T08:30:00Z（公历换算，免引入日期库）
+pub(crate) fn utc_now() -> String {
+  let secs = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64;
+  let (days, rem) = (secs.div_euclid(86_400), secs.rem_euclid(86_400));
+  // Howard Hinnant civil_from_days
+  let z = days + 719_468;
+  let era = z.div_euclid(146_097);
+  let doe = z - era * 146_097;
+  let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+  let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+  let mp = (5 * doy + 2) / 153;
+  let day = doy - (153 * mp + 2) / 5 + 1;
+  let month = if mp < 10 { mp + 3 } else { mp - 9 };
+  let year = yoe + era * 400 + i64::from(month <= 2);
+  format!("{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z", rem / 3600, rem % 3600 / 60, rem % 60)
+}
@@ -1,13 +1,26 @@
-//! Merge PDFs — qpdf direct exec (support subdir layout, linearized)
+//! Merge PDFs — qpdf direct exec (support subdir layout, linearized by default)
 use tauri::{AppHandle, Manager};
-use serde::Deserialize;
+use lopdf::{dictionary, Object};
+use serde::{Deserialize, Serialize};
 use std::{
+  collections::{HashMap, HashSet},
   fs, ffi::OsStr,
   path::{Path, PathBuf},
   process::Command,
   time::{SystemTime, UNIX_EPOCH},
 };
 
+use crate::common::{catalog_id, hex_sha256, page_count, parse_range, run_gs, save_pdf, work_dir, AtomicOutput};
+use crate::compress::{gs_dedupe, verify_pdf};
+use crate::cover::{render_cover, CoverSpec};
+use crate::inspect::{page_rect, resolve};
+use crate::limits::{check_path, check_size};
+use crate::linearize::is_linearized;
+use crate::manifest::{describe, write_manifest};
+use crate::naming::{resolve_output, NamingSource};
+use crate::pages::PageSize;
+use crate::producer::{producer_label, stamp_producer};
+
 #[derive(Deserialize, Clone)]
 pub struct PdfIn { pub name: String, pub data: Vec<u8> }
 
@@ -18,34 +31,518 @@ pub enum Inputs {
   Bytes(Vec<PdfIn>),
 }
 
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeOptions {
+  /// 按内容 SHA-256 去掉完全相同的输入，保留首次出现
+  #[serde(default)]
+  pub dedupe_inputs: bool,
+  /// 完全指定输出页序：[(输入下标, qpdf 页范围)]，如 [[0,"1-3"],[1,"1"],[0,"4-z"]]；
+  /// 给出时不再按输入逐份全页拼接，且允许只有一个输入
+  #[serde(default)]
+  pub order: Option<Vec<(usize, String)>>,
+  /// 在最前面插入生成的封面页
+  #[serde(default)]
+  pub cover: Option<CoverSpec>,
+  /// 在输出旁写 manifest.json（输入哈希 / 页数、输出、时间、引擎版本）
+  #[serde(default)]
+  pub manifest: bool,
+  /// 各输入首页 MediaBox 尺寸须一致（±1pt），否则报 E_INVALID_ARG 并列出不一致的文件
+  #[serde(default)]
+  pub require_uniform_size: bool,
+  /// 合并后的 /PageLabels 改为从 1 起连续的一段（各章节自带的编号不再重复）
+  #[serde(default)]
+  pub renumber_labels: bool,
+  /// renumber_labels 的编号样式，默认阿拉伯数字
+  #[serde(default)]
+  pub label_style: LabelStyle,
+  /// 每个输入（含封面）先经 Ghostscript 缩放居中到该纸张再拼接，输出页面尺寸统一
+  #[serde(default)]
+  pub unify_size: Option<PageSize>,
+  /// 同 unify_size，纸张取各输入中 MediaBox 面积最大的一页；与 unify_size 二选一
+  #[serde(default)]
+  pub unify_to_largest: bool,
+  /// 合并后经 Ghostscript 折叠各输入里重复嵌入的图片 / 字体（不降采样），结果更小才采用
+  #[serde(default)]
+  pub optimize: bool,
+  /// qpdf 拼接时一并线性化（网页快速查看）并经 --check-linearization 校验；默认 true，false 时跳过线性化
+  #[serde(default)]
+  pub linearize: Option<bool>,
+  /// 合并后核对注释 / 表单控件数：各输入选中页合计 vs 输出，少了在结果里标出
+  #[serde(default)]
+  pub verify_annotations: bool,
+  /// 输出的 /Producer /Creator（含 XMP）改为该值，空串时用应用名；省略则保留引擎写入的值
+  #[serde(default)]
+  pub producer: Option<String>,
+  /// 每份输入前插入一张写有其名称的分隔页（A4，排版同封面）
+  #[serde(default)]
+  pub separator: Option<SeparatorSpec>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SeparatorSpec {
+  /// 与 inputs 同序的标签；省略、不足或为空串时用源文件名（不含扩展名）
+  #[serde(default)]
+  pub labels: Vec<String>,
+  /// 第一份输入前不插分隔页
+  #[serde(default)]
+  pub skip_first: bool,
+}
+
+impl MergeOptions {
+  fn linearize(&self) -> bool { self.linearize.unwrap_or(true) }
+}
+
+#[derive(Deserialize, Clone, Copy, Default, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum LabelStyle {
+  #[default]
+  Decimal,
+  UpperRoman,
+  LowerRoman,
+  UpperAlpha,
+  LowerAlpha,
+}
+
+impl LabelStyle {
+  /// /PageLabels 的 /S 取值
+  fn code(self) -> &'static str {
+    match self {
+      LabelStyle::Decimal => "D",
+      LabelStyle::UpperRoman => "R",
+      LabelStyle::LowerRoman => "r",
+      LabelStyle::UpperAlpha => "A",
+      LabelStyle::LowerAlpha => "a",
+    }
+  }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedInput {
+  /// 在原输入列表中的下标
+  pub index: usize,
+  /// 路径版为路径，字节版为文件名
+  pub name: String,
+  /// 与之重复的首次出现下标
+  pub duplicate_of: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeResult {
+  pub path: String,
+  pub skipped: Vec<SkippedInput>,
+  /// 开启 manifest 时为清单路径
+  pub manifest: Option<String>,
+  /// 开启 optimize 时的去重前后体积
+  pub optimized: Option<SizeReduction>,
+  /// 开启 verify_annotations 时的核对结果
+  pub annotation_check: Option<AnnotationCheck>,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotCount {
+  /// 页面 /Annots 条目总数（含表单控件）
+  pub annotations: u32,
+  /// 其中的表单控件（/Subtype /Widget）
+  pub widgets: u32,
+}
+
+impl AnnotCount {
+  fn add(&mut self, other: AnnotCount) {
+    self.annotations += other.annotations;
+    self.widgets += other.widgets;
+  }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotationCheck {
+  /// 各输入选中页的合计（封面不计）
+  pub expected: AnnotCount,
+  pub actual: AnnotCount,
+  /// 输出少于预期：合并过程中丢了注释或表单控件
+  pub lost: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SizeReduction {
+  pub bytes_before: u64,
+  /// 去重无收益（或 Ghostscript 不可用）时等于 bytes_before，输出为原合并结果
+  pub bytes_after: u64,
+}
+
 #[tauri::command]
-pub async fn merge(app: AppHandle, inputs: Inputs, output: String) -> Result<String, String> {
-  ensure_parent_dir(&output)?;
+pub async fn merge(app: AppHandle, inputs: Inputs, output: String, options: Option<MergeOptions>) -> Result<MergeResult, String> {
+  let opts = options.unwrap_or_default();
+  if opts.unify_size.is_some() && opts.unify_to_largest { return Err("unifySize 与 unifyToLargest 只能二选一".into()); }
+  // 指定页序时单个输入也可（同一文件内重排）
+  let min = if opts.order.is_some() { 1 } else { 2 };
+  let output = {
+    let sources: Vec<NamingSource> = match &inputs {
+      Inputs::Paths(paths) => paths.iter().map(|p| NamingSource::Path(p.as_str())).collect(),
+      Inputs::Bytes(items) => items.iter().map(|p| NamingSource::Bytes { name: &p.name, data: &p.data }).collect(),
+    };
+    resolve_output(&output, "merge", &sources)?
+  };
+  // 分隔页标签按原始输入下标取，须在去重前记下各输入名称
+  let display: Vec<String> = match (&inputs, opts.separator.is_some()) {
+    (_, false) => Vec::new(),
+    (Inputs::Paths(paths), true) => paths.clone(),
+    (Inputs::Bytes(items), true) => items.iter().map(|p| p.name.clone()).collect(),
+  };
   match inputs {
     Inputs::Paths(paths) => {
-      if paths.len() < 2 { return Err("请选择至少两个 PDF（路径版）".into()); }
+      if paths.len() < min { return Err(format!("请选择至少 {min} 个 PDF（路径版）")); }
+      paths.iter().try_for_each(|p| check_path(p))?;
       assert_output_not_in_inputs(&paths, &output)?;
-      let args = build_args_merge_paths(&paths, &output);
-      run_qpdf(&app, &args).await?;
-      Ok(output)
+      let total = paths.len();
+      let (paths, skipped) = if opts.dedupe_inputs {
+        let hashes = paths.iter()
+          .map(|p| fs::read(p).map(|d| hex_sha256(&d)).map_err(|e| format!("读取输入失败：{e}（{p}）")))
+          .collect::<Result<Vec<_>, _>>()?;
+        dedupe(paths, &hashes, |p| p.clone())
+      } else { (paths, Vec::new()) };
+      if paths.len() < min { return Err(format!("去重后不足 {min} 个 PDF")); }
+      if opts.require_uniform_size {
+        check_uniform_size(paths.iter().map(|p| (p.as_str(), lopdf::Document::load(p))))?;
+      }
+      let positions = kept_positions(total, &skipped);
+      let segments = plan_segments(&app, &paths, opts.order.as_deref(), &positions)?;
+      let expected = expected_annotations(&segments, &opts)?;
+      let labels = separator_labels(opts.separator.as_ref(), &paths, &display, &positions);
+      let optimized = write_merged(&app, segments, &opts, &output, &labels).await?;
+      let manifest = if opts.manifest { Some(merge_manifest(&app, &paths, &paths, &output)?) } else { None };
+      let annotation_check = expected.map(|e| check_annotations(e, &output)).transpose()?;
+      Ok(MergeResult { path: output, skipped, manifest, optimized, annotation_check })
     }
     Inputs::Bytes(items) => {
-      if items.len() < 2 { return Err("请选择至少两个 PDF（字节版）".into()); }
+      if items.len() < min { return Err(format!("请选择至少 {min} 个 PDF（字节版）")); }
+      items.iter().try_for_each(|p| check_size(&p.name, p.data.len() as u64))?;
+      let total = items.len();
+      let (items, skipped) = if opts.dedupe_inputs {
+        let hashes: Vec<String> = items.iter().map(|p| hex_sha256(&p.data)).collect();
+        dedupe(items, &hashes, |p| p.name.clone())
+      } else { (items, Vec::new()) };
+      if items.len() < min { return Err(format!("去重后不足 {min} 个 PDF")); }
+      if opts.require_uniform_size {
+        check_uniform_size(items.iter().map(|p| (p.name.as_str(), lopdf::Document::load_mem(&p.data))))?;
+      }
       let (work, paths) = write_temp_pdfs(&app, &items)?;
-      assert_output_not_in_inputs(&paths, &output)?;
-      let args = build_args_merge_paths(&paths, &output);
-      let res = run_qpdf(&app, &args).await;
+      let positions = kept_positions(total, &skipped);
+      let labels = separator_labels(opts.separator.as_ref(), &paths, &display, &positions);
+      let res = match assert_output_not_in_inputs(&paths, &output)
+        .and_then(|_| plan_segments(&app, &paths, opts.order.as_deref(), &positions)) {
+        Ok(segments) => match expected_annotations(&segments, &opts) {
+          Ok(expected) => write_merged(&app, segments, &opts, &output, &labels).await.map(|o| (o, expected)),
+          Err(e) => Err(e),
+        },
+        Err(e) => Err(e),
+      };
+      // 清单要在临时文件删除前算哈希
+      let res = res.and_then(|(optimized, expected)| {
+        if !opts.manifest { return Ok((None, optimized, expected)); }
+        let names: Vec<String> = items.iter().map(|p| p.name.clone()).collect();
+        merge_manifest(&app, &names, &paths, &output).map(|m| (Some(m), optimized, expected))
+      });
       let _ = fs::remove_dir_all(&work);
-      res.map(|_| output)
+      let (manifest, optimized, expected) = res?;
+      let annotation_check = expected.map(|e| check_annotations(e, &output)).transpose()?;
+      Ok(MergeResult { path: output, skipped, manifest, optimized, annotation_check })
     }
   }
 }
 
-fn ensure_parent_dir(output: &str) -> Result<(), String> {
-  if let Some(parent) = Path::new(output).parent() {
-    fs::create_dir_all(parent).map_err(|e| format!("创建输出目录失败：{e}"))?;
+/// 可选分隔页、封面放在最前、可选统一尺寸，qpdf 拼接后原子写出；labels 为 文件 → 分隔页标签
+async fn write_merged(app: &AppHandle, segments: Vec<(String, String)>, opts: &MergeOptions, output: &str, labels: &HashMap<String, String>) -> Result<Option<SizeReduction>, String> {
+  if opts.cover.is_none() && opts.unify_size.is_none() && !opts.unify_to_largest && opts.separator.is_none() {
+    return write_segments(app, &segments, opts, output).await;
   }
-  Ok(())
+  let work = work_dir(app, "merge_prep")?;
+  let res = match prepare_segments(app, &work, segments, opts, labels) {
+    Ok(segments) => write_segments(app, &segments, opts, output).await,
+    Err(e) => Err(e),
+  };
+  let _ = fs::remove_dir_all(&work);
+  res
+}
+
+/// 分隔页、封面渲染到 work；统一尺寸时每个不同的输入文件各生成一份缩放后的临时副本（页数不变，页范围照用）
+fn prepare_segments(app: &AppHandle, work: &Path, mut segments: Vec<(String, String)>, opts: &MergeOptions, labels: &HashMap<String, String>) -> Result<Vec<(String, String)>, String> {
+  // 最大页只在原输入里找，不算后插的封面 / 分隔页
+  let target = match opts.unify_size {
+    Some(size) => Some(size),
+    None if opts.unify_to_largest => Some(largest_page_size(&segments)?),
+    None => None,
+  };
+  if let Some(spec) = opts.separator.as_ref() {
+    segments = insert_separators(app, work, segments, spec, labels)?;
+  }
+  if let Some(spec) = opts.cover.as_ref() {
+    let path = work.join("cover.pdf").to_string_lossy().to_string();
+    render_cover(app, spec, &path)?;
+    segments.insert(0, (path, "1-z".into()));
+  }
+  let Some(size) = target else { return Ok(segments) };
+  let mut fitted = HashMap::<String, String>::new();
+  for (path, _) in segments.iter_mut() {
+    if let Some(done) = fitted.get(path.as_str()) { *path = done.clone(); continue; }
+    let out = work.join(format!("fit_{}.pdf", fitted.len())).to_string_lossy().to_string();
+    fit_to_size(app, path, &out, size)?;
+    fitted.insert(std::mem::replace(path, out.clone()), out);
+  }
+  Ok(segments)
+}
+
+/// 片段换到另一个文件时在其前插入分隔页（指定 order 时同一文件被别的文件隔开后再出现也会再插一次）
+fn insert_separators(app: &AppHandle, work: &Path, segments: Vec<(String, String)>, spec: &SeparatorSpec, labels: &HashMap<String, String>) -> Result<Vec<(String, String)>, String> {
+  let mut out = Vec::with_capacity(segments.len() * 2);
+  let mut prev: Option<String> = None;
+  for (path, range) in segments {
+    if prev.as_deref() != Some(path.as_str()) && !(prev.is_none() && spec.skip_first) {
+      let title = labels.get(&path).cloned().unwrap_or_else(|| file_stem(&path));
+      let sep = work.join(format!("separator_{}.pdf", out.len())).to_string_lossy().to_string();
+      render_cover(app, &CoverSpec { title, subtitle: None, date: None }, &sep).map_err(|e| format!("生成分隔页失败：{e}"))?;
+      out.push((sep, "1".into()));
+    }
+    prev = Some(path.clone());
+    out.push((path, range));
+  }
+  Ok(out)
+}
+
+/// 各（去重后的）输入文件 → 分隔页标签；重复输入以首次出现的下标为准
+fn separator_labels(spec: Option<&SeparatorSpec>, paths: &[String], display: &[String], positions: &[usize]) -> HashMap<String, String> {
+  let mut labels = HashMap::new();
+  let Some(spec) = spec else { return labels };
+  for (idx, &pos) in positions.iter().enumerate() {
+    let label = spec.labels.get(idx).map(|l| l.trim()).filter(|l| !l.is_empty()).map(String::from)
+      .unwrap_or_else(|| display.get(idx).map(|d| file_stem(d)).unwrap_or_default());
+    labels.entry(paths[pos].clone()).or_insert(label);
+  }
+  labels
+}
+
+fn file_stem(path: &str) -> String {
+  Path::new(path).file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_else(|| path.to_string())
+}
+
+/// 各片段所在文件全部页中 MediaBox 面积最大的一页的宽高（保持其方向）
+fn largest_page_size(segments: &[(String, String)]) -> Result<PageSize, String> {
+  let mut seen = HashSet::new();
+  let mut best: Option<(f32, f32)> = None;
+  for (path, _) in segments {
+    if !seen.insert(path.as_str()) { continue; }
+    let doc = lopdf::Document::load(path).map_err(|e| format!("解析 PDF 失败：{e}（{path}）"))?;
+    for (no, page_id) in doc.get_pages() {
+      let [x0, y0, x1, y1] = page_rect(&doc, page_id, b"MediaBox").ok_or_else(|| format!("第 {no} 页缺少 MediaBox：{path}"))?;
+      let (w, h) = ((x1 - x0).abs(), (y1 - y0).abs());
+      if best.is_none_or(|(bw, bh)| w * h > bw * bh) { best = Some((w, h)); }
+    }
+  }
+  let (width_pt, height_pt) = best.ok_or("没有可统一尺寸的页面")?;
+  Ok(PageSize::Custom { width_pt, height_pt })
+}
+
+/// gs pdfwrite 固定纸张 + PDFFitPage：等比缩放居中（方向不符时旋转），书签 / 表单等交互内容可能不保留
+fn fit_to_size(app: &AppHandle, input: &str, output: &str, size: PageSize) -> Result<(), String> {
+  let (w, h) = size.dims_pt();
+  if !(w > 0.0 && h > 0.0) { return Err(format!("无效页面尺寸：{w}×{h} pt")); }
+  let args: Vec<String> = vec![
+    "-sDEVICE=pdfwrite".into(),
+    "-dFIXEDMEDIA".into(),
+    "-dPDFFitPage".into(),
+    format!("-dDEVICEWIDTHPOINTS={w}"),
+    format!("-dDEVICEHEIGHTPOINTS={h}"),
+    "-dNOPAUSE".into(), "-dQUIET".into(), "-dBATCH".into(),
+    format!("-sOutputFile={output}"),
+    input.into(),
+  ];
+  run_gs(app, &args).map(|_| ()).map_err(|e| format!("统一页面尺寸失败：{e}（{input}）"))
+}
+
+async fn write_segments(app: &AppHandle, segments: &[(String, String)], opts: &MergeOptions, output: &str) -> Result<Option<SizeReduction>, String> {
+  let out = AtomicOutput::new(output)?;
+  let linearize = opts.linearize();
+  run_qpdf(app, &build_args_merge_paths(segments, &out.path(), linearize)).await?;
+  let optimized = if opts.optimize { Some(dedupe_resources(app, &out.path(), linearize).await?) } else { None };
+  if opts.renumber_labels || opts.producer.is_some() {
+    patch_document(app, &out.path(), opts, linearize).await?;
+  }
+  if linearize && !is_linearized(app, &out.path())? {
+    return Err("合并结果线性化校验未通过（qpdf --check-linearization）".into());
+  }
+  out.commit()?;
+  Ok(optimized)
+}
+
+/// gs 重写并校验，比原合并结果小才替换（经 qpdf 写回 path）；gs 失败不算错误，保留原结果
+async fn dedupe_resources(app: &AppHandle, path: &str, linearize: bool) -> Result<SizeReduction, String> {
+  let size = |p: &str| fs::metadata(p).map(|m| m.len()).unwrap_or(0);
+  let bytes_before = size(path);
+  let tmp = format!("{path}.dedupe");
+  let res = match gs_dedupe(app, path, &tmp).and_then(|_| verify_pdf(app, &tmp)) {
+    Ok(()) if size(&tmp) < bytes_before => run_qpdf(app, &rewrite_args(&tmp, path, linearize)).await,
+    Ok(()) => Ok(()),
+    Err(e) => { eprintln!("[merge] 去重失败：{e}；保留原合并结果"); Ok(()) }
+  };
+  let _ = fs::remove_file(&tmp);
+  res.map(|_| SizeReduction { bytes_before, bytes_after: size(path) })
+}
+
+/// lopdf 一次改完页码标签与生成工具信息，写到旁边再经 qpdf 写回 path
+async fn patch_document(app: &AppHandle, path: &str, opts: &MergeOptions, linearize: bool) -> Result<(), String> {
+  let mut doc = lopdf::Document::load(path).map_err(|e| format!("解析合并结果失败：{e}"))?;
+  if opts.renumber_labels { renumber_page_labels(&mut doc, opts.label_style)?; }
+  if let Some(p) = opts.producer.as_deref() { stamp_producer(&mut doc, &producer_label(app, Some(p)))?; }
+  let tmp = format!("{path}.patched");
+  save_pdf(&mut doc, &tmp)?;
+  let res = run_qpdf(app, &rewrite_args(&tmp, path, linearize)).await;
+  let _ = fs::remove_file(&tmp);
+  res
+}
+
+/// 整份文档只留一段编号（覆盖 qpdf 拼接时带过来的各输入 /PageLabels）
+fn renumber_page_labels(doc: &mut lopdf::Document, style: LabelStyle) -> Result<(), String> {
+  let cat = catalog_id(doc)?;
+  let labels = dictionary! { "Nums" => vec![Object::Integer(0), Object::Dictionary(dictionary! { "S" => style.code() })] };
+  match doc.get_object_mut(cat) {
+    Ok(Object::Dictionary(c)) => { c.set("PageLabels", labels); Ok(()) }
+    _ => Err("文档目录不是字典".into()),
+  }
+}
+
+/// qpdf 把中间结果写回 path，保持与拼接时一致的线性化设置
+fn rewrite_args(tmp: &str, path: &str, linearize: bool) -> Vec<String> {
+  let mut args = if linearize { vec!["--linearize".to_string()] } else { Vec::new() };
+  args.extend([tmp.to_string(), path.to_string()]);
+  args
+}
+
+/// verify_annotations 时统计各片段选中页的注释数（同一文件只解析一次）
+fn expected_annotations(segments: &[(String, String)], opts: &MergeOptions) -> Result<Option<AnnotCount>, String> {
+  if !opts.verify_annotations { return Ok(None); }
+  let mut docs = HashMap::<&str, lopdf::Document>::new();
+  let mut total = AnnotCount::default();
+  for (path, range) in segments {
+    if !docs.contains_key(path.as_str()) {
+      let doc = lopdf::Document::load(path).map_err(|e| format!("解析 PDF 失败：{e}（{path}）"))?;
+      docs.insert(path, doc);
+    }
+    let doc = &docs[path.as_str()];
+    let pages = doc.get_pages();
+    for no in parse_range(range, pages.len() as u32)? {
+      if let Some(&id) = pages.get(&no) { total.add(count_annots(doc, id)); }
+    }
+  }
+  Ok(Some(total))
+}
+
+fn check_annotations(expected: AnnotCount, output: &str) -> Result<AnnotationCheck, String> {
+  let doc = lopdf::Document::load(output).map_err(|e| format!("解析合并结果失败：{e}"))?;
+  let mut actual = AnnotCount::default();
+  for id in doc.get_pages().into_values() { actual.add(count_annots(&doc, id)); }
+  let lost = actual.annotations < expected.annotations || actual.widgets < expected.widgets;
+  if lost { eprintln!("[merge] 注释核对不一致：预期 {expected:?}，实际 {actual:?}"); }
+  Ok(AnnotationCheck { expected, actual, lost })
+}
+
+fn count_annots(doc: &lopdf::Document, page_id: lopdf::ObjectId) -> AnnotCount {
+  let annots = doc.get_dictionary(page_id).ok()
+    .and_then(|p| p.get(b"Annots").ok())
+    .and_then(|o| resolve(doc, o).as_array().ok());
+  let Some(annots) = annots else { return AnnotCount::default() };
+  let widgets = annots.iter()
+    .filter(|a| resolve(doc, a).as_dict().is_ok_and(|d| d.get(b"Subtype").and_then(Object::as_name).is_ok_and(|n| n == b"Widget")))
+    .count();
+  AnnotCount { annotations: annots.len() as u32, widgets: widgets as u32 }
+}
+
+fn merge_manifest(app: &AppHandle, names: &[String], paths: &[String], output: &str) -> Result<String, String> {
+  let inputs = names.iter().zip(paths).map(|(n, p)| describe(app, n, p)).collect::<Result<Vec<_>, _>>()?;
+  let dir = Path::new(output).parent().unwrap_or(Path::new("."));
+  write_manifest(app, "merge", inputs, &[output.to_string()], dir)
+}
+
+/// 首页尺寸比较容差（pt）
+const SIZE_TOLERANCE_PT: f32 = 1.0;
+
+/// 以第一个输入的首页宽高为准，列出相差超过容差的输入
+fn check_uniform_size<'a>(inputs: impl Iterator<Item = (&'a str, lopdf::Result<lopdf::Document>)>) -> Result<(), String> {
+  let mut reference: Option<(&str, f32, f32)> = None;
+  let mut mismatched = Vec::new();
+  for (name, doc) in inputs {
+    let doc = doc.map_err(|e| format!("解析 PDF 失败：{e}（{name}）"))?;
+    let first = doc.get_pages().into_values().next().ok_or_else(|| format!("没有页面：{name}"))?;
+    let [x0, y0, x1, y1] = page_rect(&doc, first, b"MediaBox").ok_or_else(|| format!("首页缺少 MediaBox：{name}"))?;
+    let (w, h) = ((x1 - x0).abs(), (y1 - y0).abs());
+    match reference {
+      None => reference = Some((name, w, h)),
+      Some((_, rw, rh)) if (w - rw).abs() > SIZE_TOLERANCE_PT || (h - rh).abs() > SIZE_TOLERANCE_PT => {
+        mismatched.push(format!("{name}（{w:.0}×{h:.0} pt）"));
+      }
+      Some(_) => {}
+    }
+  }
+  match (reference, mismatched.is_empty()) {
+    (Some((first, w, h)), false) => Err(format!("E_INVALID_ARG：输入页面尺寸不一致，与 {first}（{w:.0}×{h:.0} pt）不同的有：{}", mismatched.join("、"))),
+    _ => Ok(()),
+  }
+}
+
+/// 原下标 → 去重后列表中的位置（被跳过的指向其首次出现）
+fn kept_positions(total: usize, skipped: &[SkippedInput]) -> Vec<usize> {
+  let mut pos = Vec::with_capacity(total);
+  let mut next = 0;
+  for i in 0..total {
+    match skipped.iter().find(|s| s.index == i) {
+      Some(s) => { let p = pos[s.duplicate_of]; pos.push(p); }
+      None => { pos.push(next); next += 1; }
+    }
+  }
+  pos
+}
+
+/// (文件, 页范围) 片段：未指定 order 时每个输入全页；指定时校验下标与页范围
+fn plan_segments(app: &AppHandle, paths: &[String], order: Option<&[(usize, String)]>, positions: &[usize]) -> Result<Vec<(String, String)>, String> {
+  let Some(order) = order else {
+    return Ok(paths.iter().map(|p| (p.clone(), "1-z".to_string())).collect());
+  };
+  if order.is_empty() { return Err("页序 order 不能为空".into()); }
+  let mut totals = HashMap::<usize, u32>::new();
+  let mut segments = Vec::with_capacity(order.len());
+  for (idx, range) in order {
+    let &pos = positions.get(*idx).ok_or_else(|| format!("页序中的输入下标越界：{idx}（共 {} 个输入）", positions.len()))?;
+    let path = &paths[pos];
+    let total = match totals.get(&pos) {
+      Some(&n) => n,
+      None => { let n = page_count(app, path)?; totals.insert(pos, n); n }
+    };
+    parse_range(range, total).map_err(|e| format!("输入 {idx} 的{e}"))?;
+    segments.push((path.clone(), range.trim().to_string()));
+  }
+  Ok(segments)
+}
+
+/// 保留每个哈希的首次出现（顺序不变），其余记为跳过
+fn dedupe<T>(items: Vec<T>, hashes: &[String], name: impl Fn(&T) -> String) -> (Vec<T>, Vec<SkippedInput>) {
+  let mut seen = HashMap::<&str, usize>::new();
+  let mut kept = Vec::with_capacity(items.len());
+  let mut skipped = Vec::new();
+  for (index, (item, hash)) in items.into_iter().zip(hashes).enumerate() {
+    match seen.get(hash.as_str()) {
+      Some(&first) => skipped.push(SkippedInput { index, name: name(&item), duplicate_of: first }),
+      None => {
+        seen.insert(hash, index);
+        kept.push(item);
+      }
+    }
+  }
+  (kept, skipped)
 }
 
 fn assert_output_not_in_inputs(inputs: &[String], output: &str) -> Result<(), String> {
@@ -74,12 +571,13 @@ fn write_temp_pdfs(app: &AppHandle, inputs: &[PdfIn]) -> Result<(PathBuf, Vec<St
   Ok((work, in_paths))
 }
 
-fn build_args_merge_paths(paths: &[String], output: &str) -> Vec<String> {
-  // qpdf --linearize --empty --pages f1 1-z f2 1-z -- out.pdf
-  let mut args = vec!["--linearize".into(), "--empty".into(), "--pages".into()];
-  for p in paths {
+fn build_args_merge_paths(segments: &[(String, String)], output: &str, linearize: bool) -> Vec<String> {
+  // qpdf [--linearize] --empty --pages f1 1-z f2 1-z -- out.pdf
+  let mut args: Vec<String> = if linearize { vec!["--linearize".into()] } else { Vec::new() };
+  args.extend(["--empty".into(), "--pages".into()]);
+  for (p, range) in segments {
     args.push(p.clone());
-    args.push("1-z".into());
+    args.push(range.clone());
   }
   args.push("--".into());
   args.push(output.to_string());
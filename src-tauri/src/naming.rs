@@ -0,0 +1,84 @@
+//! Output naming — one template syntax for every single-output command: the frontend may pass a template instead of a literal output path.
+//! Commands whose result carries no output path (auto_rotate, invert_pages, embed_fingerprint, sprite_sheet) take literal paths only;
+//! split parts expand the same tokens through SplitOptions.naming
+use std::{fs, path::Path};
+
+use crate::common::{hex_sha256, sanitize, InputOne};
+use crate::manifest::utc_now;
+
+/// 可用变量：{stem} 输入文件名（不含扩展名，多输入取第一个）、{op} 操作名、{date} UTC 日期 YYYYMMDD、
+/// {seq} 三位序号（从 001 起取第一个不存在的文件）、{sha8} 输入内容 SHA-256 前 8 位（多输入按各自哈希再取哈希）
+const TOKENS: [&str; 5] = ["{stem}", "{op}", "{date}", "{seq}", "{sha8}"];
+const MAX_SEQ: u32 = 9999;
+
+/// 命名用的输入来源
+pub(crate) enum NamingSource<'a> {
+  Path(&'a str),
+  Bytes { name: &'a str, data: &'a [u8] },
+}
+
+impl<'a> From<&'a InputOne> for NamingSource<'a> {
+  fn from(input: &'a InputOne) -> Self {
+    match input {
+      InputOne::Path(p) => NamingSource::Path(p),
+      InputOne::Bytes(pdf) => NamingSource::Bytes { name: &pdf.name, data: &pdf.data },
+    }
+  }
+}
+
+impl NamingSource<'_> {
+  fn name(&self) -> &str {
+    match self { NamingSource::Path(p) => p, NamingSource::Bytes { name, .. } => name }
+  }
+
+  fn sha256(&self) -> Result<String, String> {
+    match self {
+      NamingSource::Path(p) => fs::read(p).map(|d| hex_sha256(&d)).map_err(|e| format!("读取输入失败：{e}（{p}）")),
+      NamingSource::Bytes { data, .. } => Ok(hex_sha256(data)),
+    }
+  }
+}
+
+/// 不含任何变量时原样返回（普通路径）；否则只替换文件名部分的变量，变量值经 sanitize，返回实际要写的路径
+pub(crate) fn resolve_output(output: &str, op: &str, sources: &[NamingSource]) -> Result<String, String> {
+  if !TOKENS.iter().any(|t| output.contains(t)) { return Ok(output.to_string()); }
+  let path = Path::new(output);
+  let file = path.file_name().map(|f| f.to_string_lossy().to_string()).unwrap_or_default();
+  if !TOKENS.iter().any(|t| file.contains(t)) { return Err(format!("命名变量只能用在文件名中：{output}")); }
+  let dir = path.parent().unwrap_or(Path::new(""));
+
+  let name = expand_tokens(&file, op, sources)?;
+  if name.trim().is_empty() { return Err("命名模板展开后为空".into()); }
+  if !name.contains("{seq}") { return Ok(dir.join(name).to_string_lossy().to_string()); }
+  let name = first_free_seq(&name, |n| !dir.join(n).exists()).ok_or_else(|| format!("序号已用尽（{MAX_SEQ}）：{output}"))?;
+  Ok(dir.join(name).to_string_lossy().to_string())
+}
+
+/// 替换 {stem} {op} {date} {sha8}；{seq} 原样保留，由调用方按输出目录取号（拆分等多输出命令各份另有变量）
+pub(crate) fn expand_tokens(template: &str, op: &str, sources: &[NamingSource]) -> Result<String, String> {
+  let stem = sources.first()
+    .and_then(|s| Path::new(s.name()).file_stem().map(|f| f.to_string_lossy().to_string()))
+    .unwrap_or_else(|| "output".into());
+  let name = template
+    .replace("{stem}", &sanitize(&stem))
+    .replace("{op}", &sanitize(op))
+    .replace("{date}", &utc_now()[..10].replace('-', ""));
+  if !name.contains("{sha8}") { return Ok(name); }
+  Ok(name.replace("{sha8}", &content_hash(sources)?[..8]))
+}
+
+/// {seq} 换成从 001 起第一个 free 认可的三位序号；到 MAX_SEQ 仍没有时为 None
+pub(crate) fn first_free_seq(name: &str, free: impl Fn(&str) -> bool) -> Option<String> {
+  (1..=MAX_SEQ).map(|n| name.replace("{seq}", &format!("{n:03}"))).find(|n| free(n))
+}
+
+fn content_hash(sources: &[NamingSource]) -> Result<String, String> {
+  match sources {
+    [] => Err("{sha8} 需要输入文件".into()),
+    [one] => one.sha256(),
+    many => {
+      let joined = many.iter().map(NamingSource::sha256).collect::<Result<Vec<_>, _>>()?.concat();
+      Ok(hex_sha256(joined.as_bytes()))
+    }
+  }
+}
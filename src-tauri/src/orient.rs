@@ -0,0 +1,81 @@
+//! Auto-rotate — Tesseract OSD (--psm 0) per rendered page → qpdf --rotate corrections
+use tauri::AppHandle;
+use serde::Serialize;
+use std::{fs, path::Path};
+
+use crate::common::{
+  assert_input_not_output, run_qpdf, run_with_env, stage_input, tesseract_exe, work_dir, AtomicOutput, InputOne,
+  PageGuard,
+};
+use crate::render::{render_pages, RenderMode};
+
+/// OSD 在 150–300 dpi 效果稳定；取低端换速度
+const OSD_DPI: u32 = 150;
+/// Tesseract 方向置信度低于该值不做旋转（空白页 / 纯图片页常给出随机方向）
+const MIN_CONFIDENCE: f32 = 2.0;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageRotation {
+  pub page: u32,
+  /// 施加的顺时针旋转角度：0 / 90 / 180 / 270
+  pub rotate: u32,
+  /// OSD 方向置信度；无法识别时为 None
+  pub confidence: Option<f32>,
+}
+
+#[tauri::command]
+pub async fn auto_rotate(app: AppHandle, input: InputOne, output: String, guard: Option<PageGuard>) -> Result<Vec<PageRotation>, String> {
+  assert_input_not_output(&input, &output)?;
+  let out = AtomicOutput::new(&output)?;
+  let work = work_dir(&app, "auto_rotate")?;
+  let res = run_auto_rotate(&app, &work, &input, &out.path(), guard.unwrap_or_default());
+  let _ = fs::remove_dir_all(&work);
+  let report = res?;
+  out.commit()?;
+  Ok(report)
+}
+
+fn run_auto_rotate(app: &AppHandle, work: &Path, input: &InputOne, output: &str, guard: PageGuard) -> Result<Vec<PageRotation>, String> {
+  let in_path = stage_input(work, input)?;
+  guard.check(app, &in_path)?;
+  let report = detect_rotations(app, work, &in_path)?;
+  apply_rotations(app, &in_path, output, &report)?;
+  Ok(report)
+}
+
+/// 逐页渲染 + OSD，得出每页应施加的旋转（置信度不足为 0）；渲染图写在 work/render
+pub(crate) fn detect_rotations(app: &AppHandle, work: &Path, in_path: &str) -> Result<Vec<PageRotation>, String> {
+  let (bin_dir, exe, envs) = tesseract_exe(app)?;
+  let pngs = render_pages(app, in_path, &work.join("render"), OSD_DPI, RenderMode::Gray, None)?;
+
+  let mut report = Vec::with_capacity(pngs.len());
+  for (i, png) in pngs.iter().enumerate() {
+    let page = i as u32 + 1;
+    let args = vec![png.to_string_lossy().to_string(), "-".into(), "--psm".into(), "0".into()];
+    let out = run_with_env(&bin_dir, &exe, &args, &envs)?;
+    // 文字太少时 OSD 以非0 退出（"Too few characters"），按无法识别处理
+    let osd = if out.status.success() { parse_osd(&String::from_utf8_lossy(&out.stdout)) } else { None };
+    let rotate = match osd {
+      Some((r, c)) if c >= MIN_CONFIDENCE => r,
+      _ => 0,
+    };
+    report.push(PageRotation { page, rotate, confidence: osd.map(|(_, c)| c) });
+  }
+  Ok(report)
+}
+
+/// qpdf in.pdf out.pdf --rotate=+90:3 --rotate=+180:7
+pub(crate) fn apply_rotations(app: &AppHandle, in_path: &str, output: &str, report: &[PageRotation]) -> Result<(), String> {
+  let mut args: Vec<String> = vec![in_path.into(), output.into()];
+  args.extend(report.iter().filter(|r| r.rotate != 0).map(|r| format!("--rotate=+{}:{}", r.rotate, r.page)));
+  run_qpdf(app, &args).map(|_| ())
+}
+
+/// OSD 输出中的 "Rotate: 90" 与 "Orientation confidence: 7.53"
+fn parse_osd(stdout: &str) -> Option<(u32, f32)> {
+  let field = |key: &str| stdout.lines().find_map(|l| l.trim().strip_prefix(key)).map(str::trim);
+  let rotate = field("Rotate:")?.parse::<u32>().ok().filter(|r| matches!(r, 0 | 90 | 180 | 270))?;
+  let confidence = field("Orientation confidence:")?.parse::<f32>().ok()?;
+  Some((rotate, confidence))
+}
@@ -0,0 +1,209 @@
+//! Outline (bookmarks) — read the /Outlines tree with resolved page numbers, rewrite it per part, export as Markdown / JSON
+use lopdf::{dictionary, Dictionary, Document, Object, ObjectId};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::common::{catalog_id, decode_text, encode_text, load_pdf, InputOne};
+use crate::inspect::resolve;
+
+/// 书签节点；page 为 1 基页码，解析不出目标页时为 None
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct OutlineNode {
+  pub title: String,
+  pub page: Option<u32>,
+  pub children: Vec<OutlineNode>,
+}
+
+/// 读取整棵书签树（/First → /Next 链，带环检测）
+pub(crate) fn read_outline(doc: &Document) -> Vec<OutlineNode> {
+  let page_nos: BTreeMap<ObjectId, u32> = doc.get_pages().into_iter().map(|(no, id)| (id, no)).collect();
+  let Some(root) = doc.catalog().ok()
+    .and_then(|c| c.get(b"Outlines").ok())
+    .and_then(|o| resolve(doc, o).as_dict().ok()) else { return Vec::new() };
+  let mut seen = HashSet::new();
+  read_level(doc, root, &page_nos, &mut seen)
+}
+
+fn read_level(doc: &Document, parent: &Dictionary, page_nos: &BTreeMap<ObjectId, u32>, seen: &mut HashSet<ObjectId>) -> Vec<OutlineNode> {
+  let mut out = Vec::new();
+  let mut next = parent.get(b"First").and_then(|o| o.as_reference()).ok();
+  while let Some(id) = next {
+    if !seen.insert(id) { break; }
+    let Ok(item) = doc.get_dictionary(id) else { break };
+    let title = item.get(b"Title").ok()
+      .and_then(|o| resolve(doc, o).as_str().ok())
+      .map(decode_text)
+      .unwrap_or_default();
+    out.push(OutlineNode {
+      title,
+      page: item_page(doc, item, page_nos),
+      children: read_level(doc, item, page_nos, seen),
+    });
+    next = item.get(b"Next").and_then(|o| o.as_reference()).ok();
+  }
+  out
+}
+
+/// 书签目标页：/Dest 或 /A << /S /GoTo /D ... >>
+fn item_page(doc: &Document, item: &Dictionary, page_nos: &BTreeMap<ObjectId, u32>) -> Option<u32> {
+  let dest = match item.get(b"Dest") {
+    Ok(d) => resolve(doc, d),
+    Err(_) => {
+      let action = resolve(doc, item.get(b"A").ok()?).as_dict().ok()?;
+      if action.get(b"S").ok()?.as_name().ok()? != b"GoTo" { return None; }
+      resolve(doc, action.get(b"D").ok()?)
+    }
+  };
+  dest_page(doc, dest, page_nos, 0)
+}
+
+/// 解析目标（显式数组 / 命名目标）到 1 基页码
+pub(crate) fn dest_page(doc: &Document, dest: &Object, page_nos: &BTreeMap<ObjectId, u32>, depth: u8) -> Option<u32> {
+  if depth > 8 { return None; }
+  match dest {
+    Object::Array(a) => match a.first()? {
+      Object::Reference(id) => page_nos.get(id).copied(),
+      Object::Integer(i) if *i >= 0 => Some(*i as u32 + 1),
+      _ => None,
+    },
+    Object::Name(n) | Object::String(n, _) => {
+      let target = named_dest(doc, n)?;
+      dest_page(doc, &target, page_nos, depth + 1)
+    }
+    Object::Dictionary(d) => dest_page(doc, resolve(doc, d.get(b"D").ok()?), page_nos, depth + 1),
+    Object::Reference(_) => dest_page(doc, resolve(doc, dest), page_nos, depth + 1),
+    _ => None,
+  }
+}
+
+/// 命名目标：先查 Catalog /Dests 字典，再查 /Names /Dests 名称树
+fn named_dest(doc: &Document, name: &[u8]) -> Option<Object> {
+  let catalog = doc.catalog().ok()?;
+  if let Some(v) = catalog.get(b"Dests").ok().and_then(|o| resolve(doc, o).as_dict().ok()).and_then(|d| d.get(name).ok()) {
+    return Some(resolve(doc, v).clone());
+  }
+  let tree = catalog.get(b"Names").ok()
+    .and_then(|o| resolve(doc, o).as_dict().ok())
+    .and_then(|n| n.get(b"Dests").ok())
+    .and_then(|o| resolve(doc, o).as_dict().ok())?;
+  name_tree_get(doc, tree, name, 0)
+}
+
+pub(crate) fn name_tree_get(doc: &Document, node: &Dictionary, key: &[u8], depth: u8) -> Option<Object> {
+  if depth > 32 { return None; }
+  if let Ok(names) = node.get(b"Names").and_then(|o| resolve(doc, o).as_array()) {
+    for pair in names.chunks(2) {
+      if let [k, v] = pair {
+        if resolve(doc, k).as_str().ok() == Some(key) { return Some(resolve(doc, v).clone()); }
+      }
+    }
+  }
+  let kids = node.get(b"Kids").and_then(|o| resolve(doc, o).as_array()).ok()?;
+  kids.iter()
+    .filter_map(|k| resolve(doc, k).as_dict().ok())
+    .find_map(|kid| name_tree_get(doc, kid, key, depth + 1))
+}
+
+/// 按本段页序（原始页码）重映射：保留落在本段的书签并改为本地页码；父项不在本段时子项上提
+pub(crate) fn remap(nodes: &[OutlineNode], part_pages: &[u32]) -> Vec<OutlineNode> {
+  let mut local: HashMap<u32, u32> = HashMap::new();
+  for (i, p) in part_pages.iter().enumerate() { local.entry(*p).or_insert(i as u32 + 1); }
+  remap_with(nodes, &local)
+}
+
+fn remap_with(nodes: &[OutlineNode], local: &HashMap<u32, u32>) -> Vec<OutlineNode> {
+  let mut out = Vec::new();
+  for n in nodes {
+    let children = remap_with(&n.children, local);
+    match n.page.and_then(|p| local.get(&p)) {
+      Some(&page) => out.push(OutlineNode { title: n.title.clone(), page: Some(page), children }),
+      None => out.extend(children),
+    }
+  }
+  out
+}
+
+/// 用给定节点替换文档书签（目标统一为 [page /Fit]）；节点为空则移除 /Outlines
+pub(crate) fn write_outline(doc: &mut Document, nodes: &[OutlineNode]) -> Result<(), String> {
+  let cat = catalog_id(doc)?;
+  if nodes.is_empty() {
+    if let Ok(Object::Dictionary(c)) = doc.get_object_mut(cat) { c.remove(b"Outlines"); }
+    return Ok(());
+  }
+  let pages: Vec<ObjectId> = doc.get_pages().values().copied().collect();
+  let root_id = doc.new_object_id();
+  let (first, last, count) = write_level(doc, nodes, root_id, &pages);
+  let mut root = dictionary! { "Type" => "Outlines", "Count" => count };
+  if let (Some(f), Some(l)) = (first, last) { root.set("First", f); root.set("Last", l); }
+  doc.objects.insert(root_id, Object::Dictionary(root));
+  match doc.get_object_mut(cat) {
+    Ok(Object::Dictionary(c)) => { c.set("Outlines", root_id); Ok(()) }
+    _ => Err("文档目录（Root）不是字典".into()),
+  }
+}
+
+fn write_level(doc: &mut Document, nodes: &[OutlineNode], parent: ObjectId, pages: &[ObjectId]) -> (Option<ObjectId>, Option<ObjectId>, i64) {
+  let ids: Vec<ObjectId> = nodes.iter().map(|_| doc.new_object_id()).collect();
+  let mut count = 0i64;
+  for (i, node) in nodes.iter().enumerate() {
+    let mut d = dictionary! { "Title" => encode_text(&node.title), "Parent" => parent };
+    if let Some(p) = node.page.and_then(|p| pages.get((p as usize).wrapping_sub(1))) {
+      d.set("Dest", vec![Object::Reference(*p), "Fit".into()]);
+    }
+    if i > 0 { d.set("Prev", ids[i - 1]); }
+    if i + 1 < ids.len() { d.set("Next", ids[i + 1]); }
+    let (f, l, c) = write_level(doc, &node.children, ids[i], pages);
+    if let (Some(f), Some(l)) = (f, l) { d.set("First", f); d.set("Last", l); d.set("Count", c); }
+    count += 1 + c;
+    doc.objects.insert(ids[i], Object::Dictionary(d));
+  }
+  (ids.first().copied(), ids.last().copied(), count)
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum OutlineFormat {
+  /// 嵌套列表，每级缩进两格，条目后附页码
+  Markdown,
+  /// [{ title, page, children }]，page 解析不出时为 null
+  Json,
+}
+
+/// 书签树导出为文本，供前端保存或展示；没有书签时 Markdown 为空串、JSON 为 []
+#[tauri::command]
+pub async fn export_outline(input: InputOne, format: OutlineFormat) -> Result<String, String> {
+  let doc = load_pdf(&input)?;
+  let nodes = read_outline(&doc);
+  match format {
+    OutlineFormat::Markdown => {
+      let mut md = String::new();
+      markdown_level(&nodes, 0, &mut md);
+      Ok(md)
+    }
+    OutlineFormat::Json => serde_json::to_string_pretty(&nodes).map_err(|e| format!("序列化书签失败：{e}")),
+  }
+}
+
+fn markdown_level(nodes: &[OutlineNode], depth: usize, out: &mut String) {
+  for node in nodes {
+    out.push_str(&"  ".repeat(depth));
+    out.push_str("- ");
+    out.push_str(&escape_markdown(node.title.trim()));
+    if let Some(p) = node.page { out.push_str(&format!(" (p. {p})")); }
+    out.push('\n');
+    markdown_level(&node.children, depth + 1, out);
+  }
+}
+
+/// 标题里的 Markdown 记号原样显示；换行折成空格，免得打断列表
+fn escape_markdown(title: &str) -> String {
+  let mut out = String::with_capacity(title.len());
+  for c in title.chars() {
+    match c {
+      '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' => { out.push('\\'); out.push(c); }
+      '\r' | '\n' => out.push(' '),
+      _ => out.push(c),
+    }
+  }
+  out
+}
@@ -0,0 +1,156 @@
+//! Page manifest — one call for a page manager UI: page boxes (lopdf) + /PageLabels + Ghostscript JPEG thumbnails
+//! cached under the app cache dir by input SHA-256
+use tauri::{AppHandle, Manager};
+use base64::{engine::general_purpose, Engine as _};
+use lopdf::{Dictionary, Document};
+use serde::Serialize;
+use std::{
+  fs,
+  path::{Path, PathBuf},
+};
+
+use crate::common::{decode_text, hex_sha256, stage_input, work_dir, InputOne, PageGuard};
+use crate::inspect::{inherited, resolve};
+use crate::render::{render_pages, RenderMode};
+use crate::stamp::visible_area;
+
+/// A4 宽约 200px，够页面管理器的缩略图用
+const THUMB_DPI: u32 = 24;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageEntry {
+  /// 1 基页码
+  pub index: u32,
+  /// 可见区域（CropBox，缺省为 MediaBox）尺寸，未按 rotation 转正
+  pub width_pt: f32,
+  pub height_pt: f32,
+  /// 规范化到 0 / 90 / 180 / 270
+  pub rotation: i64,
+  /// /PageLabels 给出的页码标签；没有时为页码本身
+  pub label: String,
+  /// JPEG 缩略图（base64，不带 data: 前缀）
+  pub thumbnail_b64: String,
+}
+
+/// 打开文档时一次拿到所有页的尺寸、旋转、标签与缩略图；同一内容再次打开直接用缓存的缩略图（命中缓存时不检查页数上限）
+#[tauri::command]
+pub async fn page_manifest(app: AppHandle, input: InputOne, guard: Option<PageGuard>) -> Result<Vec<PageEntry>, String> {
+  let work = work_dir(&app, "page_manifest")?;
+  let res = run_manifest(&app, &work, &input, guard.unwrap_or_default());
+  let _ = fs::remove_dir_all(&work);
+  res
+}
+
+fn run_manifest(app: &AppHandle, work: &Path, input: &InputOne, guard: PageGuard) -> Result<Vec<PageEntry>, String> {
+  let in_path = stage_input(work, input)?;
+  let data = fs::read(&in_path).map_err(|e| format!("读取输入失败：{e}"))?;
+  let doc = Document::load_mem(&data).map_err(|e| format!("解析 PDF 失败：{e}"))?;
+  let pages = doc.get_pages();
+  let labels = page_labels(&doc, pages.len() as u32);
+  let thumbs = cached_thumbnails(app, work, &in_path, &hex_sha256(&data), pages.len(), guard)?;
+
+  pages.into_iter().zip(labels).zip(thumbs).map(|(((no, page_id), label), thumb)| {
+    let [x0, y0, x1, y1] = visible_area(&doc, page_id).ok_or(format!("第 {no} 页缺少 MediaBox"))?;
+    let rotation = inherited(&doc, page_id, b"Rotate").and_then(|o| o.as_i64().ok()).unwrap_or(0).rem_euclid(360) / 90 * 90;
+    let jpeg = fs::read(&thumb).map_err(|e| format!("读取缩略图失败：{e}"))?;
+    Ok(PageEntry {
+      index: no,
+      width_pt: x1 - x0,
+      height_pt: y1 - y0,
+      rotation,
+      label,
+      thumbnail_b64: general_purpose::STANDARD.encode(jpeg),
+    })
+  }).collect()
+}
+
+/// <缓存目录>/thumbnails/<sha256>_<dpi>/page_00001.jpg ...；页数对不上视为缓存无效，重新渲染后整目录替换
+fn cached_thumbnails(app: &AppHandle, work: &Path, in_path: &str, hash: &str, count: usize, guard: PageGuard) -> Result<Vec<PathBuf>, String> {
+  let root = app.path().app_cache_dir().map_err(|e| format!("无法定位缓存目录：{e}"))?.join("thumbnails");
+  let dir = root.join(format!("{hash}_{THUMB_DPI}"));
+  if let Some(hit) = list_jpegs(&dir).filter(|t| t.len() == count) { return Ok(hit); }
+
+  guard.check(app, in_path)?;
+  let fresh = render_pages(app, in_path, &work.join("thumbs"), THUMB_DPI, RenderMode::Jpeg, None)?;
+  if fresh.len() != count { return Err(format!("缩略图数量与页数不符：{} / {count}", fresh.len())); }
+  // 缓存写不进去不影响本次结果，直接用工作目录里的渲染图
+  let cached = fs::create_dir_all(&root).ok().and_then(|_| {
+    let _ = fs::remove_dir_all(&dir);
+    fs::rename(work.join("thumbs"), &dir).ok()
+  });
+  match cached {
+    Some(()) => list_jpegs(&dir).ok_or_else(|| "读取缩略图缓存失败".into()),
+    None => Ok(fresh),
+  }
+}
+
+fn list_jpegs(dir: &Path) -> Option<Vec<PathBuf>> {
+  let mut files: Vec<PathBuf> = fs::read_dir(dir).ok()?
+    .filter_map(|e| e.ok().map(|e| e.path()))
+    .filter(|p| p.extension().is_some_and(|x| x == "jpg"))
+    .collect();
+  files.sort();
+  Some(files)
+}
+
+// ---------- 页码标签 ----------
+
+/// 每页的显示标签：/PageLabels 数字树中起始页 ≤ 当前页的最后一段决定样式（/S）、前缀（/P）与起始值（/St）
+fn page_labels(doc: &Document, total: u32) -> Vec<String> {
+  let mut ranges: Vec<(u32, Dictionary)> = Vec::new();
+  if let Some(tree) = doc.catalog().ok().and_then(|c| c.get(b"PageLabels").ok()).and_then(|o| resolve(doc, o).as_dict().ok()) {
+    collect_nums(doc, tree, &mut ranges, 0);
+  }
+  ranges.sort_by_key(|(start, _)| *start);
+
+  (0..total).map(|i| {
+    let Some((start, spec)) = ranges.iter().rev().find(|(s, _)| *s <= i) else { return (i + 1).to_string() };
+    let prefix = spec.get(b"P").ok().and_then(|o| resolve(doc, o).as_str().ok()).map(decode_text).unwrap_or_default();
+    let first = spec.get(b"St").ok().and_then(|o| o.as_i64().ok()).unwrap_or(1).max(1) as u32;
+    let n = first + (i - start);
+    let number = match spec.get(b"S").ok().and_then(|o| o.as_name().ok()) {
+      Some(b"D") => n.to_string(),
+      Some(b"R") => roman(n),
+      Some(b"r") => roman(n).to_lowercase(),
+      Some(b"A") => letters(n),
+      Some(b"a") => letters(n).to_lowercase(),
+      _ => String::new(), // 没有 /S 时只显示前缀
+    };
+    format!("{prefix}{number}")
+  }).collect()
+}
+
+fn collect_nums(doc: &Document, node: &Dictionary, out: &mut Vec<(u32, Dictionary)>, depth: u8) {
+  if depth > 32 { return; }
+  if let Ok(nums) = node.get(b"Nums").and_then(|o| resolve(doc, o).as_array()) {
+    for pair in nums.chunks(2) {
+      if let [k, v] = pair {
+        if let (Ok(start), Ok(spec)) = (resolve(doc, k).as_i64(), resolve(doc, v).as_dict()) {
+          if let Ok(start) = u32::try_from(start) { out.push((start, spec.clone())); }
+        }
+      }
+    }
+  }
+  let Ok(kids) = node.get(b"Kids").and_then(|o| resolve(doc, o).as_array()) else { return };
+  for kid in kids.iter().filter_map(|k| resolve(doc, k).as_dict().ok()) { collect_nums(doc, kid, out, depth + 1); }
+}
+
+fn roman(mut n: u32) -> String {
+  const TABLE: [(u32, &str); 13] = [
+    (1000, "M"), (900, "CM"), (500, "D"), (400, "CD"), (100, "C"), (90, "XC"),
+    (50, "L"), (40, "XL"), (10, "X"), (9, "IX"), (5, "V"), (4, "IV"), (1, "I"),
+  ];
+  let mut out = String::new();
+  for (value, digits) in TABLE {
+    while n >= value { out.push_str(digits); n -= value; }
+  }
+  out
+}
+
+/// PDF 规范的字母编号：A–Z，之后 AA–ZZ、AAA…（同一字母重复）
+fn letters(n: u32) -> String {
+  let n = n.max(1) - 1;
+  let c = char::from(b'A' + (n % 26) as u8);
+  c.to_string().repeat((n / 26 + 1) as usize)
+}
@@ -0,0 +1,371 @@
+//! Page-level edits — blank page generation (printpdf) + qpdf page assembly + page boxes (lopdf) + content auto-crop (gs raster) + rescale (lopdf cm)
+use tauri::AppHandle;
+use lopdf::{Dictionary, Document, Object, ObjectId, Stream};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeSet, fs, path::Path};
+
+use printpdf::{Mm, PdfDocument};
+
+use crate::common::{
+  assert_input_not_output, assert_output_not_same, ensure_parent_dir, load_pdf, page_count, parse_range, run_qpdf,
+  save_pdf, stage_input, work_dir, AtomicOutput, InputOne, PageGuard,
+};
+use crate::inspect::{inherited, page_rect};
+use crate::naming::resolve_output;
+use crate::render::{render_pages, RenderMode};
+use crate::stamp::visible_area;
+
+/// 常用纸张；自定义尺寸单位为 pt（1/72 英寸）
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum PageSize {
+  A3,
+  A4,
+  A5,
+  Letter,
+  Legal,
+  #[serde(rename_all = "camelCase")]
+  Custom { width_pt: f32, height_pt: f32 },
+}
+
+impl PageSize {
+  /// 竖版 (宽, 高)，单位 pt
+  pub fn dims_pt(&self) -> (f32, f32) {
+    match *self {
+      PageSize::A3 => (841.89, 1190.55),
+      PageSize::A4 => (595.28, 841.89),
+      PageSize::A5 => (419.53, 595.28),
+      PageSize::Letter => (612.0, 792.0),
+      PageSize::Legal => (612.0, 1008.0),
+      PageSize::Custom { width_pt, height_pt } => (width_pt, height_pt),
+    }
+  }
+}
+
+/// 插入位置：开头 / 末尾 / 第 n 页之后（0 = 开头）
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum InsertPosition {
+  Start,
+  End,
+  Index(u32),
+}
+
+#[tauri::command]
+pub async fn add_blank_page(app: AppHandle, input: InputOne, output: String, size: PageSize, position: InsertPosition) -> Result<String, String> {
+  let output = resolve_output(&output, "blank", &[(&input).into()])?;
+  ensure_parent_dir(&output)?;
+  let work = work_dir(&app, "blank")?;
+  let res = run_add_blank(&app, &work, &input, &output, size, position);
+  let _ = fs::remove_dir_all(&work);
+  res.map(|_| output)
+}
+
+fn run_add_blank(app: &AppHandle, work: &Path, input: &InputOne, output: &str, size: PageSize, position: InsertPosition) -> Result<(), String> {
+  let in_path = stage_input(work, input)?;
+  assert_output_not_same(&in_path, output)?;
+
+  let total = page_count(app, &in_path)?;
+  let after = match position {
+    InsertPosition::Start => 0,
+    InsertPosition::End => total,
+    InsertPosition::Index(i) if i <= total => i,
+    InsertPosition::Index(i) => return Err(format!("插入位置越界：{i}（文档共 {total} 页）")),
+  };
+
+  let blank = work.join("blank.pdf").to_string_lossy().to_string();
+  write_blank_pdf(&blank, size)?;
+
+  // qpdf in.pdf --pages in.pdf 1-k blank.pdf 1 in.pdf k+1-z -- out.pdf（以原文件为主，保留文档级信息）
+  let mut args: Vec<String> = vec![in_path.clone(), "--pages".into()];
+  if after > 0 { args.extend([in_path.clone(), format!("1-{after}")]); }
+  args.extend([blank, "1".into()]);
+  if after < total { args.extend([in_path.clone(), format!("{}-z", after + 1)]); }
+  args.push("--".into());
+  args.push(output.to_string());
+  run_qpdf(app, &args).map(|_| ())
+}
+
+/// 页面框，单位 pt，PDF 坐标（原点左下）
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Box4 {
+  pub left: f32,
+  pub bottom: f32,
+  pub right: f32,
+  pub top: f32,
+}
+
+impl Box4 {
+  fn within(&self, outer: [f32; 4]) -> bool {
+    const EPS: f32 = 0.01;
+    self.left >= outer[0] - EPS && self.bottom >= outer[1] - EPS && self.right <= outer[2] + EPS && self.top <= outer[3] + EPS
+  }
+
+  fn to_object(self) -> Object {
+    Object::Array(vec![self.left.into(), self.bottom.into(), self.right.into(), self.top.into()])
+  }
+}
+
+/// 写入印前用的 TrimBox / BleedBox / ArtBox；ranges 省略为全部页。
+/// 每个框须在该页 MediaBox 内，TrimBox / ArtBox 同时须在 BleedBox 内。
+#[tauri::command]
+pub async fn set_print_boxes(
+  input: InputOne,
+  output: String,
+  trim: Option<Box4>,
+  bleed: Option<Box4>,
+  art: Option<Box4>,
+  ranges: Option<Vec<String>>,
+) -> Result<String, String> {
+  let output = resolve_output(&output, "print_boxes", &[(&input).into()])?;
+  let boxes: Vec<(&str, Box4)> = [("TrimBox", trim), ("BleedBox", bleed), ("ArtBox", art)]
+    .into_iter()
+    .filter_map(|(k, b)| b.map(|b| (k, b)))
+    .collect();
+  if boxes.is_empty() { return Err("请至少提供一个页面框".into()); }
+  for (key, b) in &boxes {
+    if !(b.right > b.left && b.top > b.bottom) { return Err(format!("{key} 宽高必须大于 0")); }
+  }
+  if let Some(bl) = bleed {
+    for (key, b) in [("TrimBox", trim), ("ArtBox", art)] {
+      if b.is_some_and(|b| !b.within([bl.left, bl.bottom, bl.right, bl.top])) { return Err(format!("{key} 超出 BleedBox")); }
+    }
+  }
+  assert_input_not_output(&input, &output)?;
+  let mut doc = load_pdf(&input)?;
+
+  let pages = doc.get_pages();
+  let total = pages.len() as u32;
+  let targets = target_pages(ranges.as_deref(), total)?;
+
+  for no in targets {
+    let page_id = pages[&no];
+    let mb = page_rect(&doc, page_id, b"MediaBox").ok_or(format!("第 {no} 页缺少 MediaBox"))?;
+    if let Some((key, _)) = boxes.iter().find(|(_, b)| !b.within(mb)) {
+      return Err(format!("第 {no} 页的 {key} 超出 MediaBox [{} {} {} {}]", mb[0], mb[1], mb[2], mb[3]));
+    }
+    let Ok(Object::Dictionary(page)) = doc.get_object_mut(page_id) else { return Err(format!("第 {no} 页对象不是字典")) };
+    for (key, b) in &boxes { page.set(*key, b.to_object()); }
+  }
+
+  save_pdf(&mut doc, &output)?;
+  Ok(output)
+}
+
+const MIN_SCALE: f32 = 0.1;
+const MAX_SCALE: f32 = 10.0;
+
+/// 按 scale 等比缩放每页内容（限制在 0.1–10）；给出 target 时页面改为该尺寸并居中放置，否则页面随内容一起缩放。
+/// 一页仍是一页（不同于 N-up）；横向页与带 /Rotate 90/270 的页按显示方向套用 target
+#[tauri::command]
+pub async fn rescale_pages(input: InputOne, output: String, scale: f32, target: Option<PageSize>) -> Result<String, String> {
+  let output = resolve_output(&output, "rescale", &[(&input).into()])?;
+  if !scale.is_finite() { return Err("缩放比例无效".into()); }
+  let scale = scale.clamp(MIN_SCALE, MAX_SCALE);
+  if let Some((w, h)) = target.map(|t| t.dims_pt()) {
+    if !(w > 0.0 && h > 0.0) { return Err("目标纸张宽高必须大于 0".into()); }
+  }
+  assert_input_not_output(&input, &output)?;
+  let mut doc = load_pdf(&input)?;
+
+  for (no, page_id) in doc.get_pages() {
+    let [x0, y0, x1, y1] = visible_area(&doc, page_id).ok_or(format!("第 {no} 页缺少 MediaBox"))?;
+    let rotated = inherited(&doc, page_id, b"Rotate").and_then(|r| r.as_i64().ok()).is_some_and(|r| r.rem_euclid(180) == 90);
+    let (sw, sh) = ((x1 - x0) * scale, (y1 - y0) * scale);
+    let (tw, th) = match target.map(|t| t.dims_pt()) {
+      // target 为竖版尺寸；原页显示为横向时转成横向，旋转页再换回未旋转坐标
+      Some((w, h)) => {
+        let (w, h) = if ((x1 - x0) > (y1 - y0)) != rotated { (w.max(h), w.min(h)) } else { (w.min(h), w.max(h)) };
+        if rotated { (h, w) } else { (w, h) }
+      }
+      None => (sw, sh),
+    };
+    let m = [scale, (tw - sw) / 2.0 - x0 * scale, (th - sh) / 2.0 - y0 * scale];
+    wrap_page_content(&mut doc, page_id, format!("q {} 0 0 {} {} {} cm\n", m[0], m[0], m[1], m[2]).into_bytes())?;
+    scale_annotations(&mut doc, page_id, m);
+
+    let Ok(Object::Dictionary(page)) = doc.get_object_mut(page_id) else { return Err(format!("第 {no} 页对象不是字典")) };
+    page.set("MediaBox", Object::Array(vec![0.into(), 0.into(), tw.into(), th.into()]));
+    // 旧的裁切 / 印前框坐标已失效
+    for key in [&b"CropBox"[..], b"TrimBox", b"BleedBox", b"ArtBox"] { page.remove(key); }
+  }
+
+  save_pdf(&mut doc, &output)?;
+  Ok(output)
+}
+
+/// 原内容流前后加 "q 变换" / "Q"，变换只作用于原内容
+fn wrap_page_content(doc: &mut Document, page_id: ObjectId, head: Vec<u8>) -> Result<(), String> {
+  let existing: Vec<Object> = match doc.get_dictionary(page_id).map_err(|e| format!("读取页面失败：{e}"))?.get(b"Contents") {
+    Ok(Object::Array(a)) => a.clone(),
+    Ok(Object::Reference(id)) => match doc.get_object(*id) {
+      Ok(Object::Array(a)) => a.clone(),
+      _ => vec![Object::Reference(*id)],
+    },
+    _ => Vec::new(),
+  };
+  let head = doc.add_object(Stream::new(Dictionary::new(), head));
+  let tail = doc.add_object(Stream::new(Dictionary::new(), b"\nQ\n".to_vec()));
+  let mut contents = Vec::with_capacity(existing.len() + 2);
+  contents.push(Object::Reference(head));
+  contents.extend(existing);
+  contents.push(Object::Reference(tail));
+  match doc.get_object_mut(page_id) {
+    Ok(Object::Dictionary(page)) => { page.set("Contents", contents); Ok(()) }
+    _ => Err("页面对象不是字典".into()),
+  }
+}
+
+/// 注释 /Rect 跟随内容做同样的缩放平移，链接与表单控件位置才对得上；m = [比例, 平移 x, 平移 y]
+fn scale_annotations(doc: &mut Document, page_id: ObjectId, [s, tx, ty]: [f32; 3]) {
+  let annots: Vec<Object> = match doc.get_dictionary(page_id).ok().and_then(|p| p.get(b"Annots").ok()) {
+    Some(Object::Array(a)) => a.clone(),
+    Some(Object::Reference(id)) => doc.get_object(*id).ok().and_then(|o| o.as_array().ok()).cloned().unwrap_or_default(),
+    _ => return,
+  };
+  for id in annots.iter().filter_map(|a| a.as_reference().ok()) {
+    let Ok(Object::Dictionary(annot)) = doc.get_object_mut(id) else { continue };
+    let Ok(rect) = annot.get(b"Rect").and_then(Object::as_array) else { continue };
+    let nums: Vec<f32> = rect.iter().filter_map(|n| n.as_float().ok()).collect();
+    let [l, b, r, t] = nums[..] else { continue };
+    annot.set("Rect", Object::Array(vec![(l * s + tx).into(), (b * s + ty).into(), (r * s + tx).into(), (t * s + ty).into()]));
+  }
+}
+
+/// ranges 为空或省略时为全部页
+fn target_pages(ranges: Option<&[String]>, total: u32) -> Result<BTreeSet<u32>, String> {
+  match ranges {
+    Some(rs) if !rs.is_empty() => {
+      let mut set = BTreeSet::new();
+      for r in rs { set.extend(parse_range(r, total)?); }
+      Ok(set)
+    }
+    _ => Ok((1..=total).collect()),
+  }
+}
+
+/// 72 dpi 下一像素即 1pt，精度足够
+const AUTOCROP_DPI: u32 = 72;
+/// 灰度低于该值算内容，容忍扫描底色发灰
+const INK_THRESHOLD: u8 = 230;
+/// 一行 / 一列至少这么多内容像素才计入，过滤扫描噪点
+const MIN_INK_PIXELS: usize = 2;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutocropResult {
+  pub path: String,
+  /// 已改写 CropBox 的页
+  pub cropped: Vec<u32>,
+  /// 未检测到内容、保持原样的页
+  pub blank: Vec<u32>,
+}
+
+/// 渲染每页找出非白内容的外框，CropBox 设为外框外扩 padding_pt（不超出原可见区域）；ranges 省略为全部页
+#[tauri::command]
+pub async fn autocrop(
+  app: AppHandle,
+  input: InputOne,
+  output: String,
+  padding_pt: f32,
+  ranges: Option<Vec<String>>,
+  guard: Option<PageGuard>,
+) -> Result<AutocropResult, String> {
+  let output = resolve_output(&output, "autocrop", &[(&input).into()])?;
+  if !padding_pt.is_finite() || padding_pt < 0.0 { return Err(format!("留白需为非负数：{padding_pt}")); }
+  assert_input_not_output(&input, &output)?;
+  let out = AtomicOutput::new(&output)?;
+  let work = work_dir(&app, "autocrop")?;
+  let res = run_autocrop(&app, &work, &input, &out.path(), padding_pt, ranges.as_deref(), guard.unwrap_or_default());
+  let _ = fs::remove_dir_all(&work);
+  let mut result = res?;
+  out.commit()?;
+  result.path = output;
+  Ok(result)
+}
+
+fn run_autocrop(
+  app: &AppHandle,
+  work: &Path,
+  input: &InputOne,
+  output: &str,
+  padding: f32,
+  ranges: Option<&[String]>,
+  guard: PageGuard,
+) -> Result<AutocropResult, String> {
+  let in_path = stage_input(work, input)?;
+  guard.check(app, &in_path)?;
+  let mut doc = Document::load(&in_path).map_err(|e| format!("解析 PDF 失败：{e}"))?;
+  let pages = doc.get_pages();
+  let total = pages.len() as u32;
+  let targets = target_pages(ranges, total)?;
+  let (Some(&first), Some(&last)) = (targets.first(), targets.last()) else { return Err("没有可处理的页面".into()) };
+  let pngs = render_pages(app, &in_path, &work.join("render"), AUTOCROP_DPI, RenderMode::Gray, Some((first, last)))?;
+
+  let (mut cropped, mut blank) = (Vec::new(), Vec::new());
+  for no in targets {
+    let page_id = pages[&no];
+    let png = pngs.get((no - first) as usize).ok_or(format!("第 {no} 页渲染失败"))?;
+    let mb = page_rect(&doc, page_id, b"MediaBox").ok_or(format!("第 {no} 页缺少 MediaBox"))?;
+    let visible = visible_area(&doc, page_id).unwrap_or(mb);
+    let rotate = inherited(&doc, page_id, b"Rotate").and_then(|o| o.as_i64().ok()).unwrap_or(0).rem_euclid(360);
+    let Some(ink) = ink_bounds(png)? else { blank.push(no); continue };
+    let [x0, y0, x1, y1] = unrotate(ink, mb, rotate);
+    let (l, b, r, t) = (
+      (x0 - padding).max(visible[0]),
+      (y0 - padding).max(visible[1]),
+      (x1 + padding).min(visible[2]),
+      (y1 + padding).min(visible[3]),
+    );
+    // 内容全在原裁切区之外
+    if !(r > l && t > b) { blank.push(no); continue; }
+    let Ok(Object::Dictionary(page)) = doc.get_object_mut(page_id) else { return Err(format!("第 {no} 页对象不是字典")) };
+    page.set("CropBox", Box4 { left: l, bottom: b, right: r, top: t }.to_object());
+    cropped.push(no);
+  }
+
+  save_pdf(&mut doc, output)?;
+  Ok(AutocropResult { path: output.to_string(), cropped, blank })
+}
+
+/// 内容像素外框（显示方向，pt，原点左上、y 向下）；整页空白为 None
+fn ink_bounds(png: &Path) -> Result<Option<[f32; 4]>, String> {
+  let img = image::open(png).map_err(|e| format!("读取渲染结果失败：{e}"))?.to_luma8();
+  let (w, h) = (img.width() as usize, img.height() as usize);
+  let (mut rows, mut cols) = (vec![0usize; h], vec![0usize; w]);
+  for (x, y, p) in img.enumerate_pixels() {
+    if p.0[0] < INK_THRESHOLD {
+      rows[y as usize] += 1;
+      cols[x as usize] += 1;
+    }
+  }
+  let span = |v: &[usize]| Some((v.iter().position(|&n| n >= MIN_INK_PIXELS)?, v.iter().rposition(|&n| n >= MIN_INK_PIXELS)?));
+  let (Some((top, bottom)), Some((left, right))) = (span(&rows), span(&cols)) else { return Ok(None) };
+  let pt = 72.0 / AUTOCROP_DPI as f32;
+  Ok(Some([left as f32 * pt, top as f32 * pt, (right + 1) as f32 * pt, (bottom + 1) as f32 * pt]))
+}
+
+/// 渲染结果已按 /Rotate 顺时针旋转：把显示坐标 [u0, v0, u1, v1] 换回 MediaBox 坐标
+fn unrotate([u0, v0, u1, v1]: [f32; 4], mb: [f32; 4], rotate: i64) -> [f32; 4] {
+  let [x0, y0, x1, y1] = mb;
+  match rotate {
+    90 => [x0 + v0, y0 + u0, x0 + v1, y0 + u1],
+    180 => [x1 - u1, y0 + v0, x1 - u0, y0 + v1],
+    270 => [x1 - v1, y1 - u1, x1 - v0, y1 - u0],
+    _ => [x0 + u0, y1 - v1, x0 + u1, y1 - v0],
+  }
+}
+
+/// 生成单页空白 PDF
+pub(crate) fn write_blank_pdf(path: &str, size: PageSize) -> Result<(), String> {
+  let (w, h) = size.dims_pt();
+  if !(w > 0.0 && h > 0.0) { return Err(format!("无效页面尺寸：{w}×{h} pt")); }
+  let (doc, _, _) = PdfDocument::new("blank", pt_to_mm(w), pt_to_mm(h), "Layer 1");
+  let bytes = doc.save_to_bytes().map_err(|e| format!("生成空白页失败：{e}"))?;
+  fs::write(path, bytes).map_err(|e| format!("写入空白页失败：{e}"))
+}
+
+pub(crate) fn pt_to_mm(pt: f32) -> Mm {
+  Mm(pt * 25.4 / 72.0)
+}
@@ -0,0 +1,183 @@
+//! Page tree repair — walk /Pages by hand (lopdf), rebuild it as one flat node with correct /Count and /Parent, qpdf page count as the cross-check
+use tauri::AppHandle;
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use serde::Serialize;
+use std::{collections::HashSet, fs, path::Path};
+
+use crate::common::{
+  assert_input_not_output, catalog_id, page_count, save_pdf, stage_input, work_dir, AtomicOutput, InputOne,
+};
+use crate::naming::resolve_output;
+
+/// 可从父节点继承的页面属性；拍平后中间节点消失，须落到每一页上
+const INHERITABLE: [&[u8]; 4] = [b"Resources", b"MediaBox", b"CropBox", b"Rotate"];
+/// 页树再深就按损坏处理
+const MAX_DEPTH: u32 = 64;
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepairReport {
+  pub path: String,
+  pub pages: u32,
+  /// 缺少 /Count 的节点数
+  pub missing_count: u32,
+  /// /Count 与实际页数不符的节点数
+  pub wrong_count: u32,
+  /// /Parent 缺失或指错的节点数（页与中间节点）
+  pub bad_parent: u32,
+  /// 缺少 /Type 的节点数
+  pub missing_type: u32,
+  /// 丢弃的 Kids 项：非引用、指向非字典、重复出现或形成环
+  pub dropped_kids: u32,
+  /// 拍平时去掉的中间 /Pages 节点数
+  pub flattened_nodes: u32,
+  /// 目录缺少可用的 /Pages，页树由全文件中的 /Type /Page 对象重建
+  pub rebuilt_root: bool,
+  /// 页树本身没有问题（可能只是被拍平）
+  pub clean: bool,
+}
+
+/// 重建为单层 /Pages（页序不变），修正 /Count、/Parent、/Type；重建前后页数须与 qpdf 读出的一致，否则报错且不写出
+#[tauri::command]
+pub async fn repair_page_tree(app: AppHandle, input: InputOne, output: String) -> Result<RepairReport, String> {
+  let output = resolve_output(&output, "repair_tree", &[(&input).into()])?;
+  assert_input_not_output(&input, &output)?;
+  let out = AtomicOutput::new(&output)?;
+  let work = work_dir(&app, "repair_tree")?;
+  let res = run_repair(&app, &work, &input, &out.path());
+  let _ = fs::remove_dir_all(&work);
+  let mut report = res?;
+  out.commit()?;
+  report.path = output;
+  Ok(report)
+}
+
+fn run_repair(app: &AppHandle, work: &Path, input: &InputOne, output: &str) -> Result<RepairReport, String> {
+  let in_path = stage_input(work, input)?;
+  let before = page_count(app, &in_path)?;
+  let mut doc = Document::load(&in_path).map_err(|e| format!("解析 PDF 失败：{e}"))?;
+
+  let mut report = RepairReport::default();
+  let root = catalog_id(&doc)?;
+  let pages_root = doc.get_dictionary(root).ok()
+    .and_then(|c| c.get(b"Pages").ok())
+    .and_then(|o| o.as_reference().ok())
+    .filter(|id| doc.get_dictionary(*id).is_ok());
+
+  let (pages_id, leaves) = match pages_root {
+    Some(id) => {
+      let mut walk = Walk { doc: &doc, report: &mut report, seen: HashSet::new(), leaves: Vec::new() };
+      walk.node(id, None, &Dictionary::new(), 0);
+      let leaves = walk.leaves;
+      (id, leaves)
+    }
+    None => {
+      report.rebuilt_root = true;
+      let leaves = orphan_pages(&doc);
+      (doc.add_object(Dictionary::new()), leaves)
+    }
+  };
+
+  let n = leaves.len() as u32;
+  if n == 0 { return Err("未找到任何页面，无法重建页树".into()); }
+  if n != before { return Err(format!("重建后页数不一致：qpdf 读出 {before} 页，页树中找到 {n} 页")); }
+
+  write_flat_tree(&mut doc, root, pages_id, leaves)?;
+  doc.prune_objects(); // 拍平后的中间节点不再被引用
+  save_pdf(&mut doc, output)?;
+
+  let after = page_count(app, output)?;
+  if after != before { return Err(format!("重建后页数不一致：原 {before} 页，输出 {after} 页")); }
+  report.pages = after;
+  report.clean = !report.rebuilt_root
+    && report.missing_count + report.wrong_count + report.bad_parent + report.missing_type + report.dropped_kids == 0;
+  Ok(report)
+}
+
+struct Walk<'a> {
+  doc: &'a Document,
+  report: &'a mut RepairReport,
+  seen: HashSet<ObjectId>,
+  /// (页对象, 从祖先继承来的属性)
+  leaves: Vec<(ObjectId, Dictionary)>,
+}
+
+impl Walk<'_> {
+  /// 返回该节点下的页数；节点有问题时记入报告但尽量保留其下的页
+  fn node(&mut self, id: ObjectId, parent: Option<ObjectId>, inherited: &Dictionary, depth: u32) -> u32 {
+    let doc = self.doc;
+    let Ok(dict) = doc.get_dictionary(id) else { self.report.dropped_kids += 1; return 0 };
+    if depth > MAX_DEPTH || !self.seen.insert(id) { self.report.dropped_kids += 1; return 0; }
+    if let Some(p) = parent {
+      if dict.get(b"Parent").and_then(Object::as_reference).ok() != Some(p) { self.report.bad_parent += 1; }
+    }
+    let ty = dict.get(b"Type").and_then(Object::as_name).ok();
+    if ty.is_none() { self.report.missing_type += 1; }
+    let kids = dict.get(b"Kids").ok().map(|k| match k {
+      Object::Reference(r) => doc.get_object(*r).ok().and_then(|o| o.as_array().ok()).cloned().unwrap_or_default(),
+      Object::Array(a) => a.clone(),
+      _ => Vec::new(),
+    });
+    // /Type 缺失时按有没有 /Kids 判断是中间节点还是页
+    let is_tree = match ty { Some(t) => t == b"Pages", None => kids.is_some() };
+    if !is_tree {
+      self.leaves.push((id, inherited.clone()));
+      return 1;
+    }
+
+    let mut attrs = inherited.clone();
+    for key in INHERITABLE {
+      if let Ok(v) = dict.get(key) { attrs.set(key, v.clone()); }
+    }
+    let declared = dict.get(b"Count").and_then(Object::as_i64).ok();
+    if parent.is_some() { self.report.flattened_nodes += 1; }
+
+    let mut count = 0;
+    for kid in kids.unwrap_or_default() {
+      match kid.as_reference() {
+        Ok(kid_id) => count += self.node(kid_id, Some(id), &attrs, depth + 1),
+        Err(_) => self.report.dropped_kids += 1,
+      }
+    }
+    match declared {
+      None => self.report.missing_count += 1,
+      Some(c) if c != i64::from(count) => self.report.wrong_count += 1,
+      _ => {}
+    }
+    count
+  }
+}
+
+/// 目录的 /Pages 不可用时：按对象号顺序收集所有 /Type /Page 对象
+fn orphan_pages(doc: &Document) -> Vec<(ObjectId, Dictionary)> {
+  doc.objects.iter()
+    .filter(|(_, o)| o.as_dict().ok().and_then(|d| d.get(b"Type").ok()).and_then(|t| t.as_name().ok()) == Some(&b"Page"[..]))
+    .map(|(id, _)| (*id, Dictionary::new()))
+    .collect()
+}
+
+/// pages_id 改为唯一的 /Pages 节点，所有页直接挂在其下；继承属性补到没有自带该属性的页上
+fn write_flat_tree(doc: &mut Document, root: ObjectId, pages_id: ObjectId, leaves: Vec<(ObjectId, Dictionary)>) -> Result<(), String> {
+  let count = leaves.len() as i64;
+  let mut kids = Vec::with_capacity(leaves.len());
+  for (id, attrs) in leaves {
+    let Ok(Object::Dictionary(page)) = doc.get_object_mut(id) else { return Err(format!("页面对象 {} {} 不是字典", id.0, id.1)) };
+    page.set("Type", Object::Name(b"Page".to_vec()));
+    page.set("Parent", Object::Reference(pages_id));
+    for (key, value) in attrs.iter() {
+      if !page.has(key) { page.set(key.clone(), value.clone()); }
+    }
+    kids.push(Object::Reference(id));
+  }
+
+  let mut node = Dictionary::new();
+  node.set("Type", Object::Name(b"Pages".to_vec()));
+  node.set("Kids", kids);
+  node.set("Count", count);
+  doc.objects.insert(pages_id, Object::Dictionary(node));
+
+  match doc.get_object_mut(root) {
+    Ok(Object::Dictionary(cat)) => { cat.set("Pages", Object::Reference(pages_id)); Ok(()) }
+    _ => Err("文档目录不是字典".into()),
+  }
+}
@@ -0,0 +1,59 @@
+//! Perceptual hash — per-page DCT pHash of low-DPI grayscale renders, for near-duplicate detection across files
+use tauri::AppHandle;
+use image::imageops::{self, FilterType};
+use std::{f64::consts::PI, fs, path::Path};
+
+use crate::common::{stage_input, work_dir, InputOne, PageGuard};
+use crate::render::{render_pages, RenderMode};
+
+/// 只看整体版面，低分辨率即可，也能抹平字体微调 / 重新压缩带来的差异
+const HASH_DPI: u32 = 36;
+/// DCT 输入边长
+const DCT_SIZE: usize = 32;
+/// 取左上角低频块边长（8×8 = 64 位）
+const LOW_FREQ: usize = 8;
+
+/// 每页 64 位 pHash（16 位十六进制）按页序拼接；视觉相同、字节不同的文档得到相同结果，
+/// 近似文档可逐页比较汉明距离
+#[tauri::command]
+pub async fn perceptual_hash(app: AppHandle, input: InputOne, guard: Option<PageGuard>) -> Result<String, String> {
+  let work = work_dir(&app, "phash")?;
+  let res = run_phash(&app, &work, &input, guard.unwrap_or_default());
+  let _ = fs::remove_dir_all(&work);
+  res
+}
+
+fn run_phash(app: &AppHandle, work: &Path, input: &InputOne, guard: PageGuard) -> Result<String, String> {
+  let in_path = stage_input(work, input)?;
+  guard.check(app, &in_path)?;
+  let pngs = render_pages(app, &in_path, &work.join("render"), HASH_DPI, RenderMode::Gray, None)?;
+  if pngs.is_empty() { return Err("没有可渲染的页面".into()); }
+  pngs.iter().map(|png| page_hash(png).map(|h| format!("{h:016x}"))).collect()
+}
+
+fn page_hash(png: &Path) -> Result<u64, String> {
+  let img = image::open(png).map_err(|e| format!("读取渲染结果失败：{e}"))?.to_luma8();
+  let small = imageops::resize(&img, DCT_SIZE as u32, DCT_SIZE as u32, FilterType::Triangle);
+  let px: Vec<f64> = small.pixels().map(|p| p.0[0] as f64).collect();
+
+  // 二维 DCT-II，只算需要的低频块
+  let cos = |k: usize, n: usize| ((2 * n + 1) as f64 * k as f64 * PI / (2 * DCT_SIZE) as f64).cos();
+  let mut coeffs = Vec::with_capacity(LOW_FREQ * LOW_FREQ);
+  for u in 0..LOW_FREQ {
+    for v in 0..LOW_FREQ {
+      let mut sum = 0.0;
+      for y in 0..DCT_SIZE {
+        for x in 0..DCT_SIZE {
+          sum += px[y * DCT_SIZE + x] * cos(u, y) * cos(v, x);
+        }
+      }
+      coeffs.push(sum);
+    }
+  }
+
+  // 去掉直流分量后取中位数，高于中位数记 1
+  let mut ac: Vec<f64> = coeffs[1..].to_vec();
+  ac.sort_by(|a, b| a.total_cmp(b));
+  let median = ac[ac.len() / 2];
+  Ok(coeffs.iter().enumerate().skip(1).fold(0u64, |h, (i, &c)| if c > median { h | 1 << i } else { h }))
+}
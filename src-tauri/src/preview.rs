@@ -0,0 +1,37 @@
+//! Preview — first N pages (qpdf) + diagonal text watermark (lopdf) as one shareable teaser PDF
+use tauri::AppHandle;
+use lopdf::Document;
+use std::{fs, path::Path};
+
+use crate::common::{
+  assemble_pages, assert_input_not_output, page_count, save_pdf, stage_input, work_dir, AtomicOutput, InputOne,
+};
+use crate::naming::resolve_output;
+use crate::stamp::stamp_text_watermark;
+
+/// 取前 pages 页并在每页叠加 watermark_text；pages 须在 1 到总页数之间
+#[tauri::command]
+pub async fn make_preview(app: AppHandle, input: InputOne, output: String, pages: u32, watermark_text: String) -> Result<String, String> {
+  let output = resolve_output(&output, "preview", &[(&input).into()])?;
+  if pages == 0 { return Err("预览页数须大于 0".into()); }
+  assert_input_not_output(&input, &output)?;
+  let out = AtomicOutput::new(&output)?;
+  let work = work_dir(&app, "preview")?;
+  let res = run_preview(&app, &work, &input, &out.path(), pages, &watermark_text);
+  let _ = fs::remove_dir_all(&work);
+  res?;
+  out.commit()?;
+  Ok(output)
+}
+
+fn run_preview(app: &AppHandle, work: &Path, input: &InputOne, output: &str, pages: u32, text: &str) -> Result<(), String> {
+  let in_path = stage_input(work, input)?;
+  let total = page_count(app, &in_path)?;
+  if pages > total { return Err(format!("预览页数 {pages} 超出文档页数（共 {total} 页）")); }
+
+  let head = work.join("head.pdf").to_string_lossy().to_string();
+  assemble_pages(app, &in_path, &[(in_path.clone(), format!("1-{pages}"))], &head)?;
+  let mut doc = Document::load(&head).map_err(|e| format!("解析预览失败：{e}"))?;
+  stamp_text_watermark(&mut doc, text)?;
+  save_pdf(&mut doc, output)
+}
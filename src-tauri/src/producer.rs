@@ -0,0 +1,71 @@
+//! Producer — overwrite /Producer and /Creator (Info + XMP) so outputs don't carry "GPL Ghostscript" / "qpdf"
+use tauri::AppHandle;
+use lopdf::{Document, Object};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::fs;
+
+use crate::common::{assert_input_not_output, encode_text, info_mut, load_pdf, run_qpdf, save_pdf, InputOne};
+use crate::naming::resolve_output;
+
+/// XMP 中的生成工具字段，元素与属性两种写法都有
+static XMP_FIELDS: Lazy<[(Regex, Regex); 2]> = Lazy::new(|| {
+  ["pdf:Producer", "xmp:CreatorTool"].map(|tag| {
+    (
+      Regex::new(&format!(r"(?s)(<{tag}>).*?(</{tag}>)")).unwrap(),
+      Regex::new(&format!(r#"({tag}=")[^"]*(")"#)).unwrap(),
+    )
+  })
+});
+
+/// 改写生成工具信息；producer 省略或为空时用应用名（tauri.conf 的 productName）
+#[tauri::command]
+pub async fn set_producer(app: AppHandle, input: InputOne, output: String, producer: Option<String>) -> Result<String, String> {
+  let output = resolve_output(&output, "producer", &[(&input).into()])?;
+  assert_input_not_output(&input, &output)?;
+  let name = producer_label(&app, producer.as_deref());
+  let mut doc = load_pdf(&input)?;
+  stamp_producer(&mut doc, &name)?;
+  save_pdf(&mut doc, &output)?;
+  Ok(output)
+}
+
+pub(crate) fn producer_label(app: &AppHandle, producer: Option<&str>) -> String {
+  match producer.map(str::trim) {
+    Some(p) if !p.is_empty() => p.to_string(),
+    _ => app.package_info().name.clone(),
+  }
+}
+
+/// Info 的 /Producer /Creator 与 XMP 的 pdf:Producer / xmp:CreatorTool 统一改为 name
+pub(crate) fn stamp_producer(doc: &mut Document, name: &str) -> Result<(), String> {
+  let info = info_mut(doc)?;
+  info.set("Producer", encode_text(name));
+  info.set("Creator", encode_text(name));
+
+  let Some(id) = doc.catalog().ok().and_then(|c| c.get(b"Metadata").ok()).and_then(|o| o.as_reference().ok()) else { return Ok(()) };
+  let Ok(Object::Stream(s)) = doc.get_object_mut(id) else { return Ok(()) };
+  let Ok(bytes) = s.decompressed_content().or_else(|_| if s.dict.has(b"Filter") { Err(()) } else { Ok(s.content.clone()) }) else {
+    return Ok(()); // 解不开的 XMP 原样保留
+  };
+  let escaped = name.replace('&', "&amp;").replace('<', "&lt;").replace('"', "&quot;");
+  let mut xmp = String::from_utf8_lossy(&bytes).into_owned();
+  let replacement = format!("${{1}}{}${{2}}", escaped.replace('$', "$$"));
+  for (element, attr) in XMP_FIELDS.iter() {
+    xmp = element.replace_all(&xmp, replacement.as_str()).into_owned();
+    xmp = attr.replace_all(&xmp, replacement.as_str()).into_owned();
+  }
+  s.set_plain_content(xmp.into_bytes());
+  Ok(())
+}
+
+/// 引擎输出已落盘时用：lopdf 改完写到旁边，再由 qpdf 重新生成对象流写回 path（lopdf 不写对象流，体积会回涨）
+pub(crate) fn rewrite_producer(app: &AppHandle, path: &str, name: &str) -> Result<(), String> {
+  let mut doc = Document::load(path).map_err(|e| format!("解析输出失败：{e}"))?;
+  stamp_producer(&mut doc, name)?;
+  let tmp = format!("{path}.producer");
+  save_pdf(&mut doc, &tmp)?;
+  let res = run_qpdf(app, &["--object-streams=generate".into(), tmp.clone(), path.into()]);
+  let _ = fs::remove_file(&tmp);
+  res.map(|_| ())
+}
@@ -0,0 +1,379 @@
+//! Job queue — enqueue mixed operations, one worker drains them in order with a single queue:progress event stream;
+//! cancel_batch can roll back the outputs of jobs enqueued with rollback_on_cancel
+use tauri::{AppHandle, Emitter, Manager};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+  collections::{HashSet, VecDeque},
+  ffi::OsString,
+  fs,
+  io::Write,
+  path::{Path, PathBuf},
+  sync::{Mutex, MutexGuard},
+};
+
+use crate::common::{InputOne, PageGuard};
+use crate::compress::{compress, CompressOptions, CompressPreset};
+use crate::flatten::flatten_to_images;
+use crate::linearize::linearize;
+use crate::manifest::utc_now;
+use crate::merge::{merge, Inputs, MergeOptions};
+use crate::split::{split_pdf, SplitOptions};
+use crate::tiff::{to_tiff, TiffCompression};
+
+const PROGRESS_EVT: &str = "queue:progress";
+
+// 与导出同样的串行化：同一时刻只有一个 worker 在跑任务
+static WORKER_LOCK: Lazy<tokio::sync::Mutex<()>> = Lazy::new(|| tokio::sync::Mutex::new(()));
+static QUEUE: Lazy<Mutex<QueueState>> = Lazy::new(|| Mutex::new(QueueState::default()));
+
+/// 可排队的操作，字段与对应命令参数一致；op 为标签，如 { op: "compress", input, output, preset }
+#[derive(Deserialize)]
+#[serde(tag = "op", rename_all = "camelCase")]
+pub enum OpSpec {
+  Compress { input: InputOne, output: String, preset: CompressPreset, options: Option<CompressOptions> },
+  Merge { inputs: Inputs, output: String, options: Option<MergeOptions> },
+  #[serde(rename_all = "camelCase")]
+  Split { input: InputOne, ranges: Vec<String>, out_dir: String, options: Option<SplitOptions> },
+  #[serde(rename_all = "camelCase")]
+  Linearize { input: InputOne, output: String, in_place: Option<bool> },
+  Tiff { input: InputOne, output: String, dpi: u32, compression: TiffCompression, guard: Option<PageGuard> },
+  #[serde(rename_all = "camelCase")]
+  Flatten { input: InputOne, output: String, dpi: u32, guard: Option<PageGuard>, render_with_annotations: Option<bool> },
+}
+
+impl OpSpec {
+  fn name(&self) -> &'static str {
+    match self {
+      OpSpec::Compress { .. } => "compress",
+      OpSpec::Merge { .. } => "merge",
+      OpSpec::Split { .. } => "split",
+      OpSpec::Linearize { .. } => "linearize",
+      OpSpec::Tiff { .. } => "tiff",
+      OpSpec::Flatten { .. } => "flatten",
+    }
+  }
+
+  /// 直接调用对应命令，结果转成 JSON 随 done 事件发给前端
+  async fn run(self, app: AppHandle) -> Result<Value, String> {
+    match self {
+      OpSpec::Compress { input, output, preset, options } => json(compress(app, input, output, preset, options).await),
+      OpSpec::Merge { inputs, output, options } => json(merge(app, inputs, output, options).await),
+      OpSpec::Split { input, ranges, out_dir, options } => json(split_pdf(app, input, ranges, out_dir, options).await),
+      OpSpec::Linearize { input, output, in_place } => json(linearize(app, input, output, in_place).await),
+      OpSpec::Tiff { input, output, dpi, compression, guard } => json(to_tiff(app, input, output, dpi, compression, guard).await),
+      OpSpec::Flatten { input, output, dpi, guard, render_with_annotations } => {
+        json(flatten_to_images(app, input, output, dpi, guard, render_with_annotations).await)
+      }
+    }
+  }
+}
+
+fn json<T: Serialize>(res: Result<T, String>) -> Result<Value, String> {
+  serde_json::to_value(res?).map_err(|e| format!("序列化结果失败：{e}"))
+}
+
+struct Job {
+  id: u64,
+  spec: OpSpec,
+  /// 本轮被 cancel_batch 取消时删除该任务写出的文件
+  rollback: bool,
+}
+
+#[derive(Default)]
+struct QueueState {
+  next_id: u64,
+  pending: VecDeque<Job>,
+  /// 已有 worker 在处理（新任务只入队，不再起 worker）
+  draining: bool,
+  /// 本轮（队列清空前）已开始的任务数
+  started: usize,
+  /// 本轮开启 rollback_on_cancel 且已结束（成功或失败）的任务写出的内容；本轮正常结束时清空
+  written: Vec<JobOutputs>,
+  /// cancel_batch 进行中：worker 做完当前任务即结束本轮，written 留待回滚
+  cancelling: bool,
+}
+
+impl QueueState {
+  /// 本轮总数 = 已开始 + 仍在排队
+  fn total(&self) -> usize {
+    self.started + self.pending.len()
+  }
+}
+
+#[derive(Debug, Serialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+enum Phase { Queued, Running, Done, Failed, Cancelled }
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct QueueProgress {
+  job_id: u64,
+  op: &'static str,
+  phase: Phase,
+  /// 本轮中的序号（1 起）
+  index: usize,
+  total: usize,
+  /// done 时为命令返回值
+  result: Option<Value>,
+  /// failed 时为错误信息
+  error: Option<String>,
+}
+
+fn lock() -> MutexGuard<'static, QueueState> {
+  QUEUE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn emit(app: &AppHandle, job_id: u64, op: &'static str, phase: Phase, index: usize, total: usize) {
+  let _ = app.emit(PROGRESS_EVT, QueueProgress { job_id, op, phase, index, total, result: None, error: None });
+}
+
+/// 入队并返回任务号；队列空闲时随即开始处理。rollback_on_cancel 为 true 时记录该任务写出的文件，供 cancel_batch 回滚
+#[tauri::command]
+pub async fn enqueue(app: AppHandle, op: OpSpec, rollback_on_cancel: Option<bool>) -> Result<u64, String> {
+  let name = op.name();
+  let (id, total, spawn) = {
+    let mut q = lock();
+    q.next_id += 1;
+    let id = q.next_id;
+    q.pending.push_back(Job { id, spec: op, rollback: rollback_on_cancel.unwrap_or(false) });
+    let spawn = !q.draining;
+    q.draining = true;
+    (id, q.total(), spawn)
+  };
+  emit(&app, id, name, Phase::Queued, total, total);
+  if spawn { tauri::async_runtime::spawn(drain(app)); }
+  Ok(id)
+}
+
+/// 取消仍在排队的任务；已开始的任务无法中断，返回 false
+#[tauri::command]
+pub async fn cancel_job(app: AppHandle, job_id: u64) -> Result<bool, String> {
+  let (job, index, total) = {
+    let mut q = lock();
+    let Some(pos) = q.pending.iter().position(|j| j.id == job_id) else { return Ok(false) };
+    let job = q.pending.remove(pos).expect("position 来自同一队列");
+    (job, q.started + pos + 1, q.total())
+  };
+  emit(&app, job.id, job.spec.name(), Phase::Cancelled, index, total);
+  Ok(true)
+}
+
+#[derive(Debug, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RollbackReport {
+  /// 从队列中取消的排队任务
+  pub cancelled: Vec<u64>,
+  /// 删除的输出文件（及随之创建、已清空的目录）
+  pub removed: Vec<String>,
+  /// 由 .bak 还原的原地修改文件
+  pub restored: Vec<String>,
+  /// 删除 / 还原失败的项及原因
+  pub failed: Vec<String>,
+  /// 回滚记录追加写入的日志文件
+  pub log: Option<String>,
+}
+
+/// 取消本轮：排队中的任务全部取消；正在执行的任务无法中断，等它结束后，
+/// 本轮开启 rollback_on_cancel 的任务写出的文件一并删除、原地修改由 .bak 还原（倒序），记录追加到日志目录的 rollback.log
+#[tauri::command]
+pub async fn cancel_batch(app: AppHandle) -> Result<RollbackReport, String> {
+  let (jobs, base, total, active) = {
+    let mut q = lock();
+    let active = q.draining;
+    if active { q.cancelling = true; }
+    let (base, total) = (q.started, q.total());
+    (q.pending.drain(..).collect::<Vec<Job>>(), base, total, active)
+  };
+  for (i, job) in jobs.iter().enumerate() { emit(&app, job.id, job.spec.name(), Phase::Cancelled, base + i + 1, total); }
+  let mut report = RollbackReport { cancelled: jobs.iter().map(|j| j.id).collect(), ..Default::default() };
+  if !active { return Ok(report); }
+
+  // 拿到 worker 锁即当前任务已结束、本轮已收尾
+  let guard = WORKER_LOCK.lock().await;
+  let written = std::mem::take(&mut lock().written);
+  let mut log = Vec::new();
+  for w in written.into_iter().rev() { w.roll_back(&mut report, &mut log); }
+  if !log.is_empty() { report.log = append_log(&app, &log); }
+  drop(guard);
+
+  // 取消期间新入队的任务照常开始
+  let respawn = {
+    let mut q = lock();
+    q.cancelling = false;
+    let respawn = !q.pending.is_empty() && !q.draining;
+    if respawn { q.draining = true; }
+    respawn
+  };
+  if respawn { tauri::async_runtime::spawn(drain(app)); }
+  Ok(report)
+}
+
+fn append_log(app: &AppHandle, lines: &[String]) -> Option<String> {
+  let dir = app.path().app_log_dir().ok()?;
+  fs::create_dir_all(&dir).ok()?;
+  let path = dir.join("rollback.log");
+  let mut f = fs::OpenOptions::new().create(true).append(true).open(&path).ok()?;
+  let now = utc_now();
+  for line in lines { writeln!(f, "{now}\t{line}").ok()?; }
+  Some(path.to_string_lossy().to_string())
+}
+
+/// 一个任务写出的内容：成功时只认结果里报告的、且任务开始前不存在的文件；失败时没有结果可依，按输出目录前后的文件名差异
+struct JobOutputs {
+  job_id: u64,
+  created: Vec<PathBuf>,
+  /// 原地修改：(备份, 原文件, 备份是本任务新建的)
+  restore: Option<(PathBuf, PathBuf, bool)>,
+  /// 输出目录是本任务新建的（拆分）
+  new_dir: Option<PathBuf>,
+}
+
+impl JobOutputs {
+  fn roll_back(self, report: &mut RollbackReport, log: &mut Vec<String>) {
+    let id = self.job_id;
+    for f in &self.created {
+      let shown = f.to_string_lossy().to_string();
+      match fs::remove_file(f) {
+        Ok(()) => { log.push(format!("job {id}\tremoved\t{shown}")); report.removed.push(shown); }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => report.failed.push(format!("删除失败：{e}（{shown}）")),
+      }
+    }
+    if let Some((bak, original, bak_is_new)) = &self.restore {
+      // 备份是本任务新建的就移回去；原本就有的 .bak 已被本任务覆盖为修改前的内容，复制回去并保留
+      let res = if *bak_is_new { fs::rename(bak, original) } else { fs::copy(bak, original).map(|_| ()) };
+      let shown = original.to_string_lossy().to_string();
+      match res {
+        Ok(()) => { log.push(format!("job {id}\trestored\t{shown}")); report.restored.push(shown); }
+        Err(e) => report.failed.push(format!("还原失败：{e}（{shown}）")),
+      }
+    }
+    // 只删空目录；用户后来放进去的文件不动
+    if let Some(dir) = &self.new_dir {
+      if fs::remove_dir(dir).is_ok() {
+        let shown = dir.to_string_lossy().to_string();
+        log.push(format!("job {id}\tremoved\t{shown}"));
+        report.removed.push(shown);
+      }
+    }
+  }
+}
+
+/// 任务开始前记下输出目录里已有的文件名
+struct Tracker {
+  dir: PathBuf,
+  dir_existed: bool,
+  before: HashSet<OsString>,
+  /// 原地修改的原文件
+  in_place: Option<PathBuf>,
+}
+
+impl Tracker {
+  fn new(spec: &OpSpec) -> Self {
+    let (dir, in_place) = match spec {
+      OpSpec::Compress { input, output, options, .. } => in_place_target(input, output, options.as_ref().is_some_and(|o| o.in_place)),
+      OpSpec::Linearize { input, output, in_place } => in_place_target(input, output, in_place.unwrap_or(false)),
+      OpSpec::Merge { output, .. } | OpSpec::Tiff { output, .. } | OpSpec::Flatten { output, .. } => (parent_dir(output), None),
+      OpSpec::Split { out_dir, .. } => (PathBuf::from(out_dir), None),
+    };
+    let before: HashSet<OsString> = fs::read_dir(&dir)
+      .map(|it| it.filter_map(|e| e.ok().map(|e| e.file_name())).collect())
+      .unwrap_or_default();
+    Tracker { dir_existed: dir.is_dir(), dir, before, in_place }
+  }
+
+  /// 结果 JSON 里出现的路径中，位于输出目录、开始前不存在、现在是文件的即本任务写出的
+  fn finish(self, job_id: u64, result: &Value) -> JobOutputs {
+    let mut strings = Vec::new();
+    collect_strings(result, &mut strings);
+    let mut created: Vec<PathBuf> = strings.iter()
+      .map(PathBuf::from)
+      .filter(|p| parent_dir(&p.to_string_lossy()) == self.dir && p.is_file())
+      .filter(|p| p.file_name().is_some_and(|n| !self.before.contains(n)))
+      .collect();
+    created.sort();
+    created.dedup();
+    let restore = self.in_place.map(|original| {
+      let mut bak = original.clone().into_os_string();
+      bak.push(".bak");
+      let bak = PathBuf::from(bak);
+      let is_new = bak.file_name().is_some_and(|n| !self.before.contains(n));
+      (bak, original, is_new)
+    }).filter(|(bak, _, _)| bak.is_file());
+    let new_dir = (!self.dir_existed).then_some(self.dir);
+    JobOutputs { job_id, created, restore, new_dir }
+  }
+
+  /// 任务失败（如拆分写到一半出错）：输出目录里任务开始后新出现的文件都算本任务写出的；
+  /// .bak 不删也不还原（失败的原地修改在提交前就已中止，原文件未被覆盖）
+  fn finish_failed(self, job_id: u64) -> JobOutputs {
+    let mut created: Vec<PathBuf> = fs::read_dir(&self.dir)
+      .map(|it| it.filter_map(|e| e.ok()).collect::<Vec<_>>())
+      .unwrap_or_default()
+      .into_iter()
+      .filter(|e| !self.before.contains(&e.file_name()))
+      .map(|e| e.path())
+      .filter(|p| p.is_file() && p.extension().is_none_or(|x| x != "bak"))
+      .collect();
+    created.sort();
+    let new_dir = (!self.dir_existed).then_some(self.dir);
+    JobOutputs { job_id, created, restore: None, new_dir }
+  }
+}
+
+fn in_place_target(input: &InputOne, output: &str, in_place: bool) -> (PathBuf, Option<PathBuf>) {
+  match input {
+    InputOne::Path(p) if in_place => (parent_dir(p), Some(PathBuf::from(p))),
+    _ => (parent_dir(output), None),
+  }
+}
+
+fn parent_dir(path: &str) -> PathBuf {
+  match Path::new(path).parent() {
+    Some(d) if !d.as_os_str().is_empty() => d.to_path_buf(),
+    _ => PathBuf::from("."),
+  }
+}
+
+fn collect_strings(v: &Value, out: &mut Vec<String>) {
+  match v {
+    Value::String(s) => out.push(s.clone()),
+    Value::Array(items) => items.iter().for_each(|i| collect_strings(i, out)),
+    Value::Object(map) => map.values().for_each(|i| collect_strings(i, out)),
+    _ => {}
+  }
+}
+
+async fn drain(app: AppHandle) {
+  let _guard = WORKER_LOCK.lock().await;
+  loop {
+    let (job, index, total) = {
+      let mut q = lock();
+      let next = if q.cancelling { None } else { q.pending.pop_front() };
+      let Some(job) = next else {
+        q.draining = false;
+        q.started = 0;
+        if !q.cancelling { q.written.clear(); }
+        return;
+      };
+      q.started += 1;
+      (job, q.started, q.total())
+    };
+    let op = job.spec.name();
+    emit(&app, job.id, op, Phase::Running, index, total);
+    let tracker = job.rollback.then(|| Tracker::new(&job.spec));
+    let res = job.spec.run(app.clone()).await;
+    if let Some(t) = tracker {
+      let outputs = match &res { Ok(v) => t.finish(job.id, v), Err(_) => t.finish_failed(job.id) };
+      lock().written.push(outputs);
+    }
+    let total = lock().total();
+    let event = match res {
+      Ok(v) => QueueProgress { job_id: job.id, op, phase: Phase::Done, index, total, result: Some(v), error: None },
+      Err(e) => QueueProgress { job_id: job.id, op, phase: Phase::Failed, index, total, result: None, error: Some(e) },
+    };
+    let _ = app.emit(PROGRESS_EVT, event);
+  }
+}
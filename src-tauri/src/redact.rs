@@ -0,0 +1,413 @@
+//! Redact — opaque black boxes over regions + removal of the text / images underneath from the page content stream.
+//!
+//! 局限（调用方需知晓）：
+//! - 只处理页面级内容流；Form XObject 与区域相交时整个删除，不做局部裁剪
+//! - 图片与区域相交即整张删除（包括区域外的部分），不做像素级裁切
+//! - 字宽取自字体 /Widths、/W；缺失时按估计值，区域边缘的字形可能多删或少删
+//! - 矢量图形不删除，仅被黑框覆盖
+//! - 书签、元数据、结构树 /ActualText、表单值等页面外的文字不处理
+use lopdf::{content::Operation, Dictionary, Document, Object, ObjectId};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::common::{assert_input_not_output, load_pdf, save_pdf, InputOne};
+use crate::images::write_content;
+use crate::inspect::{inherited, matrix, mul, resolve, IDENTITY};
+use crate::naming::resolve_output;
+use crate::stamp::append_page_content;
+
+/// 字体缺少宽度信息时的估计字宽（千分之一 em）
+const FALLBACK_WIDTH: f32 = 500.0;
+/// 字形框相对字号的下沿 / 上沿
+const DESCENT: f32 = -0.2;
+const ASCENT: f32 = 0.8;
+
+#[derive(Debug, Deserialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct RedactRegion {
+  pub page: u32,
+  /// [x0, y0, x1, y1]，pt，PDF 坐标（原点左下）
+  pub rect: [f32; 4],
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RedactReport {
+  pub path: String,
+  /// 删除的字形数
+  pub glyphs_removed: u32,
+  /// 删除的图片 / Form XObject 绘制数（含内嵌图片）
+  pub images_removed: u32,
+  /// 删除的注释数（与区域相交的链接、批注等）
+  pub annotations_removed: u32,
+  pub pages: Vec<u32>,
+}
+
+#[tauri::command]
+pub async fn redact_regions(input: InputOne, output: String, regions: Vec<RedactRegion>) -> Result<RedactReport, String> {
+  let output = resolve_output(&output, "redact", &[(&input).into()])?;
+  if regions.is_empty() { return Err("请提供至少一个涂黑区域".into()); }
+  assert_input_not_output(&input, &output)?;
+  let mut doc = load_pdf(&input)?;
+  if doc.is_encrypted() { return Err("加密文档请先解密再涂黑".into()); }
+  let pages = doc.get_pages();
+
+  let mut by_page: BTreeMap<u32, Vec<[f32; 4]>> = BTreeMap::new();
+  for r in &regions {
+    let [x0, y0, x1, y1] = r.rect;
+    if !pages.contains_key(&r.page) { return Err(format!("页码越界：{}（文档共 {} 页）", r.page, pages.len())); }
+    if !(x1 > x0 && y1 > y0) { return Err(format!("第 {} 页的区域无效：{:?}", r.page, r.rect)); }
+    by_page.entry(r.page).or_default().push(r.rect);
+  }
+
+  let mut report = RedactReport { path: output.clone(), glyphs_removed: 0, images_removed: 0, annotations_removed: 0, pages: Vec::new() };
+  let mut dropped_xobjects = HashSet::new();
+  for (&no, rects) in &by_page {
+    let page_id = pages[&no];
+    let (glyphs, images) = scrub_content(&mut doc, page_id, rects, &mut dropped_xobjects)?;
+    let annots = remove_annotations(&mut doc, page_id, rects)?;
+
+    // 黑框画在最上层
+    let mut ops = b"0 g\n".to_vec();
+    for [x0, y0, x1, y1] in rects {
+      ops.extend(format!("{x0} {y0} {} {} re f\n", x1 - x0, y1 - y0).into_bytes());
+    }
+    append_page_content(&mut doc, page_id, ops)?;
+
+    report.glyphs_removed += glyphs;
+    report.images_removed += images;
+    report.annotations_removed += annots;
+    report.pages.push(no);
+  }
+
+  drop_unused_xobjects(&mut doc, &dropped_xobjects)?;
+  doc.prune_objects();
+  save_pdf(&mut doc, &output)?;
+  Ok(report)
+}
+
+// ---------- 内容流 ----------
+
+/// 字体宽度表（千分之一 em）
+struct FontMetrics {
+  /// Type0（按 2 字节编码，假定 Identity-H）
+  two_byte: bool,
+  widths: HashMap<u32, f32>,
+  default_width: f32,
+}
+
+impl FontMetrics {
+  fn load(doc: &Document, font: &Dictionary) -> Self {
+    let num = |o: &Object| resolve(doc, o).as_float().ok();
+    if font.get(b"Subtype").and_then(|o| o.as_name()).is_ok_and(|n| n == b"Type0") {
+      let cid = font.get(b"DescendantFonts").ok()
+        .and_then(|o| resolve(doc, o).as_array().ok())
+        .and_then(|a| a.first())
+        .and_then(|o| resolve(doc, o).as_dict().ok());
+      let mut widths = HashMap::new();
+      let default_width = cid.and_then(|d| d.get(b"DW").ok()).and_then(num).unwrap_or(1000.0);
+      // /W [c [w1 w2 ...] c1 c2 w ...]
+      if let Some(w) = cid.and_then(|d| d.get(b"W").ok()).and_then(|o| resolve(doc, o).as_array().ok()) {
+        let mut i = 0;
+        while i + 1 < w.len() {
+          let Some(first) = num(&w[i]) else { break };
+          match resolve(doc, &w[i + 1]) {
+            Object::Array(list) => {
+              for (k, v) in list.iter().enumerate() {
+                if let Some(v) = num(v) { widths.insert(first as u32 + k as u32, v); }
+              }
+              i += 2;
+            }
+            other => {
+              let (Some(last), Some(v)) = (other.as_float().ok(), w.get(i + 2).and_then(num)) else { break };
+              for c in first as u32..=last as u32 { widths.insert(c, v); }
+              i += 3;
+            }
+          }
+        }
+      }
+      return FontMetrics { two_byte: true, widths, default_width };
+    }
+
+    let first = font.get(b"FirstChar").ok().and_then(num).unwrap_or(0.0) as u32;
+    let widths = font.get(b"Widths").ok()
+      .and_then(|o| resolve(doc, o).as_array().ok())
+      .map(|a| a.iter().enumerate().filter_map(|(k, v)| num(v).map(|v| (first + k as u32, v))).collect())
+      .unwrap_or_default();
+    FontMetrics { two_byte: false, widths, default_width: FALLBACK_WIDTH }
+  }
+
+  fn codes(&self, bytes: &[u8]) -> Vec<(u32, std::ops::Range<usize>)> {
+    let step = if self.two_byte { 2 } else { 1 };
+    (0..bytes.len()).step_by(step).map(|i| {
+      let end = (i + step).min(bytes.len());
+      let code = bytes[i..end].iter().fold(0u32, |c, &b| c << 8 | b as u32);
+      (code, i..end)
+    }).collect()
+  }
+
+  fn width(&self, code: u32) -> f32 {
+    self.widths.get(&code).copied().filter(|w| *w > 0.0).unwrap_or(self.default_width)
+  }
+}
+
+/// q / Q 保存的图形状态（含文字状态参数）
+#[derive(Clone)]
+struct GState {
+  ctm: [f32; 6],
+  char_spacing: f32,
+  word_spacing: f32,
+  h_scale: f32,
+  leading: f32,
+  font: Option<Vec<u8>>,
+  font_size: f32,
+  rise: f32,
+}
+
+struct Scrubber<'a> {
+  rects: &'a [[f32; 4]],
+  fonts: HashMap<Vec<u8>, FontMetrics>,
+  gs: GState,
+  stack: Vec<GState>,
+  tm: [f32; 6],
+  tlm: [f32; 6],
+  glyphs_removed: u32,
+}
+
+impl Scrubber<'_> {
+  fn hits(&self, m: &[f32; 6], x0: f32, y0: f32, x1: f32, y1: f32) -> bool {
+    let pts = [(x0, y0), (x1, y0), (x1, y1), (x0, y1)].map(|(x, y)| (x * m[0] + y * m[2] + m[4], x * m[1] + y * m[3] + m[5]));
+    let (bx0, bx1) = pts.iter().fold((f32::MAX, f32::MIN), |(a, b), p| (a.min(p.0), b.max(p.0)));
+    let (by0, by1) = pts.iter().fold((f32::MAX, f32::MIN), |(a, b), p| (a.min(p.1), b.max(p.1)));
+    self.rects.iter().any(|r| bx0 < r[2] && bx1 > r[0] && by0 < r[3] && by1 > r[1])
+  }
+
+  fn next_line(&mut self, tx: f32, ty: f32) {
+    self.tlm = mul(&[1.0, 0.0, 0.0, 1.0, tx, ty], &self.tlm);
+    self.tm = self.tlm;
+  }
+
+  /// 逐字形判断是否落在区域内；有删除时返回改写后的 TJ 数组（删掉的字形换成等宽的位移，保持后续排版不变）
+  fn show(&mut self, items: &[Object]) -> Option<Vec<Object>> {
+    let gs = self.gs.clone();
+    let metrics = gs.font.as_ref().and_then(|f| self.fonts.get(f));
+    let scale = gs.font_size * gs.h_scale;
+    let mut out = Vec::with_capacity(items.len());
+    let mut changed = false;
+    let push_kern = |out: &mut Vec<Object>, n: f32| match out.last_mut() {
+      Some(Object::Real(prev)) => *prev += n,
+      _ => out.push(Object::Real(n)),
+    };
+
+    for item in items {
+      match item {
+        Object::String(bytes, fmt) => {
+          let codes = match metrics {
+            Some(m) => m.codes(bytes),
+            None => (0..bytes.len()).map(|i| (bytes[i] as u32, i..i + 1)).collect(),
+          };
+          let mut kept = Vec::new();
+          for (code, range) in codes {
+            let w0 = metrics.map_or(FALLBACK_WIDTH, |m| m.width(code)) / 1000.0;
+            let space = if range.len() == 1 && code == 32 { gs.word_spacing } else { 0.0 };
+            let tx = (w0 * gs.font_size + gs.char_spacing + space) * gs.h_scale;
+            let trm = mul(&self.tm, &gs.ctm);
+            let hit = self.hits(&trm, 0.0, gs.rise + DESCENT * gs.font_size, w0 * scale, gs.rise + ASCENT * gs.font_size);
+            if hit && scale != 0.0 {
+              if !kept.is_empty() { out.push(Object::String(std::mem::take(&mut kept), *fmt)); }
+              push_kern(&mut out, -tx * 1000.0 / scale);
+              self.glyphs_removed += 1;
+              changed = true;
+            } else {
+              kept.extend_from_slice(&bytes[range]);
+            }
+            self.tm = mul(&[1.0, 0.0, 0.0, 1.0, tx, 0.0], &self.tm);
+          }
+          if !kept.is_empty() { out.push(Object::String(kept, *fmt)); }
+        }
+        other => {
+          if let Ok(n) = other.as_float() {
+            self.tm = mul(&[1.0, 0.0, 0.0, 1.0, -n / 1000.0 * scale, 0.0], &self.tm);
+            push_kern(&mut out, n);
+          }
+        }
+      }
+    }
+    changed.then_some(out)
+  }
+}
+
+/// 改写页面内容流，返回 (删除字形数, 删除图片 / 表单绘制数)
+fn scrub_content(doc: &mut Document, page_id: ObjectId, rects: &[[f32; 4]], dropped: &mut HashSet<ObjectId>) -> Result<(u32, u32), String> {
+  let mut content = doc.get_and_decode_page_content(page_id).map_err(|e| format!("解析内容流失败：{e}"))?;
+  let resources = inherited(doc, page_id, b"Resources").map(|o| resolve(doc, o)).and_then(|o| o.as_dict().ok());
+  let fonts: HashMap<Vec<u8>, FontMetrics> = resources
+    .and_then(|r| r.get(b"Font").ok())
+    .and_then(|o| resolve(doc, o).as_dict().ok())
+    .map(|fs| fs.iter().filter_map(|(n, o)| resolve(doc, o).as_dict().ok().map(|d| (n.clone(), FontMetrics::load(doc, d)))).collect())
+    .unwrap_or_default();
+  // 名称 → (对象号, 单位空间到用户空间的额外矩阵与外框)
+  let xobjects: HashMap<Vec<u8>, (ObjectId, [f32; 6], [f32; 4])> = resources
+    .and_then(|r| r.get(b"XObject").ok())
+    .and_then(|o| resolve(doc, o).as_dict().ok())
+    .map(|xs| xs.iter().filter_map(|(n, o)| {
+      let id = o.as_reference().ok()?;
+      let Ok(Object::Stream(s)) = doc.get_object(id) else { return None };
+      let form = s.dict.get(b"Subtype").and_then(|o| o.as_name()).is_ok_and(|n| n == b"Form");
+      if !form { return Some((n.clone(), (id, IDENTITY, [0.0, 0.0, 1.0, 1.0]))); }
+      let bbox: Vec<f32> = s.dict.get(b"BBox").ok()?.as_array().ok()?.iter().filter_map(|v| v.as_float().ok()).collect();
+      let m = s.dict.get(b"Matrix").ok().and_then(|o| o.as_array().ok()).and_then(|a| matrix(a)).unwrap_or(IDENTITY);
+      Some((n.clone(), (id, m, [*bbox.first()?, *bbox.get(1)?, *bbox.get(2)?, *bbox.get(3)?])))
+    }).collect())
+    .unwrap_or_default();
+
+  let gs = GState { ctm: IDENTITY, char_spacing: 0.0, word_spacing: 0.0, h_scale: 1.0, leading: 0.0, font: None, font_size: 0.0, rise: 0.0 };
+  let mut s = Scrubber { rects, fonts, gs, stack: Vec::new(), tm: IDENTITY, tlm: IDENTITY, glyphs_removed: 0 };
+  let mut images_removed = 0;
+  let mut ops = Vec::with_capacity(content.operations.len());
+  let f = |o: &[Object], i: usize| o.get(i).and_then(|v| v.as_float().ok()).unwrap_or(0.0);
+
+  for op in content.operations.drain(..) {
+    let a = &op.operands;
+    match op.operator.as_str() {
+      "q" => s.stack.push(s.gs.clone()),
+      "Q" => if let Some(g) = s.stack.pop() { s.gs = g; },
+      "cm" => if let Some(m) = matrix(a) { s.gs.ctm = mul(&m, &s.gs.ctm); },
+      "BT" => { s.tm = IDENTITY; s.tlm = IDENTITY; }
+      "Tc" => s.gs.char_spacing = f(a, 0),
+      "Tw" => s.gs.word_spacing = f(a, 0),
+      "Tz" => s.gs.h_scale = f(a, 0) / 100.0,
+      "TL" => s.gs.leading = f(a, 0),
+      "Ts" => s.gs.rise = f(a, 0),
+      "Tf" => {
+        s.gs.font = a.first().and_then(|o| o.as_name().ok()).map(<[u8]>::to_vec);
+        s.gs.font_size = f(a, 1);
+      }
+      "Td" => s.next_line(f(a, 0), f(a, 1)),
+      "TD" => { s.gs.leading = -f(a, 1); s.next_line(f(a, 0), f(a, 1)); }
+      "Tm" => if let Some(m) = matrix(a) { s.tm = m; s.tlm = m; },
+      "T*" => { let l = s.gs.leading; s.next_line(0.0, -l); }
+      "Tj" | "TJ" | "'" | "\"" => {
+        if op.operator == "\"" && a.len() == 3 {
+          s.gs.word_spacing = f(a, 0);
+          s.gs.char_spacing = f(a, 1);
+          ops.push(Operation::new("Tw", vec![a[0].clone()]));
+          ops.push(Operation::new("Tc", vec![a[1].clone()]));
+        }
+        if op.operator == "'" || op.operator == "\"" {
+          let l = s.gs.leading;
+          s.next_line(0.0, -l);
+          ops.push(Operation::new("T*", vec![]));
+        }
+        let items = match (op.operator.as_str(), a.last()) {
+          ("TJ", Some(Object::Array(arr))) => arr.clone(),
+          (_, Some(str_obj @ Object::String(..))) => vec![str_obj.clone()],
+          _ => Vec::new(),
+        };
+        match s.show(&items) {
+          Some(rewritten) => ops.push(Operation::new("TJ", vec![Object::Array(rewritten)])),
+          None if op.operator == "'" || op.operator == "\"" => ops.push(Operation::new("Tj", vec![a.last().cloned().unwrap_or(Object::Null)])),
+          None => ops.push(op),
+        }
+        continue;
+      }
+      "Do" => {
+        let hit = a.first().and_then(|o| o.as_name().ok()).and_then(|n| xobjects.get(n)).and_then(|(id, m, [x0, y0, x1, y1])| {
+          s.hits(&mul(m, &s.gs.ctm), *x0, *y0, *x1, *y1).then_some(*id)
+        });
+        if let Some(id) = hit {
+          dropped.insert(id);
+          images_removed += 1;
+          continue;
+        }
+      }
+      // 内嵌图片画在单位正方形
+      "BI" => if s.hits(&s.gs.ctm, 0.0, 0.0, 1.0, 1.0) { images_removed += 1; continue; },
+      _ => {}
+    }
+    ops.push(op);
+  }
+
+  if s.glyphs_removed > 0 || images_removed > 0 {
+    content.operations = ops;
+    write_content(doc, page_id, &content)?;
+  }
+  Ok((s.glyphs_removed, images_removed))
+}
+
+/// 删除 Rect 与区域相交的注释
+fn remove_annotations(doc: &mut Document, page_id: ObjectId, rects: &[[f32; 4]]) -> Result<u32, String> {
+  let annots: Vec<Object> = match doc.get_dictionary(page_id).map_err(|e| format!("读取页面失败：{e}"))?.get(b"Annots") {
+    Ok(o) => resolve(doc, o).as_array().map(|a| a.clone()).unwrap_or_default(),
+    Err(_) => return Ok(0),
+  };
+  let hits = |annot: &Object| -> bool {
+    let Ok(d) = resolve(doc, annot).as_dict() else { return false };
+    let Ok(r) = d.get(b"Rect").map(|o| resolve(doc, o)).and_then(|o| o.as_array()) else { return false };
+    let v: Vec<f32> = r.iter().filter_map(|o| o.as_float().ok()).collect();
+    let [a, b, c, e] = v[..] else { return false };
+    let (x0, x1, y0, y1) = (a.min(c), a.max(c), b.min(e), b.max(e));
+    rects.iter().any(|r| x0 < r[2] && x1 > r[0] && y0 < r[3] && y1 > r[1])
+  };
+  let kept: Vec<Object> = annots.iter().filter(|a| !hits(a)).cloned().collect();
+  let removed = (annots.len() - kept.len()) as u32;
+  if removed > 0 {
+    match doc.get_object_mut(page_id) {
+      Ok(Object::Dictionary(page)) => page.set("Annots", kept),
+      _ => return Err("页面对象不是字典".into()),
+    }
+  }
+  Ok(removed)
+}
+
+/// 被删绘制的 XObject 若其他页面也不再引用，从所有页面资源中摘除，之后由 prune_objects 清掉对象本身
+fn drop_unused_xobjects(doc: &mut Document, dropped: &HashSet<ObjectId>) -> Result<(), String> {
+  if dropped.is_empty() { return Ok(()); }
+  let mut still_used = HashSet::new();
+  let mut holders = HashSet::new();
+  for (_, page_id) in doc.get_pages() {
+    let Some(res) = inherited(doc, page_id, b"Resources") else { continue };
+    let Some(xobj) = resolve(doc, res).as_dict().ok().and_then(|r| r.get(b"XObject").ok()) else { continue };
+    let Ok(xs) = resolve(doc, xobj).as_dict() else { continue };
+    let used: HashSet<Vec<u8>> = doc.get_and_decode_page_content(page_id).map(|c| {
+      c.operations.iter().filter(|op| op.operator == "Do").filter_map(|op| op.operands.first()?.as_name().ok().map(<[u8]>::to_vec)).collect()
+    }).unwrap_or_default();
+    for (name, o) in xs.iter() {
+      if let Ok(id) = o.as_reference() {
+        if used.contains(name) { still_used.insert(id); }
+      }
+    }
+    // XObject 字典所在：间接对象，或页面 / 资源字典本身
+    holders.insert(match xobj { Object::Reference(id) => Some(*id), _ => None }.map_or(Holder::Page(page_id), Holder::Dict));
+  }
+  let unused: HashSet<ObjectId> = dropped.difference(&still_used).copied().collect();
+  if unused.is_empty() { return Ok(()); }
+
+  for holder in holders {
+    let xs = match holder {
+      Holder::Dict(id) => doc.get_object_mut(id).ok().and_then(|o| o.as_dict_mut().ok()),
+      Holder::Page(page_id) => page_xobject_dict_mut(doc, page_id),
+    };
+    if let Some(xs) = xs {
+      let names: Vec<Vec<u8>> = xs.iter().filter(|(_, o)| o.as_reference().is_ok_and(|id| unused.contains(&id))).map(|(n, _)| n.clone()).collect();
+      for n in names { xs.remove(&n); }
+    }
+  }
+  Ok(())
+}
+
+#[derive(PartialEq, Eq, Hash)]
+enum Holder {
+  Dict(ObjectId),
+  Page(ObjectId),
+}
+
+/// 页面直接持有（或经间接 Resources 持有）的 XObject 字典；继承自父节点的不处理
+fn page_xobject_dict_mut(doc: &mut Document, page_id: ObjectId) -> Option<&mut Dictionary> {
+  let res_ref = doc.get_dictionary(page_id).ok()?.get(b"Resources").ok()?.as_reference().ok();
+  let res = match res_ref {
+    Some(id) => doc.get_object_mut(id).ok()?.as_dict_mut().ok()?,
+    None => doc.get_object_mut(page_id).ok()?.as_dict_mut().ok()?.get_mut(b"Resources").ok()?.as_dict_mut().ok()?,
+  };
+  res.get_mut(b"XObject").ok()?.as_dict_mut().ok()
+}
@@ -0,0 +1,198 @@
+//! Rasterize — Ghostscript page rendering to PNG for pixel analysis / thumbnails (+ positioned text via txtwrite)
+use tauri::AppHandle;
+use image::imageops::{self, FilterType};
+use std::{
+  fs,
+  path::{Path, PathBuf},
+};
+
+use crate::common::run_gs;
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum RenderMode { Gray, Rgb, Jpeg }
+
+impl RenderMode {
+  fn device_ext(self) -> (&'static str, &'static str) {
+    match self {
+      RenderMode::Gray => ("pnggray", "png"),
+      RenderMode::Rgb => ("png16m", "png"),
+      RenderMode::Jpeg => ("jpeg", "jpg"),
+    }
+  }
+}
+
+/// 渲染 input 的页面到 out_dir/page_00001.png（Jpeg 模式为 .jpg）...，pages 为 (first, last) 闭区间，None 为全部
+pub(crate) fn render_pages(app: &AppHandle, input: &str, out_dir: &Path, dpi: u32, mode: RenderMode, pages: Option<(u32, u32)>) -> Result<Vec<PathBuf>, String> {
+  render_pages_with(app, input, out_dir, dpi, mode, pages, false)
+}
+
+/// annotations 为 true 时显式绘制批注与表单控件（-dShowAnnots / -dShowAcroForm；
+/// 缺外观流的字段在 NeedAppearances 下由 gs 生成），预览与阅读器显示一致
+pub(crate) fn render_pages_with(
+  app: &AppHandle,
+  input: &str,
+  out_dir: &Path,
+  dpi: u32,
+  mode: RenderMode,
+  pages: Option<(u32, u32)>,
+  annotations: bool,
+) -> Result<Vec<PathBuf>, String> {
+  fs::create_dir_all(out_dir).map_err(|e| format!("创建渲染目录失败：{e}"))?;
+  let (device, ext) = mode.device_ext();
+  let mut args: Vec<String> = vec![
+    format!("-sDEVICE={device}"),
+    format!("-r{dpi}"),
+    "-dTextAlphaBits=4".into(),
+    "-dGraphicsAlphaBits=4".into(),
+    "-dNOPAUSE".into(), "-dQUIET".into(), "-dBATCH".into(),
+  ];
+  if let RenderMode::Jpeg = mode { args.push("-dJPEGQ=80".into()); }
+  if annotations { args.extend(["-dShowAnnots=true".into(), "-dShowAcroForm=true".into()]); }
+  if let Some((first, last)) = pages {
+    args.push(format!("-dFirstPage={first}"));
+    args.push(format!("-dLastPage={last}"));
+  }
+  args.push(format!("-sOutputFile={}", out_dir.join(format!("page_%05d.{ext}")).display()));
+  args.push(input.into());
+  run_gs(app, &args)?;
+
+  let mut files: Vec<PathBuf> = fs::read_dir(out_dir).map_err(|e| format!("读取渲染目录失败：{e}"))?
+    .flatten()
+    .map(|e| e.path())
+    .filter(|p| p.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with("page_") && n.ends_with(&format!(".{ext}"))))
+    .collect();
+  files.sort();
+  Ok(files)
+}
+
+/// 平均亮度 0.0（全黑）–1.0（全白）
+pub(crate) fn mean_luminance(png: &Path) -> Result<f32, String> {
+  let img = image::open(png).map_err(|e| format!("读取渲染结果失败：{e}"))?.to_luma8();
+  let n = img.pixels().len().max(1) as f64;
+  let sum: f64 = img.pixels().map(|p| p.0[0] as f64).sum();
+  Ok((sum / n / 255.0) as f32)
+}
+
+/// SSIM 分块边长
+const SSIM_BLOCK: u32 = 8;
+
+/// 两张渲染结果的结构相似度：灰度 8×8 不重叠块 SSIM 的均值（0–1）；尺寸不同时 b 缩放到 a 的尺寸
+pub(crate) fn similarity(a: &Path, b: &Path) -> Result<f32, String> {
+  let open = |p: &Path| image::open(p).map(|i| i.to_luma8()).map_err(|e| format!("读取渲染结果失败：{e}"));
+  let a = open(a)?;
+  let mut b = open(b)?;
+  if b.dimensions() != a.dimensions() { b = imageops::resize(&b, a.width(), a.height(), FilterType::Triangle); }
+  // 常数取 SSIM 论文默认值 (0.01·255)² / (0.03·255)²
+  const C1: f64 = 6.5025;
+  const C2: f64 = 58.5225;
+  let (bw, bh) = (SSIM_BLOCK.min(a.width()), SSIM_BLOCK.min(a.height()));
+  let (mut sum, mut n) = (0.0, 0usize);
+  for y0 in (0..=a.height().saturating_sub(bh)).step_by(bh.max(1) as usize) {
+    for x0 in (0..=a.width().saturating_sub(bw)).step_by(bw.max(1) as usize) {
+      let px: Vec<(f64, f64)> = (y0..y0 + bh)
+        .flat_map(|y| (x0..x0 + bw).map(move |x| (x, y)))
+        .map(|(x, y)| (a.get_pixel(x, y).0[0] as f64, b.get_pixel(x, y).0[0] as f64))
+        .collect();
+      let k = px.len().max(1) as f64;
+      let (ma, mb) = (px.iter().map(|p| p.0).sum::<f64>() / k, px.iter().map(|p| p.1).sum::<f64>() / k);
+      let (mut va, mut vb, mut cov) = (0.0, 0.0, 0.0);
+      for (pa, pb) in &px {
+        va += (pa - ma).powi(2);
+        vb += (pb - mb).powi(2);
+        cov += (pa - ma) * (pb - mb);
+      }
+      let (va, vb, cov) = (va / k, vb / k, cov / k);
+      sum += ((2.0 * ma * mb + C1) * (2.0 * cov + C2)) / ((ma * ma + mb * mb + C1) * (va + vb + C2));
+      n += 1;
+    }
+  }
+  Ok((sum / n.max(1) as f64).clamp(0.0, 1.0) as f32)
+}
+
+/// 通道差（max − min）超过该值的像素算彩色，容忍抗锯齿 / JPEG 噪点造成的轻微偏色
+const CHROMA_TOLERANCE: u8 = 24;
+/// 彩色像素占比超过该值才算彩色页，忽略零星杂点
+const COLOR_PIXEL_FRACTION: f64 = 0.0005;
+
+/// 判断彩色只需低分辨率
+const COLOR_DETECT_DPI: u32 = 36;
+
+/// 按页序给出每页是否为彩色页（渲染到 out_dir）
+pub(crate) fn classify_color(app: &AppHandle, input: &str, out_dir: &Path) -> Result<Vec<bool>, String> {
+  render_pages(app, input, out_dir, COLOR_DETECT_DPI, RenderMode::Rgb, None)?.iter().map(|png| is_color_page(png)).collect()
+}
+
+/// RGB 渲染结果是否含有可见彩色
+fn is_color_page(png: &Path) -> Result<bool, String> {
+  let img = image::open(png).map_err(|e| format!("读取渲染结果失败：{e}"))?.to_rgb8();
+  let n = img.pixels().len().max(1) as f64;
+  let colored = img.pixels().filter(|p| {
+    let [r, g, b] = p.0;
+    r.max(g).max(b) - r.min(g).min(b) > CHROMA_TOLERANCE
+  }).count();
+  Ok(colored as f64 / n > COLOR_PIXEL_FRACTION)
+}
+
+// ---------- txtwrite：带坐标的文字 ----------
+
+/// 单个字符及其外框 [x0, y0, x1, y1]（pt，原点在页面左上角，y 向下）
+pub(crate) struct PositionedChar {
+  pub bbox: [f32; 4],
+  pub c: String,
+}
+
+/// 同一字体字号的一段文字；字符按书写顺序
+pub(crate) struct PositionedSpan {
+  /// 字号（pt）
+  pub size: f32,
+  pub chars: Vec<PositionedChar>,
+}
+
+/// 每页的文字段（span）
+pub(crate) type PageSpans = Vec<PositionedSpan>;
+
+/// gs txtwrite -dTextFormat=0：逐字符输出外框，按 <page> 切分；pages 为 (first, last) 闭区间，None 为全部
+pub(crate) fn positioned_text(app: &AppHandle, input: &str, out_dir: &Path, pages: Option<(u32, u32)>) -> Result<Vec<PageSpans>, String> {
+  fs::create_dir_all(out_dir).map_err(|e| format!("创建临时目录失败：{e}"))?;
+  let xml = out_dir.join("text.xml");
+  let mut args: Vec<String> = vec![
+    "-sDEVICE=txtwrite".into(),
+    "-dTextFormat=0".into(),
+    "-dNOPAUSE".into(), "-dQUIET".into(), "-dBATCH".into(),
+  ];
+  if let Some((first, last)) = pages {
+    args.push(format!("-dFirstPage={first}"));
+    args.push(format!("-dLastPage={last}"));
+  }
+  args.push(format!("-sOutputFile={}", xml.display()));
+  args.push(input.into());
+  run_gs(app, &args)?;
+  let text = fs::read_to_string(&xml).map_err(|e| format!("读取文字提取结果失败：{e}"))?;
+
+  let mut pages = Vec::new();
+  for page in text.split("<page").skip(1) {
+    let mut spans: PageSpans = Vec::new();
+    for span in page.split("<span").skip(1) {
+      // <span bbox="..." font="..." size="12.0000">
+      let size = span.split('>').next().and_then(|tag| attr(tag, "size")).and_then(|v| v.parse().ok()).unwrap_or(0.0);
+      let chars = span.split("<char").skip(1).filter_map(|c| {
+        let bbox: Vec<f32> = attr(c, "bbox")?.split_whitespace().filter_map(|v| v.parse().ok()).collect();
+        let [x0, y0, x1, y1] = bbox[..] else { return None };
+        Some(PositionedChar { bbox: [x0, y0, x1, y1], c: unescape(attr(c, "c")?) })
+      }).collect();
+      spans.push(PositionedSpan { size, chars });
+    }
+    pages.push(spans);
+  }
+  Ok(pages)
+}
+
+fn attr<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+  let start = tag.find(&format!("{name}=\""))? + name.len() + 2;
+  let len = tag[start..].find('"')?;
+  Some(&tag[start..start + len])
+}
+
+fn unescape(s: &str) -> String {
+  s.replace("&lt;", "<").replace("&gt;", ">").replace("&quot;", "\"").replace("&apos;", "'").replace("&amp;", "&")
+}
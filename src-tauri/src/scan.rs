@@ -0,0 +1,173 @@
+//! Scan cleanup — detect & fix negative (white-on-black) scanned pages; MRC-style size optimization via OCRmyPDF (+ jbig2)
+use tauri::{AppHandle, Emitter};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::{
+  collections::VecDeque,
+  fs,
+  io::{BufRead, BufReader},
+  path::Path,
+  process::{Command, Stdio},
+};
+
+use crate::common::{
+  assemble_pages, assert_input_not_output, assert_output_not_same, ensure_parent_dir, find_gs, find_jbig2,
+  find_ocrmypdf, find_tesseract, page_count, page_runs, parse_range, run_gs, stage_input, work_dir, AtomicOutput,
+  InputOne, PageGuard,
+};
+use crate::naming::resolve_output;
+use crate::render::{mean_luminance, render_pages, RenderMode};
+
+/// 低分辨率渲染即可判断整体明暗
+const DETECT_DPI: u32 = 24;
+/// 平均亮度低于该值（0–1）视为反色页
+const INVERTED_THRESHOLD: f32 = 0.35;
+
+#[tauri::command]
+pub async fn detect_inverted(app: AppHandle, input: InputOne, guard: Option<PageGuard>) -> Result<Vec<u32>, String> {
+  let work = work_dir(&app, "detect_inverted")?;
+  let res = run_detect(&app, &work, &input, guard.unwrap_or_default());
+  let _ = fs::remove_dir_all(&work);
+  res
+}
+
+fn run_detect(app: &AppHandle, work: &Path, input: &InputOne, guard: PageGuard) -> Result<Vec<u32>, String> {
+  let in_path = stage_input(work, input)?;
+  guard.check(app, &in_path)?;
+  let pngs = render_pages(app, &in_path, &work.join("render"), DETECT_DPI, RenderMode::Gray, None)?;
+  let mut flagged = Vec::new();
+  for (i, png) in pngs.iter().enumerate() {
+    if mean_luminance(png)? < INVERTED_THRESHOLD { flagged.push(i as u32 + 1); }
+  }
+  Ok(flagged)
+}
+
+/// 对指定页施加反色传递函数（{1 exch sub} settransfer，pdfwrite 以 /Apply 方式烘焙），其余页原样保留
+#[tauri::command]
+pub async fn invert_pages(app: AppHandle, input: InputOne, output: String, ranges: Vec<String>) -> Result<Vec<u32>, String> {
+  if ranges.is_empty() { return Err("请提供至少一个页范围".into()); }
+  ensure_parent_dir(&output)?;
+  let work = work_dir(&app, "invert")?;
+  let res = run_invert(&app, &work, &input, &output, &ranges);
+  let _ = fs::remove_dir_all(&work);
+  res
+}
+
+fn run_invert(app: &AppHandle, work: &Path, input: &InputOne, output: &str, ranges: &[String]) -> Result<Vec<u32>, String> {
+  let in_path = stage_input(work, input)?;
+  assert_output_not_same(&in_path, output)?;
+  let total = page_count(app, &in_path)?;
+
+  let mut targets: Vec<u32> = Vec::new();
+  for r in ranges { targets.extend(parse_range(r, total)?); }
+  targets.sort_unstable();
+  targets.dedup();
+
+  // ① 抽出目标页 → ② gs 反色 → ③ 按原页序拼回
+  let selected = work.join("selected.pdf").to_string_lossy().to_string();
+  let sel_runs = page_runs(&targets.iter().map(|&p| (in_path.clone(), p)).collect::<Vec<_>>());
+  assemble_pages(app, &in_path, &sel_runs, &selected)?;
+
+  let inverted = work.join("inverted.pdf").to_string_lossy().to_string();
+  let args: Vec<String> = vec![
+    "-sDEVICE=pdfwrite".into(),
+    "-dTransferFunctionInfo=/Apply".into(),
+    "-dNOPAUSE".into(), "-dQUIET".into(), "-dBATCH".into(),
+    format!("-sOutputFile={inverted}"),
+    "-c".into(), "<< /BeginPage { pop { 1 exch sub } settransfer } >> setpagedevice".into(),
+    "-f".into(), selected,
+  ];
+  run_gs(app, &args)?;
+
+  let order: Vec<(String, u32)> = (1..=total).map(|p| match targets.binary_search(&p) {
+    Ok(k) => (inverted.clone(), k as u32 + 1),
+    Err(_) => (in_path.clone(), p),
+  }).collect();
+  assemble_pages(app, &in_path, &page_runs(&order), output)?;
+  Ok(targets)
+}
+
+const OPTIMIZE_PROGRESS_EVT: &str = "optimize_scan:progress";
+/// ocrmypdf 日志里按页的行以页码开头，如 "    3 [tesseract] ..."
+static PAGE_LINE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\s*(\d+)\s").unwrap());
+/// 失败时带回的 stderr 尾部行数
+const ERROR_TAIL_LINES: usize = 20;
+
+#[derive(Debug, Serialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+struct ScanProgress { page: u32, total: u32 }
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OptimizeScanResult {
+  pub path: String,
+  pub pages: u32,
+  pub bytes_before: u64,
+  pub bytes_after: u64,
+  /// 找到了 jbig2 编码器（文字层走 JBIG2，压缩率最高）；否则只做图像重压缩
+  pub jbig2: bool,
+}
+
+/// ocrmypdf --optimize 3：分离文字掩膜与背景分别压缩，没有文字层的页顺带 OCR（--skip-text 保留已有文字）。
+/// 需要 binaries/ocrmypdf、Ghostscript 与 Tesseract（含识别语言数据）；jbig2 可选
+#[tauri::command]
+pub async fn optimize_scan(app: AppHandle, input: InputOne, output: String) -> Result<OptimizeScanResult, String> {
+  let output = resolve_output(&output, "optimize_scan", &[(&input).into()])?;
+  assert_input_not_output(&input, &output)?;
+  let out = AtomicOutput::new(&output)?;
+  let work = work_dir(&app, "optimize_scan")?;
+  let res = run_optimize_scan(&app, &work, &input, &out.path());
+  let _ = fs::remove_dir_all(&work);
+  let (pages, bytes_before, jbig2) = res?;
+  out.commit()?;
+  let bytes_after = fs::metadata(&output).map(|m| m.len()).unwrap_or(0);
+  Ok(OptimizeScanResult { path: output, pages, bytes_before, bytes_after, jbig2 })
+}
+
+fn run_optimize_scan(app: &AppHandle, work: &Path, input: &InputOne, out_path: &str) -> Result<(u32, u64, bool), String> {
+  let in_path = stage_input(work, input)?;
+  let total = page_count(app, &in_path)?;
+  let bytes_before = fs::metadata(&in_path).map(|m| m.len()).unwrap_or(0);
+  let (ocr_dir, exe) = find_ocrmypdf(app).ok_or("未找到 OCRmyPDF：请把 ocrmypdf.exe 放到 binaries/ocrmypdf/")?;
+  let (gs_dir, _, gs_envs) = find_gs(app).ok_or("未找到 Ghostscript：请把 bin/lib/Resource 放到 binaries/ghostscript/")?;
+  let (tess_dir, _, tess_envs) = find_tesseract(app).ok_or("未找到 Tesseract：请把 tesseract.exe 和 tessdata 放到 binaries/tesseract/")?;
+  let jbig2 = find_jbig2(app);
+
+  // ocrmypdf 从 PATH 找 gs / tesseract / jbig2
+  let mut path_dirs = vec![ocr_dir.display().to_string(), gs_dir.display().to_string(), tess_dir.display().to_string()];
+  if let Some((dir, _)) = &jbig2 { path_dirs.push(dir.display().to_string()); }
+  path_dirs.push(std::env::var("PATH").unwrap_or_default());
+
+  let mut child = Command::new(&exe)
+    .args(["--optimize", "3", "--skip-text", "--output-type", "pdf", "--jobs", "1", "-v", "1"])
+    .arg(&in_path)
+    .arg(out_path)
+    .current_dir(&ocr_dir)
+    .env("PATH", path_dirs.join(";"))
+    .envs(gs_envs.iter().chain(&tess_envs).map(|(k, v)| (*k, v)))
+    .stdout(Stdio::null())
+    .stderr(Stdio::piped())
+    .spawn()
+    .map_err(|e| format!("执行失败：{e}（exe: {}）", exe.display()))?;
+
+  let mut tail = VecDeque::with_capacity(ERROR_TAIL_LINES);
+  let mut reported = 0;
+  if let Some(stderr) = child.stderr.take() {
+    for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+      let page = PAGE_LINE.captures(&line).and_then(|c| c[1].parse::<u32>().ok()).filter(|p| (1..=total).contains(p));
+      if let Some(page) = page.filter(|&p| p > reported) {
+        reported = page;
+        let _ = app.emit(OPTIMIZE_PROGRESS_EVT, ScanProgress { page, total });
+      }
+      if tail.len() == ERROR_TAIL_LINES { tail.pop_front(); }
+      tail.push_back(line);
+    }
+  }
+  let status = child.wait().map_err(|e| format!("等待 OCRmyPDF 结束失败：{e}"))?;
+  if !status.success() {
+    return Err(format!("OCRmyPDF 执行失败（退出码 {:?}）：{}", status.code(), Vec::from(tail).join("\n")));
+  }
+  let _ = app.emit(OPTIMIZE_PROGRESS_EVT, ScanProgress { page: total, total });
+  Ok((total, bytes_before, jbig2.is_some()))
+}
@@ -0,0 +1,212 @@
+//! Security — encryption / permission info parsed from qpdf --show-encryption, permission updates via re-encryption
+use tauri::AppHandle;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+use crate::common::{
+  assert_input_not_output, qpdf_exe, run_qpdf, run_with_env, stage_input, work_dir, AtomicOutput, InputOne,
+};
+use crate::naming::resolve_output;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Permission {
+  /// qpdf 的权限描述，如 "print high resolution"、"modify other"
+  pub name: String,
+  pub allowed: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptionInfo {
+  pub encrypted: bool,
+  /// 需要用户密码才能打开；为 false 而 encrypted 为 true 即"仅所有者密码"（能打开、受权限限制）
+  pub has_user_password: bool,
+  /// 40 / 128 / 256；未加密或无法打开时为 None
+  pub key_bits: Option<u32>,
+  /// R 修订号（2–6）
+  pub revision: Option<u32>,
+  /// 加密方法：RC4 / AESv2 / AESv3
+  pub method: Option<String>,
+  pub permissions: Vec<Permission>,
+}
+
+#[tauri::command]
+pub async fn encryption_info(app: AppHandle, input: InputOne) -> Result<EncryptionInfo, String> {
+  let work = work_dir(&app, "encryption")?;
+  let res = stage_input(&work, &input).and_then(|p| show_encryption(&app, &p));
+  let _ = fs::remove_dir_all(&work);
+  res
+}
+
+fn show_encryption(app: &AppHandle, path: &str) -> Result<EncryptionInfo, String> {
+  let (bin_dir, exe) = qpdf_exe(app)?;
+  let out = run_with_env(&bin_dir, &exe, &["--show-encryption".into(), path.into()], &[])?;
+  let stdout = String::from_utf8_lossy(&out.stdout);
+  let stderr = String::from_utf8_lossy(&out.stderr);
+
+  // 空密码打不开 → 设置了用户密码
+  if !out.status.success() && stderr.to_ascii_lowercase().contains("invalid password") {
+    return Ok(EncryptionInfo { encrypted: true, has_user_password: true, key_bits: None, revision: None, method: None, permissions: Vec::new() });
+  }
+  // 退出码 3 仅表示有警告
+  if !matches!(out.status.code(), Some(0) | Some(3)) {
+    return Err(format!("qpdf 读取加密信息失败：{stderr}"));
+  }
+  if stdout.contains("File is not encrypted") {
+    return Ok(EncryptionInfo { encrypted: false, has_user_password: false, key_bits: None, revision: None, method: None, permissions: Vec::new() });
+  }
+
+  Ok(parse_encryption(&stdout))
+}
+
+/// 解析已成功打开的加密文档的 --show-encryption 输出
+fn parse_encryption(stdout: &str) -> EncryptionInfo {
+  let mut revision = None;
+  let mut method = None;
+  let mut permissions = Vec::new();
+  for line in stdout.lines().map(str::trim) {
+    if let Some(r) = line.strip_prefix("R = ") {
+      revision = r.trim().parse::<u32>().ok();
+    } else if let Some(m) = line.strip_prefix("stream encryption method: ") {
+      method = Some(m.trim().to_string());
+    } else if let Some((name, v)) = line.rsplit_once(": ") {
+      match v {
+        "allowed" => permissions.push(Permission { name: name.into(), allowed: true }),
+        "not allowed" => permissions.push(Permission { name: name.into(), allowed: false }),
+        _ => {}
+      }
+    }
+  }
+  let key_bits = match (method.as_deref(), revision) {
+    (Some("AESv3"), _) => Some(256),
+    (Some("AESv2"), _) => Some(128),
+    (_, Some(2)) => Some(40),
+    (_, Some(_)) => Some(128),
+    _ => None,
+  };
+  EncryptionInfo { encrypted: true, has_user_password: false, key_bits, revision, method, permissions }
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PrintPermission {
+  Full,
+  /// 仅低分辨率（40 位加密不区分，按允许处理）
+  Low,
+  None,
+}
+
+/// 新的权限位；未传的字段默认允许
+#[derive(Debug, Deserialize, Clone, Copy)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Permissions {
+  pub print: PrintPermission,
+  /// 修改文档内容
+  pub modify: bool,
+  /// 复制 / 提取文字与图片
+  pub extract: bool,
+  /// 辅助功能提取（40 位加密不支持，忽略）
+  pub accessibility: bool,
+  /// 添加 / 修改注释
+  pub annotate: bool,
+  /// 填写表单（40 位加密随 annotate）
+  pub fill_forms: bool,
+  /// 插入 / 删除 / 旋转页面（40 位加密随 modify）
+  pub assemble: bool,
+}
+
+impl Default for Permissions {
+  fn default() -> Self {
+    Permissions { print: PrintPermission::Full, modify: true, extract: true, accessibility: true, annotate: true, fill_forms: true, assemble: true }
+  }
+}
+
+/// 用所有者密码打开已加密文档，保留原用户 / 所有者密码与密钥长度，只换权限位重新加密
+#[tauri::command]
+pub async fn update_permissions(app: AppHandle, input: InputOne, output: String, password: String, permissions: Permissions) -> Result<String, String> {
+  let output = resolve_output(&output, "permissions", &[(&input).into()])?;
+  if password.is_empty() { return Err("请提供所有者密码".into()); }
+  assert_input_not_output(&input, &output)?;
+  let out = AtomicOutput::new(&output)?;
+  let work = work_dir(&app, "permissions")?;
+  let res = stage_input(&work, &input).and_then(|in_path| reencrypt(&app, &in_path, &out.path(), &password, &permissions));
+  let _ = fs::remove_dir_all(&work);
+  res?;
+  out.commit()?;
+  Ok(output)
+}
+
+/// 用 password 打开加密文档读取 --show-encryption；密码错误或文档未加密时报错，返回 (解析结果, 原始输出)
+pub(crate) fn open_encrypted(app: &AppHandle, path: &str, password: &str) -> Result<(EncryptionInfo, String), String> {
+  let (bin_dir, exe) = qpdf_exe(app)?;
+  let out = run_with_env(&bin_dir, &exe, &[format!("--password={password}"), "--show-encryption".into(), path.into()], &[])?;
+  let stdout = String::from_utf8_lossy(&out.stdout).to_string();
+  let stderr = String::from_utf8_lossy(&out.stderr);
+  if stderr.to_ascii_lowercase().contains("invalid password") { return Err("密码错误".into()); }
+  if !matches!(out.status.code(), Some(0) | Some(3)) {
+    return Err(format!("qpdf 读取加密信息失败：{stderr}"));
+  }
+  if stdout.contains("File is not encrypted") { return Err("文档未加密".into()); }
+  Ok((parse_encryption(&stdout), stdout))
+}
+
+/// 把 source 的加密参数（密钥、密码、权限位）原样套到 input 上写出 output
+pub(crate) fn copy_encryption(app: &AppHandle, source: &str, password: &str, input: &str, output: &str) -> Result<(), String> {
+  run_qpdf(app, &[
+    format!("--copy-encryption={source}"),
+    format!("--encryption-file-password={password}"),
+    input.into(),
+    output.into(),
+  ]).map(|_| ())
+}
+
+fn reencrypt(app: &AppHandle, in_path: &str, out_path: &str, password: &str, perms: &Permissions) -> Result<(), String> {
+  let (bin_dir, exe) = qpdf_exe(app)?;
+  let pw_arg = format!("--password={password}");
+  let (info, stdout) = open_encrypted(app, in_path, password)?;
+  // 用户密码只能查看、不能改权限
+  if !stdout.contains("Supplied password is owner password") { return Err("提供的是用户密码，修改权限需要所有者密码".into()); }
+
+  let bits = info.key_bits.ok_or("无法识别原加密方式")?;
+  // R2–R4 用所有者密码打开时 qpdf 会给出用户密码；AES-256 只能判断用户密码是否为空
+  let user = match stdout.lines().find_map(|l| l.trim().strip_prefix("User password = ")) {
+    Some(u) => u.to_string(),
+    None => {
+      let req = run_with_env(&bin_dir, &exe, &["--requires-password".into(), in_path.into()], &[])?;
+      // 退出码 3：已加密但空密码可打开
+      if req.status.code() != Some(3) {
+        return Err("AES-256 文档无法从所有者密码还原用户密码，请改用重新加密".into());
+      }
+      String::new()
+    }
+  };
+
+  let yn = |b: bool| if b { "y" } else { "n" };
+  let mut args = vec![pw_arg, "--encrypt".into(), user, password.into(), bits.to_string()];
+  if bits == 40 {
+    args.extend([
+      format!("--print={}", yn(perms.print != PrintPermission::None)),
+      format!("--modify={}", yn(perms.modify && perms.assemble)),
+      format!("--extract={}", yn(perms.extract)),
+      format!("--annotate={}", yn(perms.annotate && perms.fill_forms)),
+    ]);
+  } else {
+    let print = match perms.print { PrintPermission::Full => "full", PrintPermission::Low => "low", PrintPermission::None => "none" };
+    args.extend([
+      format!("--print={print}"),
+      format!("--modify-other={}", yn(perms.modify)),
+      format!("--extract={}", yn(perms.extract)),
+      format!("--accessibility={}", yn(perms.accessibility)),
+      format!("--annotate={}", yn(perms.annotate)),
+      format!("--form={}", yn(perms.fill_forms)),
+      format!("--assemble={}", yn(perms.assemble)),
+    ]);
+    if bits == 128 { args.push(format!("--use-aes={}", yn(info.method.as_deref() == Some("AESv2")))); }
+  }
+  args.push("--".into());
+  // 沿用原有的 RC4 / 40 位加密时 qpdf 要求显式允许弱加密
+  if bits == 40 || info.method.as_deref() == Some("RC4") { args.insert(0, "--allow-weak-crypto".into()); }
+  args.extend([in_path.to_string(), out_path.to_string()]);
+  run_qpdf(app, &args).map(|_| ())
+}
@@ -1,12 +1,12 @@
 // src-tauri/src/sign.rs
 use std::{
   fs,
-  io::Write,
+  io::{Read, Seek, SeekFrom, Write},
   path::{Path, PathBuf},
   time::Instant,
 };
 
-use base64::{engine::general_purpose, Engine as _};
+use base64::{engine::general_purpose, read::DecoderReader, Engine as _};
 use memchr::memmem;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
@@ -15,22 +15,32 @@ use tauri::{AppHandle, Emitter};       // v2: emit 需要 Emitter
 use tauri_plugin_dialog::DialogExt;    // v2: 从 AppHandle 拿对话框
 use tokio::sync::Mutex;
 
+use crate::limits::oversize_message;
+use crate::manifest::utc_now;
+
 // 并发互斥（防重复导出）
 static EXPORT_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
 
 const PROGRESS_EVT: &str = "sign:progress";
+// 尾部 %%EOF 检查窗口；B64 按 4 字符对齐取尾，5464 字符 ≈ 4098 字节
+const TAIL_WINDOW: usize = 4096;
+const TAIL_WINDOW_B64: usize = 5464;
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SignAndExportPayload {
-  /// 前端传来的 PDF（已合成落章）的 base64
-  pub pdf_bytes_b64: String,
+  /// 前端传来的 PDF（已合成落章）的 base64；小文件用
+  pub pdf_bytes_b64: Option<String>,
+  /// 大文件：前端已落盘的 PDF 路径（与 pdf_bytes_b64 二选一），后端流式拷贝
+  pub source_path: Option<String>,
   /// 保存对话框的默认文件名（可选）
   pub suggested_name: Option<String>,
   /// 若前端已指定保存路径，后端不再弹窗
   pub target_path: Option<String>,
   /// 允许覆盖
   pub overwrite: Option<bool>,
+  /// 哈希链清单（JSON Lines）：每次导出追加一行，含上一行的哈希，批量归档可证明未被重排 / 篡改
+  pub chain_path: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -40,6 +50,19 @@ pub struct SignExportOk {
   pub bytes_written: usize,
   pub sha256: String,
   pub took_ms: u128,
+  /// 指定 chain_path 时为新追加行的哈希（链头）
+  pub chain_head: Option<String>,
+}
+
+/// 哈希链的一行；行哈希 = 该行 JSON 文本（不含换行）的 SHA-256
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ChainEntry<'a> {
+  timestamp: String,
+  filename: &'a str,
+  sha256: &'a str,
+  /// 上一行的行哈希；首行为 64 个 0
+  prev_hash: String,
 }
 
 #[derive(Debug, Serialize)]
@@ -87,18 +110,19 @@ pub async fn sign_and_export_pdf(app: AppHandle, payload: SignAndExportPayload)
   let t0 = Instant::now();
   emit_progress(&app, &Progress::Prepare);
 
-  // 1) 解码 + 校验
+  // 1) 定位数据源 + 头尾校验（不整体解码，避免大文件双份内存）
   let overwrite = payload.overwrite.unwrap_or(false);
-  let bytes = match decode_b64(&payload.pdf_bytes_b64) {
-    Ok(b) => b,
-    Err(e) => {
-      emit_error(&app, SignErrorCode::EInvalidArg, "base64 解码失败");
-      return Err(err(SignErrorCode::EInvalidArg, format!("base64 解码失败: {e}")));
+  let source = match (payload.pdf_bytes_b64.as_deref(), payload.source_path.as_deref()) {
+    (Some(b64), None) => Source::B64(b64),
+    (None, Some(p)) => Source::File(Path::new(p)),
+    _ => {
+      emit_error(&app, SignErrorCode::EInvalidArg, "pdfBytesB64 与 sourcePath 必须二选一");
+      return Err(err(SignErrorCode::EInvalidArg, "pdfBytesB64 与 sourcePath 必须二选一"));
     }
   };
-  if let Err(m) = validate_pdf(&bytes) {
-    emit_error(&app, SignErrorCode::EInvalidPdf, &m);
-    return Err(err(SignErrorCode::EInvalidPdf, m));
+  if let Err(e) = check_source(&source) {
+    emit_error(&app, e.code, &e.message);
+    return Err(e);
   }
 
   // 2) 解析输出路径（优先 target_path，否则弹 Save）
@@ -118,17 +142,28 @@ pub async fn sign_and_export_pdf(app: AppHandle, payload: SignAndExportPayload)
     return Err(err(SignErrorCode::EExists, "目标已存在，且未允许覆盖"));
   }
 
-  // 3) 原子写入
+  // 3) 流式写入（边解码边哈希）→ 原子重命名
   emit_progress(&app, &Progress::Write);
-  let sha = hex_sha256(&bytes);
-  let written = match atomic_write_all(&out_path, &bytes, overwrite) {
-    Ok(n) => n,
+  let (written, sha) = match write_source(&source, &out_path, overwrite) {
+    Ok(r) => r,
     Err(e) => {
       emit_error(&app, e.code, &e.message);
       return Err(e);
     }
   };
 
+  // 4) 可选：追加哈希链（导出锁保证同一进程内追加有序）
+  let chain_head = match payload.chain_path.as_deref() {
+    Some(chain) => match append_chain(Path::new(chain), &out_path, &sha) {
+      Ok(h) => Some(h),
+      Err(e) => {
+        emit_error(&app, e.code, &e.message);
+        return Err(e);
+      }
+    },
+    None => None,
+  };
+
   emit_progress(&app, &Progress::Done { path: out_path.to_string_lossy().as_ref(), sha256: &sha });
 
   Ok(SignExportOk {
@@ -136,6 +171,7 @@ pub async fn sign_and_export_pdf(app: AppHandle, payload: SignAndExportPayload)
     bytes_written: written,
     sha256: sha,
     took_ms: t0.elapsed().as_millis(),
+    chain_head,
   })
 }
 
@@ -168,30 +204,96 @@ async fn resolve_output_path(app: &AppHandle, target: Option<&str>, suggested: O
   }
 }
 
-fn decode_b64(s: &str) -> Result<Vec<u8>, base64::DecodeError> {
-  general_purpose::STANDARD.decode(s.as_bytes())
+/// 导出数据源：IPC 里的 base64 字符串，或前端已落盘的文件
+enum Source<'a> {
+  B64(&'a str),
+  File(&'a Path),
+}
+
+/// 只取头 8 字节 + 尾部窗口做 PDF 校验，不物化整份数据
+fn check_source(src: &Source) -> SignResult<()> {
+  // 体积上限：base64 按 3/4 折算解码后大小
+  let size = match src {
+    Source::B64(s) => s.len() as u64 / 4 * 3,
+    Source::File(p) => fs::metadata(p).map(|m| m.len()).unwrap_or(0),
+  };
+  if let Some(m) = oversize_message("待签名 PDF", size) { return Err(err(SignErrorCode::EInvalidArg, m)); }
+  let (head, tail) = match src {
+    Source::B64(s) => {
+      let b = s.as_bytes();
+      let head = decode_b64(&b[..b.len().min(12)])
+        .map_err(|e| err(SignErrorCode::EInvalidArg, format!("base64 解码失败: {e}")))?;
+      let tail = decode_b64(&b[b.len().saturating_sub(TAIL_WINDOW_B64)..])
+        .map_err(|e| err(SignErrorCode::EInvalidArg, format!("base64 解码失败: {e}")))?;
+      (head, tail)
+    }
+    Source::File(p) => read_head_tail(p).map_err(|e| map_io("读取源文件失败", e))?,
+  };
+  validate_pdf(&head, &tail).map_err(|m| err(SignErrorCode::EInvalidPdf, m))
+}
+
+pub(crate) fn read_head_tail(p: &Path) -> std::io::Result<(Vec<u8>, Vec<u8>)> {
+  let mut f = fs::File::open(p)?;
+  let len = f.metadata()?.len();
+  let mut head = Vec::with_capacity(8);
+  (&mut f).take(8).read_to_end(&mut head)?;
+  f.seek(SeekFrom::Start(len.saturating_sub(TAIL_WINDOW as u64)))?;
+  let mut tail = Vec::with_capacity(TAIL_WINDOW);
+  f.read_to_end(&mut tail)?;
+  Ok((head, tail))
+}
+
+fn write_source(src: &Source, path: &Path, overwrite: bool) -> SignResult<(usize, String)> {
+  match src {
+    Source::B64(s) => atomic_write_all(path, DecoderReader::new(s.as_bytes(), &general_purpose::STANDARD), overwrite),
+    Source::File(p) => {
+      let f = fs::File::open(p).map_err(|e| map_io("读取源文件失败", e))?;
+      atomic_write_all(path, f, overwrite)
+    }
+  }
+}
+
+fn decode_b64(s: &[u8]) -> Result<Vec<u8>, base64::DecodeError> {
+  general_purpose::STANDARD.decode(s)
 }
 
-fn validate_pdf(bytes: &[u8]) -> Result<(), String> {
-  if bytes.len() < 8 { return Err("PDF 太短".into()); }
-  let head_ok = bytes.starts_with(b"%PDF-");
-  let tail_slice_start = bytes.len().saturating_sub(4096);
-  let tail_ok = memmem::find(&bytes[tail_slice_start..], b"%%EOF").is_some();
+pub(crate) fn validate_pdf(head: &[u8], tail: &[u8]) -> Result<(), String> {
+  if head.len() < 8 { return Err("PDF 太短".into()); }
+  let head_ok = head.starts_with(b"%PDF-");
+  let tail_ok = memmem::find(tail, b"%%EOF").is_some();
   if !head_ok || !tail_ok {
     return Err("不是有效 PDF（缺少头/尾标记）".into());
   }
   Ok(())
 }
 
-fn hex_sha256(data: &[u8]) -> String {
-  let mut h = Sha256::new();
-  h.update(data);
-  let d = h.finalize();
+/// 读最后一行算 prev_hash，追加新行并 fsync，返回新行哈希
+fn append_chain(chain: &Path, exported: &Path, sha: &str) -> SignResult<String> {
+  let prev_hash = match fs::read_to_string(chain) {
+    Ok(text) => match text.lines().rev().find(|l| !l.trim().is_empty()) {
+      Some(last) => hex_digest(&Sha256::digest(last.trim_end().as_bytes())),
+      None => "0".repeat(64),
+    },
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => "0".repeat(64),
+    Err(e) => return Err(map_io("读取哈希链失败", e)),
+  };
+  let filename = exported.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+  let entry = ChainEntry { timestamp: utc_now(), filename: &filename, sha256: sha, prev_hash };
+  let line = serde_json::to_string(&entry).map_err(|e| err(SignErrorCode::EUnknown, format!("序列化哈希链失败: {e}")))?;
+
+  let mut f = fs::OpenOptions::new().create(true).append(true).open(chain).map_err(|e| map_io("打开哈希链失败", e))?;
+  f.write_all(format!("{line}\n").as_bytes())
+    .and_then(|_| f.sync_all())
+    .map_err(|e| map_io("写入哈希链失败", e))?;
+  Ok(hex_digest(&Sha256::digest(line.as_bytes())))
+}
+
+fn hex_digest(d: &[u8]) -> String {
   d.iter().map(|b| format!("{:02x}", b)).collect()
 }
 
-/// 在目标目录创建临时文件 → 写入 + fsync → 覆盖/重命名到目标
-fn atomic_write_all(path: &Path, data: &[u8], overwrite: bool) -> SignResult<usize> {
+/// 在目标目录创建临时文件 → 分块读取 src 写入（同时计算 SHA-256）+ fsync → 覆盖/重命名到目标
+fn atomic_write_all<R: Read>(path: &Path, mut src: R, overwrite: bool) -> SignResult<(usize, String)> {
   let dir = path.parent().ok_or_else(|| err(SignErrorCode::EInvalidArg, "输出路径无父目录"))?;
   if !dir.exists() {
     return Err(err(SignErrorCode::EPermission, "输出目录不存在或无权限"));
@@ -202,9 +304,26 @@ fn atomic_write_all(path: &Path, data: &[u8], overwrite: bool) -> SignResult<usi
 
   let mut tmp = tempfile::NamedTempFile::new_in(dir)
     .map_err(|e| map_io("创建临时文件失败", e))?;
+  let mut hasher = Sha256::new();
+  let mut buf = vec![0u8; 256 * 1024];
+  let mut total = 0usize;
+  loop {
+    let n = match src.read(&mut buf) {
+      Ok(0) => break,
+      Ok(n) => n,
+      Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+      // DecoderReader 遇到非法 base64 时报 InvalidData
+      Err(e) if e.kind() == std::io::ErrorKind::InvalidData => {
+        return Err(err(SignErrorCode::EInvalidArg, format!("base64 解码失败: {e}")));
+      }
+      Err(e) => return Err(map_io("读取源数据失败", e)),
+    };
+    hasher.update(&buf[..n]);
+    tmp.as_file_mut().write_all(&buf[..n]).map_err(|e| map_io("写入失败", e))?;
+    total += n;
+  }
   tmp.as_file_mut()
-    .write_all(data)
-    .and_then(|_| tmp.as_file_mut().flush())
+    .flush()
     .and_then(|_| tmp.as_file_mut().sync_all())
     .map_err(|e| map_io("写入失败", e))?;
 
@@ -212,7 +331,7 @@ fn atomic_write_all(path: &Path, data: &[u8], overwrite: bool) -> SignResult<usi
     fs::remove_file(path).map_err(|e| map_io("删除旧文件失败", e))?;
   }
   match tmp.persist(path) {
-    Ok(_) => Ok(data.len()),
+    Ok(_) => Ok((total, hex_digest(&hasher.finalize()))),
     Err(e) => Err(map_io("原子重命名失败", e.error)),
   }
 }
@@ -1,49 +1,702 @@
-// src-tauri/src/split.rs
-use tauri::AppHandle;
-use tauri_plugin_shell::ShellExt;
+//! Split PDF — qpdf page ranges → one file per range (+ optional per-part compression / bookmarks / text boundaries / fixed-size bursting / color vs B/W / CSV split map / page-size changes / per-part export to PNG, TIFF or text)
+use tauri::{AppHandle, Emitter};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, fs, path::Path};
+
+use crate::common::{
+  ensure_parent_dir, page_count, page_count_with, page_runs, parse_range, run_qpdf, sanitize, save_pdf, stage_input, work_dir,
+  InputOne, PageGuard,
+};
+use crate::compress::{run_path, CompressOptions, CompressPreset, CompressResult};
+use crate::forms::prune_acroform;
+use crate::inspect::{page_rect, page_text};
+use crate::limits::check_input;
+use crate::security::{copy_encryption, open_encrypted};
+use crate::manifest::{describe, write_manifest};
+use crate::naming::{expand_tokens, first_free_seq};
+use crate::render::{classify_color, positioned_text, render_pages, PageSpans, RenderMode};
+use crate::tiff::{write_tiff, TiffCompression};
+use crate::outline::{read_outline, remap, write_outline, OutlineNode};
+
+const PROGRESS_EVT: &str = "split:progress";
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "phase", rename_all = "lowercase")]
+enum Progress<'a> {
+  Split { index: usize, total: usize, range: &'a str },
+  Compress { index: usize, total: usize, range: &'a str },
+  Done { total: usize },
+}
+
+/// 拆分可选参数（均可省略）
+#[derive(Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SplitOptions {
+  /// 每段只保留目标页落在本段的书签，并改为本段页码
+  pub preserve_bookmarks: bool,
+  /// 输出文件名模板；省略时为 split_01_1-3.pdf
+  pub naming: Option<NamingScheme>,
+  /// 在 out_dir 写 manifest.json（仅 split_pdf）
+  pub manifest: bool,
+  /// 每段的 AcroForm 只保留控件落在本段页面上的字段，拆出的表单仍可填写
+  pub preserve_forms: bool,
+  /// 加密输入的密码（用户或所有者密码）；拆出的各段默认不加密
+  pub password: Option<String>,
+  /// 各段按原文件的加密参数（密钥、密码、权限）重新加密（仅 split_pdf，需 password）
+  pub reapply_encryption: bool,
+  /// 拆出的各段再经 qpdf --object-streams=generate --remove-unreferenced-resources=yes 精简，
+  /// 去掉页面用不到的共享资源（仅 split_pdf）
+  pub compact: bool,
+  /// 每段额外带上起始页之前的 overlap 页作为上下文（不越过第 1 页；仅 split_pdf）
+  pub overlap: u32,
+}
+
+/// 文件名模板，变量：{index} 序号（1 起，按总数补零，至少 2 位）、{range} 页范围、{total} 总份数，
+/// 以及与单输出命令通用的 {stem} {op} {date} {sha8} {seq}（见 naming；{seq} 按份取 out_dir 中第一个未用的序号）。
+/// 如 "{stem}_part{index}" → report_final_part01.pdf，"{stem}_{seq}" → report_final_001.pdf、report_final_002.pdf …
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NamingScheme {
+  pub template: String,
+}
+
+impl NamingScheme {
+  fn validate(&self) -> Result<(), String> {
+    if self.template.trim().is_empty() { return Err("文件名模板不能为空".into()); }
+    Ok(())
+  }
+
+  /// 只替换各份不同的变量；通用变量已在 PartNamer::new 中展开
+  fn render(&self, i: usize, total: usize, range: &str) -> String {
+    let width = total.to_string().len().max(2);
+    let name = self.template
+      .replace("{index}", &format!("{:0width$}", i + 1))
+      .replace("{range}", &range.replace(',', "_").replace(' ', ""))
+      .replace("{total}", &total.to_string());
+    let name = sanitize(name.trim());
+    name.strip_suffix(".pdf").map(String::from).unwrap_or(name)
+  }
+}
+
+impl SplitOptions {
+  fn validate(&self) -> Result<(), String> {
+    if self.reapply_encryption && self.password.is_none() { return Err("重新加密需要提供原文件密码".into()); }
+    self.naming.as_ref().map_or(Ok(()), NamingScheme::validate)
+  }
+}
+
+fn emit_progress(app: &AppHandle, payload: &Progress) {
+  let _ = app.emit(PROGRESS_EVT, payload);
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SplitResult {
+  pub paths: Vec<String>,
+  /// 开启 manifest 时为清单路径
+  pub manifest: Option<String>,
+  /// 开启 compact 时各段精简前后的体积
+  pub compacted: Option<Vec<CompactedPart>>,
+  /// overlap > 0 时各段实际拆出的页范围（含上下文页，如 overlap 2 时 "5-9" → "3-9"）；文件名仍按原范围
+  pub overlapped_ranges: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompactedPart {
+  pub path: String,
+  /// 直接按页范围拆出的体积
+  pub naive_bytes: u64,
+  /// 精简后（重新加密前）的体积
+  pub compact_bytes: u64,
+}
 
 #[tauri::command]
 pub async fn split_pdf(
-    app: AppHandle,
-    input: String,
-    ranges: Vec<String>, // 例如 ["1-3", "8", "10-12"]
-    out_dir: String,
+  app: AppHandle,
+  input: InputOne,
+  ranges: Vec<String>, // 例如 ["1-3", "8", "10-12"]
+  out_dir: String,
+  options: Option<SplitOptions>,
+) -> Result<SplitResult, String> {
+  check_input(&input)?;
+  let opts = options.unwrap_or_default();
+  check_ranges(&ranges)?;
+  opts.validate()?;
+  fs::create_dir_all(&out_dir).map_err(|e| format!("创建输出目录失败：{e}"))?;
+  let work = work_dir(&app, "split")?;
+  let res = run_split(&app, &work, &input, &ranges, &out_dir, &opts);
+  let _ = fs::remove_dir_all(&work);
+  res
+}
+
+fn run_split(app: &AppHandle, work: &Path, input: &InputOne, ranges: &[String], out_dir: &str, opts: &SplitOptions) -> Result<SplitResult, String> {
+  let in_path = stage_input(work, input)?;
+  let password = opts.password.as_deref();
+  if let (true, Some(pw)) = (opts.reapply_encryption, password) { open_encrypted(app, &in_path, pw)?; }
+  let source = SourceInfo::load(&in_path, opts)?;
+  let mut namer = PartNamer::new(opts, input, out_dir, ranges.len())?;
+  let mut outputs = Vec::with_capacity(ranges.len());
+  let mut compacted = Vec::new();
+  let staged = |name: String| work.join(name).to_string_lossy().to_string();
+  let total = if opts.overlap > 0 { page_count_with(app, &in_path, password)? } else { 0 };
+  let mut overlapped = Vec::new();
+  for (i, r) in ranges.iter().enumerate() {
+    let out_path = namer.path(i, r)?;
+    let r = &if opts.overlap > 0 { with_context(r, opts.overlap, total)? } else { r.clone() };
+    // 重新加密 / 精简时先解密拆到临时文件，书签 / 表单处理完再精简、套回原加密
+    let part = if opts.reapply_encryption || opts.compact { staged(format!("part_{:03}.pdf", i)) } else { out_path.clone() };
+    extract_range_with(app, &in_path, r, &part, password)?;
+    source.post_process(&part, r)?;
+    let part = if opts.compact {
+      let dest = if opts.reapply_encryption { staged(format!("compact_{:03}.pdf", i)) } else { out_path.clone() };
+      compact_part(app, &part, &dest)?;
+      compacted.push(CompactedPart { path: out_path.clone(), naive_bytes: file_size(&part), compact_bytes: file_size(&dest) });
+      dest
+    } else { part };
+    if let (true, Some(pw)) = (opts.reapply_encryption, password) { copy_encryption(app, &in_path, pw, &part, &out_path)?; }
+    outputs.push(out_path);
+    overlapped.push(r.clone());
+  }
+  let manifest = if opts.manifest {
+    let name = match input { InputOne::Path(p) => p.clone(), InputOne::Bytes(pdf) => pdf.name.clone() };
+    let entry = describe(app, &name, &in_path)?;
+    Some(write_manifest(app, "split", vec![entry], &outputs, Path::new(out_dir))?)
+  } else { None };
+  Ok(SplitResult {
+    paths: outputs,
+    manifest,
+    compacted: opts.compact.then_some(compacted),
+    overlapped_ranges: (opts.overlap > 0).then_some(overlapped),
+  })
+}
+
+/// 范围前补上起始页（范围内最小页码）之前的 overlap 页，以显式页码重写，如 overlap 2 时 "5-9:odd" → "3-5,7,9"
+fn with_context(range: &str, overlap: u32, total: u32) -> Result<String, String> {
+  let pages = parse_range(range, total)?;
+  let Some(&start) = pages.iter().min() else { return Ok(range.to_string()) };
+  let first = start.saturating_sub(overlap).max(1);
+  if first == start { return Ok(range.to_string()); }
+  let all: Vec<(String, u32)> = (first..start).chain(pages).map(|p| (String::new(), p)).collect();
+  Ok(page_runs(&all).into_iter().map(|(_, r)| r).collect::<Vec<_>>().join(","))
+}
+
+/// 重写时生成对象流、丢掉各页 /Resources 里内容流没用到的条目（共享资源字典常带着整份原文件的字体图片）
+fn compact_part(app: &AppHandle, input: &str, output: &str) -> Result<(), String> {
+  let args: Vec<String> = vec![
+    "--object-streams=generate".into(),
+    "--remove-unreferenced-resources=yes".into(),
+    input.into(),
+    output.into(),
+  ];
+  run_qpdf(app, &args).map(|_| ()).map_err(|e| format!("qpdf 精简失败：{e}"))
+}
+
+fn file_size(path: &str) -> u64 {
+  fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+/// 先拆再压：每个范围 qpdf 拆到临时文件，再按预设压缩到最终路径
+#[tauri::command]
+pub async fn split_and_compress(
+  app: AppHandle,
+  input: InputOne,
+  ranges: Vec<String>,
+  preset: CompressPreset,
+  out_dir: String,
+  options: Option<SplitOptions>,
+) -> Result<Vec<CompressResult>, String> {
+  check_input(&input)?;
+  let opts = options.unwrap_or_default();
+  check_ranges(&ranges)?;
+  opts.validate()?;
+  fs::create_dir_all(&out_dir).map_err(|e| format!("创建输出目录失败：{e}"))?;
+  let work = work_dir(&app, "split_compress")?;
+  let res = run_split_and_compress(&app, &work, &input, &ranges, &preset, &out_dir, &opts).await;
+  let _ = fs::remove_dir_all(&work);
+  res
+}
+
+async fn run_split_and_compress(app: &AppHandle, work: &Path, input: &InputOne, ranges: &[String], preset: &CompressPreset, out_dir: &str, opts: &SplitOptions) -> Result<Vec<CompressResult>, String> {
+  let in_path = stage_input(work, input)?;
+  let source = SourceInfo::load(&in_path, opts)?;
+  let total = ranges.len();
+  let mut namer = PartNamer::new(opts, input, out_dir, total)?;
+  let mut results = Vec::with_capacity(total);
+  for (i, r) in ranges.iter().enumerate() {
+    emit_progress(app, &Progress::Split { index: i, total, range: r });
+    let part = work.join(format!("part_{:03}.pdf", i)).to_string_lossy().to_string();
+    extract_range_with(app, &in_path, r, &part, opts.password.as_deref())?;
+    source.post_process(&part, r)?;
+
+    emit_progress(app, &Progress::Compress { index: i, total, range: r });
+    let out_path = namer.path(i, r)?;
+    let c = run_path(app, &part, &out_path, preset, &CompressOptions::default()).await?;
+    results.push(CompressResult::measure(&part, &out_path, Some(r), c));
+  }
+  emit_progress(app, &Progress::Done { total });
+  Ok(results)
+}
+
+/// split_and_export 的输出格式
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat { Pdf, Png, Tiff, Text }
+
+/// 逐页 PNG 的渲染分辨率
+const EXPORT_PNG_DPI: u32 = 150;
+/// 每段一个多页 TIFF（8 位灰度 LZW，无损）
+const EXPORT_TIFF_DPI: u32 = 200;
+
+/// 先按范围拆分，再把每段转成所选格式：Pdf 即 split_01_1-3.pdf；Png 每页一张 split_01_1-3_p01.png ...；
+/// Tiff 每段一个 split_01_1-3.tif；Text 每段一个 split_01_1-3.txt（页间以换页符 \f 分隔）。返回生成的全部文件。
+/// Png / Tiff 逐页渲染，开始前按 guard 检查页数
+#[tauri::command]
+pub async fn split_and_export(
+  app: AppHandle,
+  input: InputOne,
+  ranges: Vec<String>,
+  format: ExportFormat,
+  out_dir: String,
+  guard: Option<PageGuard>,
 ) -> Result<Vec<String>, String> {
-    if ranges.is_empty() {
-        return Err("请提供至少一个页范围".into());
-    }
+  check_input(&input)?;
+  check_ranges(&ranges)?;
+  fs::create_dir_all(&out_dir).map_err(|e| format!("创建输出目录失败：{e}"))?;
+  let work = work_dir(&app, "split_export")?;
+  let res = run_split_and_export(&app, &work, &input, &ranges, format, &out_dir, guard.unwrap_or_default());
+  let _ = fs::remove_dir_all(&work);
+  res
+}
 
-    let mut outputs = Vec::new();
-    for (i, r) in ranges.iter().enumerate() {
-        // out 文件名：split_01_1-3.pdf
-        let safe = r.replace(',', "_").replace(' ', "");
-        let out_path = format!("{}/split_{:02}_{}.pdf", out_dir, i + 1, safe);
-
-        // qpdf input.pdf --pages input.pdf 1-3 -- out.pdf
-        let args = vec![
-            input.clone(),
-            "--pages".into(),
-            input.clone(),
-            r.clone(),
-            "--".into(),
-            out_path.clone(),
-        ];
-
-        let output = app
-            .shell()
-            .command("qpdf")
-            .args(args)
-            .output()
-            .await
-            .map_err(|e| format!("无法执行 qpdf：{e}"))?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-            return Err(format!("qpdf 拆分失败（{}）：{stderr}", r));
+fn run_split_and_export(
+  app: &AppHandle,
+  work: &Path,
+  input: &InputOne,
+  ranges: &[String],
+  format: ExportFormat,
+  out_dir: &str,
+  guard: PageGuard,
+) -> Result<Vec<String>, String> {
+  let in_path = stage_input(work, input)?;
+  if matches!(format, ExportFormat::Png | ExportFormat::Tiff) { guard.check(app, &in_path)?; }
+  let total = ranges.len();
+  let mut files = Vec::new();
+  for (i, r) in ranges.iter().enumerate() {
+    emit_progress(app, &Progress::Split { index: i, total, range: r });
+    let pdf = part_path(out_dir, i, r);
+    // 非 Pdf 格式先拆到工作目录，再转换写出
+    let part = match format {
+      ExportFormat::Pdf => pdf.clone(),
+      _ => work.join(format!("part_{:03}.pdf", i)).to_string_lossy().to_string(),
+    };
+    extract_range(app, &in_path, r, &part)?;
+    let stem = pdf.strip_suffix(".pdf").unwrap_or(&pdf);
+    match format {
+      ExportFormat::Pdf => files.push(pdf.clone()),
+      ExportFormat::Png => {
+        let pngs = render_pages(app, &part, &work.join(format!("render_{:03}", i)), EXPORT_PNG_DPI, RenderMode::Rgb, None)?;
+        for (k, png) in pngs.iter().enumerate() {
+          let dest = format!("{stem}_p{:02}.png", k + 1);
+          fs::copy(png, &dest).map_err(|e| format!("写出图片失败：{e}（{dest}）"))?;
+          files.push(dest);
         }
+      }
+      ExportFormat::Tiff => {
+        let dest = format!("{stem}.tif");
+        write_tiff(app, &part, &dest, EXPORT_TIFF_DPI, TiffCompression::Lzw)?;
+        files.push(dest);
+      }
+      ExportFormat::Text => {
+        let doc = lopdf::Document::load(&part).map_err(|e| format!("解析拆分结果失败：{e}"))?;
+        let text = doc.get_pages().into_keys().map(|no| page_text(&doc, no)).collect::<Vec<_>>().join("\u{c}");
+        let dest = format!("{stem}.txt");
+        fs::write(&dest, text).map_err(|e| format!("写出文本失败：{e}（{dest}）"))?;
+        files.push(dest);
+      }
+    }
+  }
+  emit_progress(app, &Progress::Done { total });
+  Ok(files)
+}
+
+/// 按文字边界拆分：页面文字匹配 boundary_regex 即开始新的一份（如每张发票首页的 "Invoice No"）。
+/// 正则带捕获组时用第 1 组命名输出（如 INV-0042.pdf），且只在编号首次出现的页分开；首个匹配之前的页单独成一份。
+#[tauri::command]
+pub async fn split_by_text(app: AppHandle, input: InputOne, boundary_regex: String, out_dir: String) -> Result<Vec<String>, String> {
+  check_input(&input)?;
+  let re = Regex::new(&boundary_regex).map_err(|e| format!("正则无效：{e}"))?;
+  fs::create_dir_all(&out_dir).map_err(|e| format!("创建输出目录失败：{e}"))?;
+  let work = work_dir(&app, "split_text")?;
+  let res = run_split_by_text(&app, &work, &input, &re, &out_dir);
+  let _ = fs::remove_dir_all(&work);
+  res
+}
+
+fn run_split_by_text(app: &AppHandle, work: &Path, input: &InputOne, re: &Regex, out_dir: &str) -> Result<Vec<String>, String> {
+  let in_path = stage_input(work, input)?;
+  let doc = lopdf::Document::load(&in_path).map_err(|e| format!("解析 PDF 失败：{e}"))?;
+
+  let mut used = HashSet::new();
+  let mut outputs = Vec::new();
+  for (i, part) in text_boundaries(&doc, re, false)?.iter().enumerate() {
+    let out_path = match &part.label {
+      Some(l) => unique_path(out_dir, l, &mut used),
+      None => part_path(out_dir, i, &part.range),
+    };
+    extract_range(app, &in_path, &part.range, &out_path)?;
+    outputs.push(out_path);
+  }
+  Ok(outputs)
+}
+
+
+/// 按 CSV 拆分映射表拆分：每行 name,range（如 intro,1-3），输出 out_dir/<name>.pdf。
+/// 首行为 name,range 时视为表头；空行忽略；范围含逗号时可加引号或直接写在后续列（"1-3,7" 或 1-3,7）。
+/// 先校验全部行（范围越界、重名），再逐行拆分
+#[tauri::command]
+pub async fn split_from_csv(app: AppHandle, input: InputOne, csv_path: String, out_dir: String) -> Result<Vec<String>, String> {
+  check_input(&input)?;
+  let text = fs::read_to_string(&csv_path).map_err(|e| format!("读取 CSV 失败：{e}（{csv_path}）"))?;
+  let rows = parse_split_csv(&text)?;
+  fs::create_dir_all(&out_dir).map_err(|e| format!("创建输出目录失败：{e}"))?;
+  let work = work_dir(&app, "split_csv")?;
+  let res = run_split_from_csv(&app, &work, &input, &rows, &out_dir);
+  let _ = fs::remove_dir_all(&work);
+  res
+}
+
+fn run_split_from_csv(app: &AppHandle, work: &Path, input: &InputOne, rows: &[(usize, String, String)], out_dir: &str) -> Result<Vec<String>, String> {
+  let in_path = stage_input(work, input)?;
+  let total = page_count(app, &in_path)?;
+  for (line, _, range) in rows {
+    parse_range(range, total).map_err(|e| format!("CSV 第 {line} 行：{e}"))?;
+  }
+  let mut used = HashSet::new();
+  let mut outputs = Vec::with_capacity(rows.len());
+  for (_, name, range) in rows {
+    let out_path = unique_path(out_dir, name, &mut used);
+    extract_range(app, &in_path, range, &out_path)?;
+    outputs.push(out_path);
+  }
+  Ok(outputs)
+}
 
-        outputs.push(out_path);
+/// 解析为 (行号, 文件名, 范围)；文件名已去掉 .pdf 后缀并替换非法字符
+fn parse_split_csv(text: &str) -> Result<Vec<(usize, String, String)>, String> {
+  let mut rows = Vec::new();
+  let mut names = HashSet::new();
+  for (i, line) in text.trim_start_matches('\u{feff}').lines().enumerate() {
+    let line_no = i + 1;
+    if line.trim().is_empty() { continue; }
+    let fields = csv_fields(line).map_err(|e| format!("CSV 第 {line_no} 行：{e}"))?;
+    let name = fields[0].trim();
+    let range = fields[1..].iter().map(|f| f.trim()).filter(|f| !f.is_empty()).collect::<Vec<_>>().join(",");
+    if rows.is_empty() && name.eq_ignore_ascii_case("name") && range.eq_ignore_ascii_case("range") { continue; }
+    if name.is_empty() || range.is_empty() { return Err(format!("CSV 第 {line_no} 行需为 name,range")); }
+    let name = sanitize(name.strip_suffix(".pdf").unwrap_or(name));
+    if !names.insert(name.clone()) { return Err(format!("CSV 第 {line_no} 行文件名重复：{name}")); }
+    rows.push((line_no, name, range));
+  }
+  if rows.is_empty() { return Err("CSV 中没有拆分行".into()); }
+  Ok(rows)
+}
+
+/// 单行 CSV：逗号分隔，双引号包裹的字段内可含逗号，"" 表示引号本身
+fn csv_fields(line: &str) -> Result<Vec<String>, String> {
+  let mut fields = vec![String::new()];
+  let mut quoted = false;
+  let mut chars = line.chars().peekable();
+  while let Some(c) = chars.next() {
+    let cur = fields.last_mut().unwrap();
+    match c {
+      '"' if quoted && chars.peek() == Some(&'"') => { cur.push('"'); chars.next(); }
+      '"' => quoted = !quoted,
+      ',' if !quoted => fields.push(String::new()),
+      _ => cur.push(c),
+    }
+  }
+  if quoted { return Err("引号未闭合".into()); }
+  Ok(fields)
+}
+
+/// 相邻页 MediaBox 宽高差都在该范围内视为同一尺寸
+const SIZE_CHANGE_TOLERANCE_PT: f32 = 1.0;
+
+/// 按页面尺寸变化拆分：相邻页 MediaBox 宽或高相差超过 1pt 即开始新的一份（如 A4 正文与 A3 折页），
+/// 输出 split_01_1-12.pdf ...；横竖版互换也算尺寸变化
+#[tauri::command]
+pub async fn split_by_size_change(app: AppHandle, input: InputOne, out_dir: String) -> Result<Vec<String>, String> {
+  check_input(&input)?;
+  fs::create_dir_all(&out_dir).map_err(|e| format!("创建输出目录失败：{e}"))?;
+  let work = work_dir(&app, "split_size")?;
+  let res = run_split_by_size_change(&app, &work, &input, &out_dir);
+  let _ = fs::remove_dir_all(&work);
+  res
+}
+
+fn run_split_by_size_change(app: &AppHandle, work: &Path, input: &InputOne, out_dir: &str) -> Result<Vec<String>, String> {
+  let in_path = stage_input(work, input)?;
+  let doc = lopdf::Document::load(&in_path).map_err(|e| format!("解析 PDF 失败：{e}"))?;
+  let mut sizes = Vec::new();
+  for (no, page_id) in doc.get_pages() {
+    let mb = page_rect(&doc, page_id, b"MediaBox").ok_or(format!("第 {no} 页缺少 MediaBox"))?;
+    sizes.push((mb[2] - mb[0], mb[3] - mb[1]));
+  }
+  if sizes.is_empty() { return Err("文档没有页面".into()); }
+
+  let mut outputs = Vec::new();
+  let mut start = 0;
+  for end in 1..=sizes.len() {
+    let same = |(w, h): (f32, f32)| (w - sizes[start].0).abs() <= SIZE_CHANGE_TOLERANCE_PT && (h - sizes[start].1).abs() <= SIZE_CHANGE_TOLERANCE_PT;
+    if end < sizes.len() && same(sizes[end]) { continue; }
+    let range = if end - start == 1 { format!("{end}") } else { format!("{}-{end}", start + 1) };
+    let out_path = part_path(out_dir, outputs.len(), &range);
+    extract_range(app, &in_path, &range, &out_path)?;
+    outputs.push(out_path);
+    start = end;
+  }
+  Ok(outputs)
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColorSplitPart {
+  pub path: String,
+  pub page_count: u32,
+}
+
+/// 彩色页 → out_dir/color.pdf，黑白页 → out_dir/bw.pdf（均按原页序）；没有页的一类不生成文件
+#[tauri::command]
+pub async fn split_by_color(app: AppHandle, input: InputOne, out_dir: String, guard: Option<PageGuard>) -> Result<Vec<ColorSplitPart>, String> {
+  check_input(&input)?;
+  fs::create_dir_all(&out_dir).map_err(|e| format!("创建输出目录失败：{e}"))?;
+  let work = work_dir(&app, "split_color")?;
+  let res = run_split_by_color(&app, &work, &input, &out_dir, guard.unwrap_or_default());
+  let _ = fs::remove_dir_all(&work);
+  res
+}
+
+fn run_split_by_color(app: &AppHandle, work: &Path, input: &InputOne, out_dir: &str, guard: PageGuard) -> Result<Vec<ColorSplitPart>, String> {
+  let in_path = stage_input(work, input)?;
+  guard.check(app, &in_path)?;
+  let (mut color, mut bw) = (Vec::new(), Vec::new());
+  for (i, is_color) in classify_color(app, &in_path, &work.join("render"))?.into_iter().enumerate() {
+    let page = (i + 1).to_string();
+    if is_color { color.push(page) } else { bw.push(page) }
+  }
+
+  let mut parts = Vec::new();
+  for (name, pages) in [("color.pdf", color), ("bw.pdf", bw)] {
+    if pages.is_empty() { continue; }
+    let path = Path::new(out_dir).join(name).to_string_lossy().to_string();
+    extract_range(app, &in_path, &pages.join(","), &path)?;
+    parts.push(ColorSplitPart { path, page_count: pages.len() as u32 });
+  }
+  Ok(parts)
+}
+
+/// 按文字边界切出的一份
+pub(crate) struct TextPart {
+  /// qpdf 页范围，如 "4-6"
+  pub range: String,
+  /// 第 1 捕获组（whole_match 时无分组则取整个匹配），已去掉文件名非法字符；首个匹配之前的页为 None
+  pub label: Option<String>,
+}
+
+/// 逐页提取文字，匹配 re 的页开始新的一份；首个匹配之前的页单独成一份。
+/// 取得编号（第 1 捕获组，或 whole_match 时的整个匹配）时只在编号变化处分开，每页都印着同一编号的多页文档仍为一份；
+/// 取不到编号时每个匹配页都开始新的一份
+pub(crate) fn text_boundaries(doc: &lopdf::Document, re: &Regex, whole_match: bool) -> Result<Vec<TextPart>, String> {
+  let total = doc.get_pages().len() as u32;
+  // (起始页, 捕获的编号)
+  let mut starts: Vec<(u32, Option<String>)> = Vec::new();
+  for no in 1..=total {
+    let text = page_text(doc, no);
+    if let Some(caps) = re.captures(&text) {
+      let m = caps.get(1).or_else(|| if whole_match { caps.get(0) } else { None });
+      let label = m.map(|m| sanitize(m.as_str().trim())).filter(|s| !s.is_empty());
+      if label.is_some() && starts.last().is_some_and(|(_, prev)| *prev == label) { continue; }
+      starts.push((no, label));
     }
+  }
+  if starts.is_empty() { return Err("没有页面匹配边界正则".into()); }
+  if starts[0].0 > 1 { starts.insert(0, (1, None)); }
+
+  Ok(starts.iter().enumerate().map(|(i, (first, label))| {
+    let last = starts.get(i + 1).map(|(n, _)| n - 1).unwrap_or(total);
+    let range = if *first == last { first.to_string() } else { format!("{first}-{last}") };
+    TextPart { range, label: label.clone() }
+  }).collect())
+}
+
+/// 页面上的取名区域，单位 pt，原点为页面左上角（与屏幕预览一致，y 向下）
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TextRegion {
+  pub x: f32,
+  pub y: f32,
+  pub width: f32,
+  pub height: f32,
+}
+
+impl TextRegion {
+  fn contains(&self, bbox: &[f32; 4]) -> bool {
+    let (cx, cy) = ((bbox[0] + bbox[2]) / 2.0, (bbox[1] + bbox[3]) / 2.0);
+    cx >= self.x && cx <= self.x + self.width && cy >= self.y && cy <= self.y + self.height
+  }
+
+  /// 区域内文字：段内直接拼接，段间以空格分隔
+  fn text(&self, spans: &PageSpans) -> String {
+    spans.iter()
+      .map(|span| span.chars.iter().filter(|c| self.contains(&c.bbox)).map(|c| c.c.as_str()).collect::<String>())
+      .filter(|t| !t.trim().is_empty())
+      .collect::<Vec<_>>()
+      .join(" ")
+  }
+}
+
+/// 按固定页数拆分（如每位客户的对账单都是 3 页）；给出 name_from_text 时用每份首页该区域的文字命名，
+/// 区域内没有文字的份退回 split_01_1-3.pdf 命名。
+#[tauri::command]
+pub async fn split_fixed_named(app: AppHandle, input: InputOne, pages_per_doc: u32, name_from_text: Option<TextRegion>, out_dir: String, guard: Option<PageGuard>) -> Result<Vec<String>, String> {
+  check_input(&input)?;
+  if pages_per_doc == 0 { return Err("每份页数必须大于 0".into()); }
+  if let Some(r) = name_from_text {
+    if !(r.width > 0.0 && r.height > 0.0) { return Err("取名区域的宽高必须大于 0".into()); }
+  }
+  fs::create_dir_all(&out_dir).map_err(|e| format!("创建输出目录失败：{e}"))?;
+  let work = work_dir(&app, "split_fixed")?;
+  let res = run_split_fixed(&app, &work, &input, pages_per_doc, name_from_text, &out_dir, guard.unwrap_or_default());
+  let _ = fs::remove_dir_all(&work);
+  res
+}
+
+fn run_split_fixed(app: &AppHandle, work: &Path, input: &InputOne, per_doc: u32, region: Option<TextRegion>, out_dir: &str, guard: PageGuard) -> Result<Vec<String>, String> {
+  let in_path = stage_input(work, input)?;
+  let total = guard.check(app, &in_path)?;
+  let texts = match region {
+    Some(_) => positioned_text(app, &in_path, &work.join("text"), None)?,
+    None => Vec::new(),
+  };
+
+  let mut used = HashSet::new();
+  let mut outputs = Vec::new();
+  for (i, first) in (1..=total).step_by(per_doc as usize).enumerate() {
+    let last = (first + per_doc - 1).min(total);
+    let range = if first == last { first.to_string() } else { format!("{first}-{last}") };
+    let label = region
+      .zip(texts.get(first as usize - 1))
+      .map(|(r, spans)| sanitize(r.text(spans).trim()))
+      .filter(|l| !l.is_empty());
+    let out_path = match label {
+      Some(l) => unique_path(out_dir, &l, &mut used),
+      None => part_path(out_dir, i, &range),
+    };
+    extract_range(app, &in_path, &range, &out_path)?;
+    outputs.push(out_path);
+  }
+  Ok(outputs)
+}
+
+/// 同名编号追加 _2、_3 ...
+pub(crate) fn unique_path(out_dir: &str, label: &str, used: &mut HashSet<String>) -> String {
+  let mut name = label.to_string();
+  let mut n = 2;
+  while !used.insert(name.clone()) {
+    name = format!("{label}_{n}");
+    n += 1;
+  }
+  Path::new(out_dir).join(format!("{name}.pdf")).to_string_lossy().to_string()
+}
+
+// ---------- 共用 ----------
+
+/// 每段的输出路径：有模板按模板（{seq} 取未用序号，其余重名追加 _2 ...），否则 split_01_1-3.pdf
+struct PartNamer<'a> {
+  /// 已展开通用变量的模板
+  naming: Option<NamingScheme>,
+  out_dir: &'a str,
+  total: usize,
+  used: HashSet<String>,
+}
+
+impl<'a> PartNamer<'a> {
+  fn new(opts: &SplitOptions, input: &InputOne, out_dir: &'a str, total: usize) -> Result<Self, String> {
+    let naming = opts.naming.as_ref()
+      .map(|n| expand_tokens(&n.template, "split", &[input.into()]).map(|template| NamingScheme { template }))
+      .transpose()?;
+    Ok(PartNamer { naming, out_dir, total, used: HashSet::new() })
+  }
+
+  fn path(&mut self, i: usize, range: &str) -> Result<String, String> {
+    let Some(n) = self.naming.as_ref() else { return Ok(part_path(self.out_dir, i, range)) };
+    let name = n.render(i, self.total, range);
+    if !name.contains("{seq}") { return Ok(unique_path(self.out_dir, &name, &mut self.used)); }
+    let dir = Path::new(self.out_dir);
+    let name = first_free_seq(&name, |n| !self.used.contains(n) && !dir.join(format!("{n}.pdf")).exists())
+      .ok_or_else(|| format!("序号已用尽：{name}"))?;
+    self.used.insert(name.clone());
+    Ok(dir.join(format!("{name}.pdf")).to_string_lossy().to_string())
+  }
+}
+
+/// 拆分后处理所需的原文件信息（仅在开启相应选项时读取）
+struct SourceInfo {
+  total: u32,
+  outline: Option<Vec<OutlineNode>>,
+  forms: bool,
+}
+
+impl SourceInfo {
+  fn load(in_path: &str, opts: &SplitOptions) -> Result<Self, String> {
+    let forms = opts.preserve_forms;
+    if !opts.preserve_bookmarks { return Ok(SourceInfo { total: 0, outline: None, forms }); }
+    let doc = lopdf::Document::load(in_path).map_err(|e| format!("解析 PDF 失败：{e}"))?;
+    Ok(SourceInfo { total: doc.get_pages().len() as u32, outline: Some(read_outline(&doc)), forms })
+  }
+
+  /// 按选项改写拆出的单段文件（书签裁剪 + 页码重映射、表单字段裁剪）
+  fn post_process(&self, part_path: &str, range: &str) -> Result<(), String> {
+    if self.outline.is_none() && !self.forms { return Ok(()); }
+    let mut part = lopdf::Document::load(part_path).map_err(|e| format!("解析拆分结果失败：{e}"))?;
+    if let Some(outline) = self.outline.as_ref() {
+      let pages = parse_range(range, self.total)?;
+      write_outline(&mut part, &remap(outline, &pages))?;
+    }
+    if self.forms { prune_acroform(&mut part)?; }
+    part.prune_objects(); // 丢掉 qpdf 残留的旧书签 / 其他段的字段对象
+    save_pdf(&mut part, part_path)
+  }
+}
+
+pub(crate) fn check_ranges(ranges: &[String]) -> Result<(), String> {
+  if ranges.is_empty() {
+    return Err("请提供至少一个页范围".into());
+  }
+  Ok(())
+}
+
+/// out 文件名：split_01_1-3.pdf
+pub(crate) fn part_path(out_dir: &str, i: usize, range: &str) -> String {
+  let safe = range.replace(',', "_").replace(' ', "");
+  Path::new(out_dir).join(format!("split_{:02}_{}.pdf", i + 1, safe)).to_string_lossy().to_string()
+}
+
+/// qpdf input.pdf --pages input.pdf 1-3 -- out.pdf（以原文件为主，保留文档级信息）
+pub(crate) fn extract_range(app: &AppHandle, input: &str, range: &str, output: &str) -> Result<(), String> {
+  extract_range_with(app, input, range, output, None)
+}
 
-    Ok(outputs)
+/// 加密输入：password 同时用于主输入与 --pages 输入，输出解密
+pub(crate) fn extract_range_with(app: &AppHandle, input: &str, range: &str, output: &str, password: Option<&str>) -> Result<(), String> {
+  ensure_parent_dir(output)?;
+  let pw = password.map(|p| format!("--password={p}"));
+  let mut args: Vec<String> = Vec::new();
+  if let Some(pw) = &pw { args.extend([pw.clone(), "--decrypt".into()]); }
+  args.extend([input.into(), "--pages".into(), input.into()]);
+  if let Some(pw) = pw { args.push(pw); }
+  args.extend([range.into(), "--".into(), output.into()]);
+  run_qpdf(app, &args).map(|_| ()).map_err(|e| format!("qpdf 拆分失败（{range}）：{e}"))
 }
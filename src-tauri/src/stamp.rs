@@ -0,0 +1,277 @@
+//! Stamp — place the first page of a stamp PDF as a Form XObject, per-page-range positions & scales, one pass via lopdf
+//! (+ page-number overlay shared with assemble, diagonal text watermark)
+use lopdf::{dictionary, Dictionary, Document, Object, ObjectId, Stream};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+use crate::common::{assert_input_not_output, load_pdf, parse_range, save_pdf, InputOne};
+use crate::inspect::{inherited, page_rect, resolve};
+use crate::naming::resolve_output;
+
+pub(crate) const DEFAULT_MARGIN_PT: f32 = 36.0;
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum StampPosition {
+  TopLeft, TopCenter, TopRight,
+  MiddleLeft, Center, MiddleRight,
+  BottomLeft, BottomCenter, BottomRight,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StampPlacement {
+  /// qpdf 页范围语法，如 "1-z:odd"、"2-10"、"z"
+  pub pages: String,
+  pub position: StampPosition,
+  /// 相对印章原始尺寸的缩放，默认 1.0
+  #[serde(default = "one")]
+  pub scale: f32,
+  /// 距页面边缘（pt），默认 36pt = 0.5in；居中方向忽略
+  pub margin_pt: Option<f32>,
+}
+
+fn one() -> f32 { 1.0 }
+
+/// stamp 取第一页；同一页可命中多条 placement，按顺序叠加
+#[tauri::command]
+pub async fn stamp_pdf(input: InputOne, stamp: InputOne, output: String, placements: Vec<StampPlacement>) -> Result<String, String> {
+  let output = resolve_output(&output, "stamp", &[(&input).into()])?;
+  if placements.is_empty() { return Err("请提供至少一个盖章位置".into()); }
+  if let Some(p) = placements.iter().find(|p| !(p.scale > 0.0 && p.scale.is_finite())) {
+    return Err(format!("缩放比例无效：{}", p.scale));
+  }
+  assert_input_not_output(&input, &output)?;
+  let mut doc = load_pdf(&input)?;
+  let stamp_doc = load_pdf(&stamp)?;
+
+  let pages = doc.get_pages();
+  let total = pages.len() as u32;
+  let mut per_page: BTreeMap<u32, Vec<&StampPlacement>> = BTreeMap::new();
+  for p in &placements {
+    for no in parse_range(&p.pages, total)? { per_page.entry(no).or_default().push(p); }
+  }
+
+  let form = import_form(&mut doc, stamp_doc)?;
+  let name = format!("LpStamp{}", form.id.0);
+  for (no, list) in per_page {
+    let Some(&page_id) = pages.get(&no) else { continue };
+    let area = visible_area(&doc, page_id).ok_or(format!("第 {no} 页缺少 MediaBox"))?;
+    let mut ops = String::new();
+    for p in list {
+      let m = form_matrix(&form, area, p);
+      ops.push_str(&format!("q {} 0 0 {} {} {} cm /{name} Do Q\n", m[0], m[3], m[4], m[5]));
+    }
+    add_resource(&mut doc, page_id, b"XObject", &name, form.id)?;
+    append_page_content(&mut doc, page_id, ops.into_bytes())?;
+  }
+
+  doc.prune_objects(); // 丢掉随印章文档带进来的页树 / Catalog
+  save_pdf(&mut doc, &output)?;
+  Ok(output)
+}
+
+/// 导入到目标文档的 Form XObject 及其 BBox
+pub(crate) struct ImportedForm {
+  pub id: ObjectId,
+  pub bbox: [f32; 4],
+}
+
+/// 把 src 第一页变成 doc 里的 Form XObject（对象整体重编号后并入）
+pub(crate) fn import_form(doc: &mut Document, mut src: Document) -> Result<ImportedForm, String> {
+  src.renumber_objects_with(doc.max_id + 1);
+  doc.max_id = src.max_id;
+  let page_id = *src.get_pages().values().next().ok_or("印章 PDF 没有页面")?;
+  doc.objects.extend(std::mem::take(&mut src.objects));
+  page_form(doc, page_id)
+}
+
+/// 把 doc 内的一页包装成 Form XObject（BBox 取 MediaBox，内容与资源原样引用）
+pub(crate) fn page_form(doc: &mut Document, page_id: ObjectId) -> Result<ImportedForm, String> {
+  let bbox = page_rect(doc, page_id, b"MediaBox").ok_or("页面缺少 MediaBox")?;
+  let content = doc.get_page_content(page_id).map_err(|e| format!("读取页面内容失败：{e}"))?;
+  let resources = inherited(doc, page_id, b"Resources").cloned().unwrap_or_else(|| Object::Dictionary(Dictionary::new()));
+
+  let mut form = Stream::new(dictionary! {
+    "Type" => "XObject",
+    "Subtype" => "Form",
+    "BBox" => bbox.iter().map(|v| Object::Real(*v)).collect::<Vec<_>>(),
+    "Resources" => resources,
+  }, content);
+  let _ = form.compress();
+  Ok(ImportedForm { id: doc.add_object(form), bbox })
+}
+
+/// 印章 → 页面的变换 [s 0 0 s tx ty]
+fn form_matrix(form: &ImportedForm, area: [f32; 4], p: &StampPlacement) -> [f32; 6] {
+  let s = p.scale;
+  let (w, h) = ((form.bbox[2] - form.bbox[0]) * s, (form.bbox[3] - form.bbox[1]) * s);
+  let (x, y) = place(area, w, h, p.position, p.margin_pt.unwrap_or(DEFAULT_MARGIN_PT));
+  [s, 0.0, 0.0, s, x - form.bbox[0] * s, y - form.bbox[1] * s]
+}
+
+/// w×h 的块在 area 内按九宫格定位，返回左下角坐标
+pub(crate) fn place(area: [f32; 4], w: f32, h: f32, pos: StampPosition, margin: f32) -> (f32, f32) {
+  use StampPosition::*;
+  let [x0, y0, x1, y1] = area;
+  let x = match pos {
+    TopLeft | MiddleLeft | BottomLeft => x0 + margin,
+    TopCenter | Center | BottomCenter => (x0 + x1 - w) / 2.0,
+    TopRight | MiddleRight | BottomRight => x1 - margin - w,
+  };
+  let y = match pos {
+    BottomLeft | BottomCenter | BottomRight => y0 + margin,
+    MiddleLeft | Center | MiddleRight => (y0 + y1 - h) / 2.0,
+    TopLeft | TopCenter | TopRight => y1 - margin - h,
+  };
+  (x, y)
+}
+
+/// 页面可见区域：CropBox，缺省回退 MediaBox
+pub(crate) fn visible_area(doc: &Document, page_id: ObjectId) -> Option<[f32; 4]> {
+  page_rect(doc, page_id, b"CropBox").or_else(|| page_rect(doc, page_id, b"MediaBox"))
+}
+
+// ---------- 页码 ----------
+
+/// 页码样式；format 中 {n} 为页码、{total} 为总页数
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PageNumberStyle {
+  pub format: String,
+  pub position: StampPosition,
+  pub font_size: f32,
+  pub margin_pt: f32,
+  /// 第一页显示的编号
+  pub start_at: u32,
+}
+
+impl Default for PageNumberStyle {
+  fn default() -> Self {
+    PageNumberStyle { format: "{n}".into(), position: StampPosition::BottomCenter, font_size: 10.0, margin_pt: 24.0, start_at: 1 }
+  }
+}
+
+/// 给每页叠加 Helvetica 页码（仅 ASCII；其他字符按 ? 输出）
+pub(crate) fn stamp_page_numbers(doc: &mut Document, style: &PageNumberStyle) -> Result<(), String> {
+  if !(style.font_size > 0.0 && style.font_size.is_finite()) { return Err(format!("字号无效：{}", style.font_size)); }
+  let font = doc.add_object(dictionary! { "Type" => "Font", "Subtype" => "Type1", "BaseFont" => "Helvetica", "Encoding" => "WinAnsiEncoding" });
+  let name = format!("LpHelv{}", font.0);
+  let pages = doc.get_pages();
+  let total = pages.len() as u32 + style.start_at.saturating_sub(1);
+  for (i, (no, page_id)) in pages.into_iter().enumerate() {
+    let area = visible_area(doc, page_id).ok_or(format!("第 {no} 页缺少 MediaBox"))?;
+    let text = style.format.replace("{n}", &(style.start_at + i as u32).to_string()).replace("{total}", &total.to_string());
+    let w = helvetica_width(&text) * style.font_size;
+    let (x, y) = place(area, w, style.font_size, style.position, style.margin_pt);
+    let ops = format!("BT 0 g /{name} {} Tf {x} {y} Td ({}) Tj ET\n", style.font_size, pdf_string(&text));
+    add_resource(doc, page_id, b"Font", &name, font)?;
+    append_page_content(doc, page_id, ops.into_bytes())?;
+  }
+  Ok(())
+}
+
+// ---------- 文字水印 ----------
+
+/// 水印字号上限（pt）；长文字按页面对角线缩小
+const WATERMARK_MAX_PT: f32 = 96.0;
+/// 水印最多占对角线的比例
+const WATERMARK_SPAN: f32 = 0.7;
+/// 填充不透明度
+const WATERMARK_OPACITY: f32 = 0.25;
+
+/// 每页沿对角线居中叠加半透明灰色 Helvetica 文字（仅 ASCII）
+pub(crate) fn stamp_text_watermark(doc: &mut Document, text: &str) -> Result<(), String> {
+  let text = text.trim();
+  if text.is_empty() { return Err("水印文字不能为空".into()); }
+  if !text.is_ascii() { return Err("水印文字仅支持 ASCII（内置 Helvetica 无中文字形）".into()); }
+  let font = doc.add_object(dictionary! { "Type" => "Font", "Subtype" => "Type1", "BaseFont" => "Helvetica", "Encoding" => "WinAnsiEncoding" });
+  let gs = doc.add_object(dictionary! { "Type" => "ExtGState", "ca" => WATERMARK_OPACITY, "CA" => WATERMARK_OPACITY });
+  let (font_name, gs_name) = (format!("LpHelv{}", font.0), format!("LpWm{}", gs.0));
+  let em = helvetica_width(text).max(f32::EPSILON);
+  for (no, page_id) in doc.get_pages() {
+    let [x0, y0, x1, y1] = visible_area(doc, page_id).ok_or(format!("第 {no} 页缺少 MediaBox"))?;
+    let (w, h) = (x1 - x0, y1 - y0);
+    let size = (w.hypot(h) * WATERMARK_SPAN / em).min(WATERMARK_MAX_PT);
+    let angle = h.atan2(w);
+    let (sin, cos) = angle.sin_cos();
+    let (cx, cy) = (x0 + w / 2.0, y0 + h / 2.0);
+    // 先把文字矩阵转到对角线方向，再在文字空间里左移半个字宽、下移约半个字高居中
+    let ops = format!(
+      "q /{gs_name} gs 0.5 g BT /{font_name} {size} Tf {cos} {sin} {} {cos} {cx} {cy} Tm {} {} Td ({}) Tj ET Q\n",
+      -sin, -em * size / 2.0, -size * 0.35, pdf_string(text),
+    );
+    add_resource(doc, page_id, b"Font", &font_name, font)?;
+    add_resource(doc, page_id, b"ExtGState", &gs_name, gs)?;
+    append_page_content(doc, page_id, ops.into_bytes())?;
+  }
+  Ok(())
+}
+
+/// Helvetica 近似字宽（em）
+pub(crate) fn helvetica_width(s: &str) -> f32 {
+  s.chars().map(|c| match c {
+    '0'..='9' => 0.556,
+    ' ' | '/' | '.' | ',' | ':' | '-' => 0.3,
+    'A'..='Z' => 0.67,
+    _ => 0.5,
+  }).sum()
+}
+
+pub(crate) fn pdf_string(s: &str) -> String {
+  s.chars().map(|c| match c {
+    '(' | ')' | '\\' => format!("\\{c}"),
+    c if c.is_ascii() && !c.is_ascii_control() => c.to_string(),
+    _ => "?".into(),
+  }).collect()
+}
+
+// ---------- 共用：资源与内容流 ----------
+
+/// 往页面 /Resources /<category> 加一项；页面只继承父节点资源时先拷到页面上，共享的子字典复制后再改
+pub(crate) fn add_resource(doc: &mut Document, page_id: ObjectId, category: &[u8], name: &str, id: ObjectId) -> Result<(), String> {
+  let page = doc.get_dictionary(page_id).map_err(|e| format!("读取页面失败：{e}"))?;
+  if !page.has(b"Resources") {
+    if let Some(res) = inherited(doc, page_id, b"Resources").cloned() {
+      if let Ok(Object::Dictionary(p)) = doc.get_object_mut(page_id) { p.set("Resources", res); }
+    }
+  }
+  let mut entries = {
+    let page = doc.get_dictionary(page_id).map_err(|e| format!("读取页面失败：{e}"))?;
+    page.get(b"Resources").ok()
+      .and_then(|o| resolve(doc, o).as_dict().ok())
+      .and_then(|r| r.get(category).ok())
+      .and_then(|o| resolve(doc, o).as_dict().ok())
+      .cloned()
+      .unwrap_or_else(Dictionary::new)
+  };
+  entries.set(name, id);
+  let res = doc.get_or_create_resources(page_id).and_then(Object::as_dict_mut).map_err(|e| format!("写入页面资源失败：{e}"))?;
+  res.set(category, entries);
+  Ok(())
+}
+
+/// 在页面原内容之后追加绘制：原内容前后补 q / Q 隔离图形状态，不解码原内容流
+pub(crate) fn append_page_content(doc: &mut Document, page_id: ObjectId, ops: Vec<u8>) -> Result<(), String> {
+  let existing: Vec<Object> = match doc.get_dictionary(page_id).map_err(|e| format!("读取页面失败：{e}"))?.get(b"Contents") {
+    Ok(Object::Array(a)) => a.clone(),
+    Ok(Object::Reference(id)) => match doc.get_object(*id) {
+      Ok(Object::Array(a)) => a.clone(),
+      _ => vec![Object::Reference(*id)],
+    },
+    _ => Vec::new(),
+  };
+  let head = doc.add_object(Stream::new(Dictionary::new(), b"q\n".to_vec()));
+  let mut tail = b"\nQ\n".to_vec();
+  tail.extend(ops);
+  let tail = doc.add_object(Stream::new(Dictionary::new(), tail));
+
+  let mut contents = Vec::with_capacity(existing.len() + 2);
+  contents.push(Object::Reference(head));
+  contents.extend(existing);
+  contents.push(Object::Reference(tail));
+  match doc.get_object_mut(page_id) {
+    Ok(Object::Dictionary(page)) => { page.set("Contents", contents); Ok(()) }
+    _ => Err("页面对象不是字典".into()),
+  }
+}
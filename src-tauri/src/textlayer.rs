@@ -0,0 +1,45 @@
+//! Text layer — positioned text runs for one page (Ghostscript txtwrite) for click-to-select overlays
+use tauri::AppHandle;
+use serde::Serialize;
+use std::{fs, path::Path};
+
+use crate::common::{page_count, stage_input, work_dir, InputOne};
+use crate::render::positioned_text;
+
+/// 一段同字号文字；坐标为 pt，原点在页面左上角，y 向下
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextRun {
+  pub text: String,
+  pub x: f32,
+  pub y: f32,
+  pub width: f32,
+  pub height: f32,
+  pub font_size: f32,
+}
+
+/// page 为 1 基页码；一次只返回一页，控制负载大小
+#[tauri::command]
+pub async fn text_layout(app: AppHandle, input: InputOne, page: u32) -> Result<Vec<TextRun>, String> {
+  let work = work_dir(&app, "text_layout")?;
+  let res = run_text_layout(&app, &work, &input, page);
+  let _ = fs::remove_dir_all(&work);
+  res
+}
+
+fn run_text_layout(app: &AppHandle, work: &Path, input: &InputOne, page: u32) -> Result<Vec<TextRun>, String> {
+  let in_path = stage_input(work, input)?;
+  let total = page_count(app, &in_path)?;
+  if page == 0 || page > total { return Err(format!("页码越界：{page}（文档共 {total} 页）")); }
+
+  let pages = positioned_text(app, &in_path, &work.join("text"), Some((page, page)))?;
+  let Some(spans) = pages.into_iter().next() else { return Ok(Vec::new()) };
+  Ok(spans.into_iter().filter_map(|span| {
+    let text: String = span.chars.iter().map(|c| c.c.as_str()).collect();
+    if text.trim().is_empty() { return None; }
+    let (x0, y0, x1, y1) = span.chars.iter().fold((f32::MAX, f32::MAX, f32::MIN, f32::MIN), |(a, b, c, d), ch| {
+      (a.min(ch.bbox[0]), b.min(ch.bbox[1]), c.max(ch.bbox[2]), d.max(ch.bbox[3]))
+    });
+    Some(TextRun { text, x: x0, y: y0, width: x1 - x0, height: y1 - y0, font_size: span.size })
+  }).collect())
+}
@@ -0,0 +1,65 @@
+//! PDF → multi-page TIFF — Ghostscript tiffg4 (bilevel, CCITT G4) / tiffscaled8 (grayscale, LZW)
+use tauri::AppHandle;
+use serde::Deserialize;
+use std::{fs, path::Path};
+
+use crate::common::{assert_input_not_output, run_gs, stage_input, work_dir, AtomicOutput, InputOne, PageGuard};
+use crate::naming::resolve_output;
+
+const MIN_DPI: u32 = 72;
+const MAX_DPI: u32 = 1200;
+
+#[derive(Debug, Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum TiffCompression {
+  /// 1 位黑白，CCITT Group 4（归档常用，体积最小）
+  G4,
+  /// 8 位灰度，LZW 无损
+  Lzw,
+}
+
+impl TiffCompression {
+  fn device_args(self) -> [&'static str; 2] {
+    match self {
+      TiffCompression::G4 => ["-sDEVICE=tiffg4", "-sCompression=g4"],
+      TiffCompression::Lzw => ["-sDEVICE=tiffscaled8", "-sCompression=lzw"],
+    }
+  }
+}
+
+/// 所有页写入同一个多页 TIFF
+#[tauri::command]
+pub async fn to_tiff(app: AppHandle, input: InputOne, output: String, dpi: u32, compression: TiffCompression, guard: Option<PageGuard>) -> Result<String, String> {
+  if !(MIN_DPI..=MAX_DPI).contains(&dpi) {
+    return Err(format!("分辨率需在 {MIN_DPI}–{MAX_DPI} dpi 之间：{dpi}"));
+  }
+  let output = resolve_output(&output, "tiff", &[(&input).into()])?;
+  assert_input_not_output(&input, &output)?;
+  let out = AtomicOutput::new(&output)?;
+  let work = work_dir(&app, "tiff")?;
+  let res = run_tiff(&app, &work, &input, &out.path(), dpi, compression, guard.unwrap_or_default());
+  let _ = fs::remove_dir_all(&work);
+  res?;
+  out.commit()?;
+  Ok(output)
+}
+
+fn run_tiff(app: &AppHandle, work: &Path, input: &InputOne, output: &str, dpi: u32, compression: TiffCompression, guard: PageGuard) -> Result<(), String> {
+  let in_path = stage_input(work, input)?;
+  guard.check(app, &in_path)?;
+  write_tiff(app, &in_path, output, dpi, compression)
+}
+
+/// in_path 的全部页写成一个多页 TIFF
+pub(crate) fn write_tiff(app: &AppHandle, in_path: &str, output: &str, dpi: u32, compression: TiffCompression) -> Result<(), String> {
+  let mut args: Vec<String> = compression.device_args().iter().map(|s| s.to_string()).collect();
+  args.extend([
+    format!("-r{dpi}"),
+    "-dNOPAUSE".into(), "-dQUIET".into(), "-dBATCH".into(),
+    // 输出文件名不含 %d → 所有页写入同一文件
+    format!("-sOutputFile={output}"),
+    in_path.into(),
+  ]);
+  run_gs(app, &args)?;
+  Ok(())
+}
@@ -0,0 +1,154 @@
+//! Verify signatures — ByteRange digest vs. the messageDigest inside the embedded PKCS#7 (adbe.pkcs7.detached / ETSI.CAdES.detached)
+use cms::{
+  cert::{x509::der::{asn1::{ObjectIdentifier, OctetString}, Decode, SliceReader}, CertificateChoices},
+  content_info::ContentInfo,
+  signed_data::{SignedData, SignerIdentifier, SignerInfo},
+};
+use lopdf::{Dictionary, Document, Object};
+use serde::Serialize;
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use std::{borrow::Cow, collections::HashSet, fs};
+
+use crate::common::{decode_text, InputOne};
+use crate::inspect::resolve;
+
+const OID_MESSAGE_DIGEST: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.9.4");
+const OID_CN: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.5.4.3");
+const OID_SHA1: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.14.3.2.26");
+const OID_SHA256: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.2.1");
+const OID_SHA384: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.2.2");
+const OID_SHA512: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.2.3");
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SigStatus {
+  /// 签名域完整名（/T，父子以 . 连接）
+  pub field: String,
+  /// 签名证书主题 CN（取不到时为 /Name 或空）
+  pub signer: Option<String>,
+  /// ByteRange 覆盖字节的摘要与 PKCS#7 messageDigest 一致
+  pub valid_digest: bool,
+  /// ByteRange 覆盖到文件末尾（之后没有增量修改）
+  pub covers_whole_doc: bool,
+  /// 无法校验时的原因
+  pub note: Option<String>,
+}
+
+/// 只校验内容摘要是否被篡改；不验证证书链、吊销状态与签名值本身
+#[tauri::command]
+pub async fn verify_signatures(input: InputOne) -> Result<Vec<SigStatus>, String> {
+  let raw: Cow<[u8]> = match &input {
+    InputOne::Path(p) => Cow::Owned(fs::read(p).map_err(|e| format!("读取失败：{e}（{p}）"))?),
+    InputOne::Bytes(pdf) => Cow::Borrowed(&pdf.data),
+  };
+  let doc = Document::load_mem(&raw).map_err(|e| format!("解析 PDF 失败：{e}"))?;
+
+  let mut out = Vec::new();
+  for (field, sig) in sig_fields(&doc) {
+    let name = sig.get(b"Name").ok().and_then(|o| resolve(&doc, o).as_str().ok()).map(decode_text);
+    let status = match check_sig(&doc, sig, &raw) {
+      Ok((signer, valid_digest, covers_whole_doc)) => SigStatus { field, signer: signer.or(name), valid_digest, covers_whole_doc, note: None },
+      Err(e) => SigStatus { field, signer: name, valid_digest: false, covers_whole_doc: false, note: Some(e) },
+    };
+    out.push(status);
+  }
+  Ok(out)
+}
+
+/// AcroForm 里已签名的 /Sig 域：(完整域名, 签名字典 /V)
+fn sig_fields(doc: &Document) -> Vec<(String, &Dictionary)> {
+  let mut out = Vec::new();
+  let Some(fields) = doc.catalog().ok()
+    .and_then(|c| c.get(b"AcroForm").ok())
+    .and_then(|o| resolve(doc, o).as_dict().ok())
+    .and_then(|f| f.get(b"Fields").ok())
+    .and_then(|o| resolve(doc, o).as_array().ok()) else { return out };
+
+  let mut seen = HashSet::new();
+  let mut stack: Vec<(&Object, String, bool)> = fields.iter().rev().map(|f| (f, String::new(), false)).collect();
+  while let Some((obj, prefix, parent_sig)) = stack.pop() {
+    if let Object::Reference(id) = obj {
+      if !seen.insert(*id) { continue; }
+    }
+    let Ok(d) = resolve(doc, obj).as_dict() else { continue };
+    let partial = d.get(b"T").ok().and_then(|o| resolve(doc, o).as_str().ok()).map(decode_text);
+    let name = match (prefix.is_empty(), partial) {
+      (_, None) => prefix.clone(),
+      (true, Some(t)) => t,
+      (false, Some(t)) => format!("{prefix}.{t}"),
+    };
+    // /FT 可继承
+    let is_sig = d.get(b"FT").ok().and_then(|o| o.as_name().ok()).map(|n| n == b"Sig").unwrap_or(parent_sig);
+    if is_sig {
+      if let Some(v) = d.get(b"V").ok().and_then(|o| resolve(doc, o).as_dict().ok()) {
+        out.push((name.clone(), v));
+      }
+    }
+    if let Ok(kids) = d.get(b"Kids").and_then(|o| resolve(doc, o).as_array()) {
+      stack.extend(kids.iter().rev().map(|k| (k, name.clone(), is_sig)));
+    }
+  }
+  out
+}
+
+/// 返回 (签名者, 摘要一致, 覆盖全文)
+fn check_sig(doc: &Document, sig: &Dictionary, raw: &[u8]) -> Result<(Option<String>, bool, bool), String> {
+  let range: Vec<usize> = sig.get(b"ByteRange").and_then(|o| resolve(doc, o).as_array()).map_err(|_| "缺少 /ByteRange".to_string())?
+    .iter()
+    .map(|o| resolve(doc, o).as_i64().ok().and_then(|v| usize::try_from(v).ok()))
+    .collect::<Option<_>>()
+    .ok_or("ByteRange 含非法数值")?;
+  if range.is_empty() || range.len() % 2 != 0 { return Err("ByteRange 长度不是偶数".into()); }
+  let mut signed = Vec::new();
+  for pair in range.chunks(2) {
+    let (start, len) = (pair[0], pair[1]);
+    let end = start.checked_add(len).filter(|&e| e <= raw.len()).ok_or("ByteRange 超出文件长度")?;
+    signed.extend_from_slice(&raw[start..end]);
+  }
+  let covers_whole_doc = range[0] == 0 && range[range.len() - 2] + range[range.len() - 1] == raw.len();
+
+  let contents = sig.get(b"Contents").ok().and_then(|o| resolve(doc, o).as_str().ok()).ok_or("缺少 /Contents")?;
+  // /Contents 预留空间以 0 填充，只解码第一个 DER 值
+  let mut reader = SliceReader::new(contents).map_err(|e| format!("PKCS#7 解析失败：{e}"))?;
+  let info = ContentInfo::decode(&mut reader).map_err(|e| format!("PKCS#7 解析失败：{e}"))?;
+  let signed_data: SignedData = info.content.decode_as().map_err(|e| format!("不是 SignedData：{e}"))?;
+  let signer_info = signed_data.signer_infos.0.iter().next().ok_or("PKCS#7 中没有 SignerInfo")?;
+
+  let signer = signer_name(&signed_data, signer_info);
+  let expected = message_digest(signer_info).ok_or("SignerInfo 缺少 messageDigest 属性（可能是 adbe.x509.rsa_sha1，暂不支持）")?;
+  let actual = digest(&signer_info.digest_alg.oid, &signed)?;
+  Ok((signer, actual == expected, covers_whole_doc))
+}
+
+fn message_digest(si: &SignerInfo) -> Option<Vec<u8>> {
+  let attr = si.signed_attrs.as_ref()?.iter().find(|a| a.oid == OID_MESSAGE_DIGEST)?;
+  let v: OctetString = attr.values.iter().next()?.decode_as().ok()?;
+  Some(v.as_bytes().to_vec())
+}
+
+fn digest(alg: &ObjectIdentifier, data: &[u8]) -> Result<Vec<u8>, String> {
+  Ok(match *alg {
+    OID_SHA1 => Sha1::digest(data).to_vec(),
+    OID_SHA256 => Sha256::digest(data).to_vec(),
+    OID_SHA384 => Sha384::digest(data).to_vec(),
+    OID_SHA512 => Sha512::digest(data).to_vec(),
+    other => return Err(format!("不支持的摘要算法：{other}")),
+  })
+}
+
+/// 按 IssuerAndSerialNumber 找到签名证书，取主题 CN
+fn signer_name(sd: &SignedData, si: &SignerInfo) -> Option<String> {
+  let SignerIdentifier::IssuerAndSerialNumber(id) = &si.sid else { return None };
+  let cert = sd.certificates.as_ref()?.0.iter().find_map(|c| match c {
+    CertificateChoices::Certificate(cert)
+      if cert.tbs_certificate.issuer == id.issuer && cert.tbs_certificate.serial_number == id.serial_number => Some(cert),
+    _ => None,
+  })?;
+  let subject = &cert.tbs_certificate.subject;
+  subject.0.iter()
+    .flat_map(|rdn| rdn.0.iter())
+    .find(|atv| atv.oid == OID_CN)
+    .map(|atv| atv.to_string().trim_start_matches("CN=").to_string())
+    .or_else(|| Some(subject.to_string()))
+}
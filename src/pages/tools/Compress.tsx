@@ -24,6 +24,7 @@ function presetHint(p: CompressPreset): string {
     case "small":    return "有损（≈150dpi）";
     case "smaller":  return "有损（≈72–96dpi）";
     case "tiny":     return "更狠的有损";
+    case "qpdfmax":  return "无损最大压缩（仅 qpdf）";
   }
   const _exhaustive: never = p; return _exhaustive;
 }
@@ -1,17 +1,830 @@
 import { invoke } from "@tauri-apps/api/core";
 
 export type BytesInput = { name: string; data: number[] };
-export type CompressPreset = "lossless" | "small" | "smaller" | "tiny";
+export type CompressPreset = "lossless" | "small" | "smaller" | "tiny" | "qpdfmax";
 
 export const toBytesInput = (name: string, u8: Uint8Array): BytesInput => ({
   name,
   data: Array.from(u8),
 });
 
-export function mergePdfs(inputs: string[] | BytesInput[], output: string): Promise<string> {
-  return invoke<string>("merge", { inputs, output });
+/**
+ * 输出命名模板：结果里带输出路径的单输出命令，其 output 均可直接传模板，如 "D:/out/{stem}_{op}_{date}_{seq}.pdf"；
+ * 变量只在文件名中生效，结果里返回实际路径。结果不含路径的 autoRotate / invertPages / embedFingerprint / spriteSheet 只接受字面路径；
+ * 拆分的各份文件名见 SplitOptions.naming。
+ * {stem} 输入文件名、{op} 操作名、{date} UTC 日期 YYYYMMDD、{seq} 三位序号（取第一个不存在的）、{sha8} 输入内容哈希前 8 位
+ */
+export type OutputTemplate = string;
+
+export type MergeOptions = {
+  /** 按内容 SHA-256 去掉完全相同的输入，保留首次出现 */
+  dedupeInputs?: boolean;
+  /** 完全指定输出页序：[输入下标, 页范围]，如 [[0, "1-3"], [1, "1"], [0, "4-z"]] */
+  order?: [number, string][];
+  /** 在最前插入生成的封面页（A4，文字居中） */
+  cover?: CoverSpec;
+  /** 在输出旁写 manifest.json */
+  manifest?: boolean;
+  /** 各输入首页尺寸须一致（±1pt），否则报 E_INVALID_ARG 并列出不一致的文件 */
+  requireUniformSize?: boolean;
+  /** 输出的页码标签改为从 1 起连续一段 */
+  renumberLabels?: boolean;
+  /** renumberLabels 的样式，默认 decimal */
+  labelStyle?: LabelStyle;
+  /** 每个输入先缩放居中到该纸张再拼接（经 Ghostscript，书签等交互内容可能丢失） */
+  unifySize?: PageSize;
+  /** 同 unifySize，纸张取各输入中面积最大的一页；与 unifySize 二选一 */
+  unifyToLargest?: boolean;
+  /** 合并后折叠重复嵌入的图片 / 字体（不降采样），结果更小才采用 */
+  optimize?: boolean;
+  /** 拼接时一并线性化并校验（网页快速查看）；默认 true，false 时不线性化 */
+  linearize?: boolean;
+  /** 合并后核对注释 / 表单控件数，结果见 annotationCheck */
+  verifyAnnotations?: boolean;
+  /** 输出的 Producer / Creator（含 XMP）改为该值，空串时用应用名；省略则保留引擎写入的值 */
+  producer?: string;
+  /** 每份输入前插入一张写有其名称的分隔页 */
+  separator?: SeparatorSpec;
+};
+
+/** labels 与 inputs 同序，缺省或空串时用源文件名；skipFirst 时第一份输入前不插 */
+export type SeparatorSpec = { labels?: string[]; skipFirst?: boolean };
+
+export type LabelStyle = "decimal" | "upperRoman" | "lowerRoman" | "upperAlpha" | "lowerAlpha";
+
+export type CoverSpec = { title: string; subtitle?: string; date?: string };
+
+export type SkippedInput = { index: number; name: string; duplicateOf: number };
+/** 去重无收益时 bytesAfter 等于 bytesBefore */
+export type SizeReduction = { bytesBefore: number; bytesAfter: number };
+/** annotations 为页面注释总数（含表单控件），widgets 为其中的表单控件 */
+export type AnnotCount = { annotations: number; widgets: number };
+/** expected 为各输入选中页合计（封面不计）；lost 表示输出少于预期 */
+export type AnnotationCheck = { expected: AnnotCount; actual: AnnotCount; lost: boolean };
+export type MergeResult = {
+  path: string;
+  skipped: SkippedInput[];
+  manifest: string | null;
+  optimized: SizeReduction | null;
+  annotationCheck: AnnotationCheck | null;
+};
+
+export type ManifestFile = { name: string; sha256: string; bytes: number; pageCount: number };
+/** manifest.json 的内容 */
+export type Manifest = {
+  operation: "merge" | "split";
+  /** UTC，ISO 8601 */
+  createdAt: string;
+  inputs: ManifestFile[];
+  outputs: ManifestFile[];
+  engines: { app: string; qpdf: string | null; ghostscript: string | null };
+};
+
+export type FileHash = { filename: string; bytes: number; sha256: string };
+
+/** 目录下每个 PDF 的 SHA-256（不递归）；writeSums 时另写 SHA256SUMS，可用 sha256sum -c 校验 */
+export function manifestDir(dir: string, writeSums?: boolean): Promise<FileHash[]> {
+  return invoke<FileHash[]>("manifest_dir", { dir, writeSums: writeSums ?? null });
+}
+
+export function mergePdfs(inputs: string[] | BytesInput[], output: string, options?: MergeOptions): Promise<MergeResult> {
+  return invoke<MergeResult>("merge", { inputs, output, options });
+}
+
+export type DownsampleType = "bicubic" | "average" | "subsample";
+
+export type CompressOptions = {
+  /** Ghostscript 兼容级别 "1.3"–"2.0"，默认 "1.4" */
+  compatLevel?: string;
+  /** 仅降采样超过 目标分辨率 × 阈值 的图片，需 ≥ 1.0 */
+  downsampleThreshold?: number;
+  /** 三类图片统一的降采样方式 */
+  interpolation?: DownsampleType;
+  /** 覆盖原文件（忽略 output，仅路径入参），先备份为 .bak */
+  inPlace?: boolean;
+  /** 校验 Ghostscript 输出（qpdf --check + 头尾标记），失败回退 qpdf 无损；默认 true */
+  verifyOutput?: boolean;
+  /** 首 / 中 / 末页渲染前后对比，相似度过低回退 qpdf 无损；默认 false */
+  verifyVisual?: boolean;
+  /** Ghostscript 内存上限（MB，64–65536），用于内存受限的环境 */
+  maxMemoryMb?: number;
+  /** Ghostscript 渲染线程数（1–64） */
+  threads?: number;
+  /** 输出的 Producer / Creator（含 XMP）改为该值，空串时用应用名；省略则保留引擎写入的值 */
+  producer?: string;
+  /** 只压缩这些页（页范围，可多段），其余页原样保留；仅 small / smaller / tiny */
+  pages?: string[];
+};
+
+/** score 0–1，1 为完全一致 */
+export type VisualScore = { page: number; score: number };
+
+export type CompressResult = {
+  path: string;
+  range: string | null;
+  bytesBefore: number;
+  bytesAfter: number;
+  /** Ghostscript 失败或输出未通过校验，结果来自 qpdf 无损 */
+  fellBack: boolean;
+  /** 开启 verifyVisual 时各抽样页的相似度 */
+  visualScores: VisualScore[] | null;
+  /** 指定 pages 时实际重新压缩的页 */
+  recompressedPages: number[] | null;
+};
+
+export function compressPdf(input: string | BytesInput, output: string, preset: CompressPreset, options?: CompressOptions): Promise<CompressResult> {
+  return invoke<CompressResult>("compress", { input, output, preset, options: options ?? null });
+}
+
+/** compress:progress 事件负载 */
+export type TargetPass = { pass: number; dpi: number; resultingBytes: number };
+
+export type TargetCompressResult = {
+  path: string;
+  bytesBefore: number;
+  bytesAfter: number;
+  dpi: number;
+  passes: number;
+  /** false：最低分辨率仍超出目标，输出为最小的一次结果 */
+  metTarget: boolean;
+};
+
+export function compressToTarget(input: string | BytesInput, output: string, targetBytes: number, options?: CompressOptions): Promise<TargetCompressResult> {
+  return invoke<TargetCompressResult>("compress_to_target", { input, output, targetBytes, options });
+}
+
+export type RangePart = { range: string; bytesBefore: number; bytesAfter: number; fellBack: boolean };
+export type RangesCompressResult = { path: string; bytesBefore: number; bytesAfter: number; parts: RangePart[] };
+
+/** 每条 [页范围, 预设] 分别压缩后按原页序拼回；范围须不重叠且覆盖全部页，如 [["1-49", "lossless"], ["50-z", "tiny"]] */
+export function compressRanges(input: string | BytesInput, output: string, rules: [string, CompressPreset][]): Promise<RangesCompressResult> {
+  return invoke<RangesCompressResult>("compress_ranges", { input, output, rules });
+}
+
+export type PageSize =
+  | "a3" | "a4" | "a5" | "letter" | "legal"
+  | { custom: { widthPt: number; heightPt: number } };
+export type InsertPosition = "start" | "end" | { index: number };
+
+export function addBlankPage(input: string | BytesInput, output: string, size: PageSize, position: InsertPosition): Promise<string> {
+  return invoke<string>("add_blank_page", { input, output, size, position });
+}
+
+/** pt，PDF 坐标（原点左下） */
+export type Box4 = { left: number; bottom: number; right: number; top: number };
+
+export function setPrintBoxes(
+  input: string | BytesInput,
+  output: string,
+  boxes: { trim?: Box4; bleed?: Box4; art?: Box4 },
+  ranges?: string[],
+): Promise<string> {
+  return invoke<string>("set_print_boxes", {
+    input, output,
+    trim: boxes.trim ?? null, bleed: boxes.bleed ?? null, art: boxes.art ?? null,
+    ranges: ranges ?? null,
+  });
+}
+
+export type ColorSpace = "rgb" | "cmyk" | "gray";
+
+export function convertColorspace(input: string | BytesInput, output: string, target: ColorSpace, iccProfile?: string): Promise<string> {
+  return invoke<string>("convert_colorspace", { input, output, target, iccProfile: iccProfile ?? null });
+}
+
+export function embedIcc(input: string | BytesInput, output: string, iccPath: string): Promise<string> {
+  return invoke<string>("embed_icc", { input, output, iccPath });
+}
+
+export type PdfxStandard = "x1a" | "x4";
+export type PdfxReport = { path: string; conformant: boolean; issues: string[] };
+
+export function convertPdfx(input: string | BytesInput, output: string, standard: PdfxStandard, outputIntentIcc: string): Promise<PdfxReport> {
+  return invoke<PdfxReport>("convert_pdfx", { input, output, standard, outputIntentIcc });
+}
+
+export type SplitOptions = {
+  /** 每段保留本段内的书签（页码改为本段编号） */
+  preserveBookmarks?: boolean;
+  /** 变量 {index} {range} {total}，以及通用的 {stem} {op} {date} {sha8} {seq}（按份取未用序号），如 "{stem}_part{index}"、"{stem}_{seq}" */
+  naming?: { template: string };
+  /** 在输出目录写 manifest.json（仅 splitPdf） */
+  manifest?: boolean;
+  /** 每段只保留控件在本段页面上的表单字段 */
+  preserveForms?: boolean;
+  /** 加密输入的密码 */
+  password?: string;
+  /** 各段套用原文件的加密参数（仅 splitPdf，需 password） */
+  reapplyEncryption?: boolean;
+  /** 各段经 qpdf 精简（生成对象流、去掉未用到的资源），结果里报告精简前后体积（仅 splitPdf） */
+  compact?: boolean;
+  /** 每段额外带上起始页之前的 overlap 页作为上下文（仅 splitPdf） */
+  overlap?: number;
+};
+
+export type CompactedPart = { path: string; naiveBytes: number; compactBytes: number };
+/** overlappedRanges 为开启 overlap 时各段实际拆出的页范围（含上下文页） */
+export type SplitResult = {
+  paths: string[];
+  manifest: string | null;
+  compacted: CompactedPart[] | null;
+  overlappedRanges: string[] | null;
+};
+
+export function splitPdf(input: string | BytesInput, ranges: string[], outDir: string, options?: SplitOptions): Promise<SplitResult> {
+  return invoke<SplitResult>("split_pdf", { input, ranges, outDir, options: options ?? null });
+}
+
+export function splitAndCompress(input: string | BytesInput, ranges: string[], preset: CompressPreset, outDir: string, options?: SplitOptions): Promise<CompressResult[]> {
+  return invoke<CompressResult[]>("split_and_compress", { input, ranges, preset, outDir, options: options ?? null });
+}
+
+export function splitByText(input: string | BytesInput, boundaryRegex: string, outDir: string): Promise<string[]> {
+  return invoke<string[]>("split_by_text", { input, boundaryRegex, outDir });
+}
+
+/** CSV 每行 name,range（如 intro,1-3），输出 outDir/<name>.pdf；首行 name,range 视为表头 */
+export function splitFromCsv(input: string | BytesInput, csvPath: string, outDir: string): Promise<string[]> {
+  return invoke<string[]>("split_from_csv", { input, csvPath, outDir });
+}
+
+/** 相邻页尺寸相差超过 1pt 即分段（如 A4 正文 + A3 折页） */
+export function splitBySizeChange(input: string | BytesInput, outDir: string): Promise<string[]> {
+  return invoke<string[]>("split_by_size_change", { input, outDir });
+}
+
+export type ExportFormat = "pdf" | "png" | "tiff" | "text";
+
+/** 先拆分再逐段转换：png 每页一张、tiff 每段一个多页文件、text 每段一个 .txt；返回生成的全部文件 */
+export function splitAndExport(input: string | BytesInput, ranges: string[], format: ExportFormat, outDir: string, guard?: PageGuard): Promise<string[]> {
+  return invoke<string[]>("split_and_export", { input, ranges, format, outDir, guard });
+}
+
+/** pt，原点为页面左上角 */
+export type TextRegion = { x: number; y: number; width: number; height: number };
+
+export function splitFixedNamed(input: string | BytesInput, pagesPerDoc: number, nameFromText: TextRegion | null, outDir: string, guard?: PageGuard): Promise<string[]> {
+  return invoke<string[]>("split_fixed_named", { input, pagesPerDoc, nameFromText, outDir, guard });
+}
+
+/** 超过页数上限（默认 2000）时逐页处理类命令会报错，确认后传 force: true */
+export type PageGuard = { force?: boolean; limit?: number };
+
+export type ColorSplitPart = { path: string; pageCount: number };
+
+export function splitByColor(input: string | BytesInput, outDir: string, guard?: PageGuard): Promise<ColorSplitPart[]> {
+  return invoke<ColorSplitPart[]>("split_by_color", { input, outDir, guard });
+}
+
+export type PdfKind = "imageOnly" | "mixed" | "textBased" | "noText";
+
+export function classifyPdf(input: string | BytesInput): Promise<PdfKind> {
+  return invoke<PdfKind>("classify_pdf", { input });
+}
+
+/** score 为可识别字符占比（0–1），文字太少时为 null */
+export type PageTextQuality = { page: number; chars: number; score: number | null; garbled: boolean };
+export type TextQuality = { pages: PageTextQuality[]; garbledPages: number[] };
+
+export function textQuality(input: string | BytesInput): Promise<TextQuality> {
+  return invoke<TextQuality>("text_quality", { input });
+}
+
+/** 每页建议打印方向；宽高为按 /Rotate 转正后的显示尺寸 */
+export type Orientation = { page: number; widthPt: number; heightPt: number; rotate: number; landscape: boolean };
+
+export function printOrientationHints(input: string | BytesInput): Promise<Orientation[]> {
+  return invoke<Orientation[]>("print_orientation_hints", { input });
+}
+
+/** 未嵌入的字体及用到它的页；base14 为标准 14 字体（仅 requireEmbedded 时报告） */
+export type FontIssue = { name: string; subtype: string; base14: boolean; pages: number[] };
+
+export function fontCompliance(input: string | BytesInput, requireEmbedded: boolean): Promise<FontIssue[]> {
+  return invoke<FontIssue[]>("font_compliance", { input, requireEmbedded });
+}
+
+export type PageDensity = { page: number; imageFraction: number; likelyPhoto: boolean };
+
+/** 每页图片面积占比（0–1）；likelyPhoto 的页适合交给 compressRanges 用有损预设 */
+export function contentDensity(input: string | BytesInput): Promise<PageDensity[]> {
+  return invoke<PageDensity[]>("content_density", { input });
+}
+
+export type SizeGroup = {
+  widthPt: number;
+  heightPt: number;
+  paper: string | null;
+  landscape: boolean;
+  count: number;
+  pages: number[];
+};
+
+export type SizeSummary = { sizes: SizeGroup[]; uniform: boolean };
+
+export function sizeSummary(input: string | BytesInput): Promise<SizeSummary> {
+  return invoke<SizeSummary>("size_summary", { input });
+}
+
+export type ImageInfo = { page: number; name: string; width: number; height: number; filter: string | null; bytes: number; hash: string };
+export type RemoveReport = { path: string; removed: number; pages: number[] };
+
+export function listImages(input: string | BytesInput): Promise<ImageInfo[]> {
+  return invoke<ImageInfo[]>("list_images", { input });
+}
+
+export function removeImage(input: string | BytesInput, output: string, imageHash: string): Promise<RemoveReport> {
+  return invoke<RemoveReport>("remove_image", { input, output, imageHash });
+}
+
+export function detectInverted(input: string | BytesInput, guard?: PageGuard): Promise<number[]> {
+  return invoke<number[]>("detect_inverted", { input, guard });
+}
+
+export function invertPages(input: string | BytesInput, output: string, ranges: string[]): Promise<number[]> {
+  return invoke<number[]>("invert_pages", { input, output, ranges });
 }
 
-export function compressPdf(input: string | BytesInput, output: string, preset: CompressPreset): Promise<string> {
-  return invoke<string>("compress", { input, output, preset });
+export type A11yCheck = { id: "structTree" | "markInfo" | "lang" | "title" | "displayDocTitle" | "altText"; passed: boolean; detail: string };
+export type A11yReport = { tagged: boolean; checks: A11yCheck[] };
+
+export function accessibilityReport(input: string | BytesInput): Promise<A11yReport> {
+  return invoke<A11yReport>("accessibility_report", { input });
+}
+
+export function setA11yBasics(input: string | BytesInput, output: string, lang: string, title: string, displayTitle: boolean): Promise<string> {
+  return invoke<string>("set_a11y_basics", { input, output, lang, title, displayTitle });
+}
+
+export type SigStatus = {
+  field: string;
+  signer: string | null;
+  validDigest: boolean;
+  coversWholeDoc: boolean;
+  note: string | null;
+};
+
+export function verifySignatures(input: string | BytesInput): Promise<SigStatus[]> {
+  return invoke<SigStatus[]>("verify_signatures", { input });
+}
+
+export type StampPosition =
+  | "topLeft" | "topCenter" | "topRight"
+  | "middleLeft" | "center" | "middleRight"
+  | "bottomLeft" | "bottomCenter" | "bottomRight";
+
+export type StampPlacement = {
+  /** qpdf 页范围，如 "1-z:odd" */
+  pages: string;
+  position: StampPosition;
+  scale?: number;
+  marginPt?: number;
+};
+
+export function stampPdf(input: string | BytesInput, stamp: string | BytesInput, output: string, placements: StampPlacement[]): Promise<string> {
+  return invoke<string>("stamp_pdf", { input, stamp, output, placements });
+}
+
+/** renderWithAnnotations 默认 true：缩略图带批注与已填表单值 */
+export function contactSheet(input: string | BytesInput, output: string, cols: number, rows: number, guard?: PageGuard, renderWithAnnotations?: boolean): Promise<string> {
+  return invoke<string>("contact_sheet", { input, output, cols, rows, guard, renderWithAnnotations });
+}
+
+export type PageNumberStyle = {
+  /** {n} 页码，{total} 总页数；默认 "{n}" */
+  format?: string;
+  position?: StampPosition;
+  fontSize?: number;
+  marginPt?: number;
+  startAt?: number;
+};
+
+export type AssembleOptions = {
+  toc?: boolean;
+  titles?: string[];
+  keepSourceBookmarks?: boolean;
+  pageNumbers?: PageNumberStyle;
+};
+
+export function assemblePdfs(inputs: (string | BytesInput)[], output: string, options?: AssembleOptions): Promise<string> {
+  return invoke<string>("assemble", { inputs, output, options: options ?? null });
+}
+
+/** warnings 列出非单页的输入；autoRotate 时 rotations 为合并后每页的 OSD 结果 */
+export type AssembleScansResult = { path: string; warnings: string[]; rotations: PageRotation[] };
+
+/** 一页一个 PDF 的扫描按顺序拼成一份，可选逐页自动纠正方向 */
+export function assembleScans(
+  inputs: (string | BytesInput)[],
+  output: string,
+  autoRotate: boolean,
+  guard?: PageGuard,
+): Promise<AssembleScansResult> {
+  return invoke<AssembleScansResult>("assemble_scans", { inputs, output, autoRotate, guard });
+}
+
+export type Rect = [number, number, number, number];
+
+export type LinkInfo = { page: number; uri: string; rect: Rect };
+export type InternalLink = { page: number; targetPage: number | null; rect: Rect };
+export type LinkList = { external: LinkInfo[]; internal: InternalLink[] };
+
+export function listLinks(input: string | BytesInput): Promise<LinkList> {
+  return invoke<LinkList>("list_links", { input });
+}
+
+export type UrlRule = { find: string; replace: string; regex?: boolean };
+export type RewriteReport = { path: string; rewritten: number; removed: number };
+
+export function rewriteLinks(input: string | BytesInput, output: string, rules: UrlRule[], stripAll: boolean): Promise<RewriteReport> {
+  return invoke<RewriteReport>("rewrite_links", { input, output, rules, stripAll });
+}
+
+export function makeBooklet(input: string | BytesInput, output: string, paper: PageSize): Promise<string> {
+  return invoke<string>("make_booklet", { input, output, paper });
+}
+
+export type ObjectKind = "image" | "font" | "content" | "form" | "iccProfile" | "metadata" | "attachment" | "other";
+
+export type ObjectSize = {
+  object: string;
+  kind: ObjectKind;
+  bytes: number;
+  filter: string | null;
+};
+
+export function sizeBreakdown(input: string | BytesInput, limit?: number): Promise<ObjectSize[]> {
+  return invoke<ObjectSize[]>("size_breakdown", { input, limit: limit ?? null });
+}
+
+export type LinearizeResult = { path: string; linearized: boolean };
+
+export function linearizePdf(input: string | BytesInput, output: string, inPlace = false): Promise<LinearizeResult> {
+  return invoke<LinearizeResult>("linearize", { input, output, inPlace });
+}
+
+export function checkLinearized(input: string | BytesInput): Promise<boolean> {
+  return invoke<boolean>("check_linearized", { input });
+}
+
+export type Permission = { name: string; allowed: boolean };
+
+export type EncryptionInfo = {
+  encrypted: boolean;
+  hasUserPassword: boolean;
+  keyBits: number | null;
+  revision: number | null;
+  method: string | null;
+  permissions: Permission[];
+};
+
+export function encryptionInfo(input: string | BytesInput): Promise<EncryptionInfo> {
+  return invoke<EncryptionInfo>("encryption_info", { input });
+}
+
+/** 未传的字段默认允许 */
+export type Permissions = {
+  print?: "full" | "low" | "none";
+  modify?: boolean;
+  extract?: boolean;
+  accessibility?: boolean;
+  annotate?: boolean;
+  fillForms?: boolean;
+  assemble?: boolean;
+};
+
+/** password 须为所有者密码；原密码与密钥长度保持不变 */
+export function updatePermissions(input: string | BytesInput, output: string, password: string, permissions: Permissions): Promise<string> {
+  return invoke<string>("update_permissions", { input, output, password, permissions });
+}
+
+export type BurstEntry = { id: string | null; pages: string; file: string };
+export type BurstResult = { zip: string; manifest: BurstEntry[] };
+
+export function burstStatements(input: string | BytesInput, idRegex: string, outZip: string): Promise<BurstResult> {
+  return invoke<BurstResult>("burst_statements", { input, idRegex, outZip });
+}
+
+export type FindingKind = "xref" | "trailer" | "missingEof" | "damagedObject" | "stream" | "encryption" | "other";
+
+export type Finding = {
+  kind: FindingKind;
+  severity: "recoverable" | "fatal";
+  object: string | null;
+  offset: number | null;
+  line: string;
+};
+
+export type Diagnosis = {
+  ok: boolean;
+  fatal: boolean;
+  pdfVersion: string | null;
+  encrypted: boolean | null;
+  linearized: boolean | null;
+  findings: Finding[];
+};
+
+export function diagnosePdf(input: string | BytesInput): Promise<Diagnosis> {
+  return invoke<Diagnosis>("diagnose", { input });
+}
+
+export type OutlineFormat = "markdown" | "json";
+
+/** 书签树导出为文本：markdown 为嵌套列表（附页码），json 为 [{ title, page, children }] */
+export function exportOutline(input: string | BytesInput, format: OutlineFormat): Promise<string> {
+  return invoke<string>("export_outline", { input, format });
+}
+
+export type RepairReport = {
+  path: string;
+  pages: number;
+  missingCount: number;
+  wrongCount: number;
+  badParent: number;
+  missingType: number;
+  droppedKids: number;
+  flattenedNodes: number;
+  rebuiltRoot: boolean;
+  /** 页树本身没有问题，只是被拍平 */
+  clean: boolean;
+};
+
+/** 把 /Pages 重建为单层并修正 /Count、/Parent；页数与 qpdf 读出的不一致时报错 */
+export function repairPageTree(input: string | BytesInput, output: string): Promise<RepairReport> {
+  return invoke<RepairReport>("repair_page_tree", { input, output });
+}
+
+/** g4：1 位黑白 CCITT G4；lzw：8 位灰度 LZW */
+export type TiffCompression = "g4" | "lzw";
+
+export function toTiff(input: string | BytesInput, output: string, dpi: number, compression: TiffCompression, guard?: PageGuard): Promise<string> {
+  return invoke<string>("to_tiff", { input, output, dpi, compression, guard });
+}
+
+export type PageRotation = {
+  page: number;
+  /** 施加的顺时针旋转：0 / 90 / 180 / 270 */
+  rotate: number;
+  confidence: number | null;
+};
+
+export function autoRotate(input: string | BytesInput, output: string, guard?: PageGuard): Promise<PageRotation[]> {
+  return invoke<PageRotation[]>("auto_rotate", { input, output, guard });
+}
+
+/** pt，原点为页面左上角 */
+export type TextRun = { text: string; x: number; y: number; width: number; height: number; fontSize: number };
+
+export function textLayout(input: string | BytesInput, page: number): Promise<TextRun[]> {
+  return invoke<TextRun[]>("text_layout", { input, page });
+}
+
+/** 每页 16 位十六进制 pHash 按页序拼接 */
+export function perceptualHash(input: string | BytesInput, guard?: PageGuard): Promise<string> {
+  return invoke<string>("perceptual_hash", { input, guard });
+}
+
+/** 输入体积上限（字节）；null 为不限制。初值来自环境变量 LUMENPDF_MAX_INPUT_BYTES */
+export function getMaxInputBytes(): Promise<number | null> {
+  return invoke<number | null>("get_max_input_bytes");
+}
+
+export function setMaxInputBytes(limit: number | null): Promise<number | null> {
+  return invoke<number | null>("set_max_input_bytes", { limit });
+}
+
+export type CompareResult = { path: string; pages: number; changedPages: number[] };
+
+export function compareSideBySide(a: string | BytesInput, b: string | BytesInput, output: string, dpi: number, guard?: PageGuard): Promise<CompareResult> {
+  return invoke<CompareResult>("compare_side_by_side", { a, b, output, dpi, guard });
+}
+
+/** rect 为 PDF 坐标（pt，原点左下） */
+export type RedactRegion = { page: number; rect: Rect };
+
+export type RedactReport = {
+  path: string;
+  glyphsRemoved: number;
+  imagesRemoved: number;
+  annotationsRemoved: number;
+  pages: number[];
+};
+
+export function redactRegions(input: string | BytesInput, output: string, regions: RedactRegion[]): Promise<RedactReport> {
+  return invoke<RedactReport>("redact_regions", { input, output, regions });
+}
+
+export type FieldKind = "text" | "checkbox" | "radio" | "choice" | "pushButton" | "signature";
+
+export type FormField = {
+  /** 完整名称，各级以 "." 连接 */
+  name: string;
+  kind: FieldKind;
+  /** 复选 / 单选为状态名（如 "Yes"、"Off"） */
+  value: string | null;
+  /** 下拉 / 列表为导出值；复选 / 单选为选中状态名 */
+  options: string[];
+};
+
+export function listFormFields(input: string | BytesInput): Promise<FormField[]> {
+  return invoke<FormField[]>("list_form_fields", { input });
+}
+
+export type FillReport = {
+  path: string;
+  filled: string[];
+  /** 文档中不存在的字段名 */
+  unmatched: string[];
+  rejected: { name: string; reason: string }[];
+  /** 部分字段交由阅读器重绘外观（NeedAppearances） */
+  needsAppearances: boolean;
+};
+
+/** 复选 / 单选的值为状态名，或 "true" / "false" */
+export function fillForm(input: string | BytesInput, output: string, values: Record<string, string>, flatten: boolean): Promise<FillReport> {
+  return invoke<FillReport>("fill_form", { input, output, values, flatten });
+}
+
+export type FlattenResult = { path: string; pages: number; bytesBefore: number; bytesAfter: number; warnings: string[] };
+
+/** 输出为纯图片：不可选中 / 检索，体积通常变大；dpi 72–600 */
+export function flattenToImages(input: string | BytesInput, output: string, dpi: number, guard?: PageGuard, renderWithAnnotations?: boolean): Promise<FlattenResult> {
+  return invoke<FlattenResult>("flatten_to_images", { input, output, dpi, guard, renderWithAnnotations });
+}
+
+export type OptimizeScanResult = { path: string; pages: number; bytesBefore: number; bytesAfter: number; jbig2: boolean };
+
+/** 进度事件 "optimize_scan:progress"：{ page, total } */
+export function optimizeScan(input: string | BytesInput, output: string): Promise<OptimizeScanResult> {
+  return invoke<OptimizeScanResult>("optimize_scan", { input, output });
+}
+
+export type Symbology = "qr" | "code128";
+
+/** pages 为页范围，默认仅第一页；"1-z" 为每页 */
+export function stampBarcode(input: string | BytesInput, output: string, data: string, symbology: Symbology, position: StampPosition, pages?: string): Promise<string> {
+  return invoke<string>("stamp_barcode", { input, output, data, symbology, position, pages });
+}
+
+export type SigField = {
+  name: string;
+  page: number | null;
+  /** PDF 坐标（pt，原点左下） */
+  rect: Rect | null;
+  /** false 为待签的空占位 */
+  signed: boolean;
+};
+
+export function findSignatureFields(input: string | BytesInput): Promise<SigField[]> {
+  return invoke<SigField[]>("find_signature_fields", { input });
+}
+
+/** appearance 取第一页，等比缩放居中放进签名控件；已签名的字段会被拒绝 */
+export function setSignatureAppearance(input: string | BytesInput, output: string, fieldName: string, appearance: string | BytesInput): Promise<string> {
+  return invoke<string>("set_signature_appearance", { input, output, fieldName, appearance });
+}
+
+export type AutocropResult = { path: string; cropped: number[]; blank: number[] };
+
+/** CropBox 收紧到内容外框 + paddingPt；空白页保持原样 */
+export function autocrop(
+  input: string | BytesInput,
+  output: string,
+  paddingPt: number,
+  ranges?: string[],
+  guard?: PageGuard,
+): Promise<AutocropResult> {
+  return invoke<AutocropResult>("autocrop", { input, output, paddingPt, ranges: ranges ?? null, guard });
+}
+
+/** 内容按 scale（0.1–10）等比缩放；给出 target 时换成该纸张并居中，一页仍是一页 */
+export function rescalePages(input: string | BytesInput, output: string, scale: number, target?: PageSize): Promise<string> {
+  return invoke<string>("rescale_pages", { input, output, scale, target: target ?? null });
+}
+
+/** 第 i 页（0 起）位于 ((i % cols) * thumbPx, floor(i / cols) * thumbPx) */
+export type SpriteMeta = { cols: number; rows: number; thumbPx: number; count: number };
+
+/** outImage 扩展名决定格式：.png / .jpg；renderWithAnnotations 默认 true */
+export function spriteSheet(input: string | BytesInput, outImage: string, cols: number, thumbPx: number, guard?: PageGuard, renderWithAnnotations?: boolean): Promise<SpriteMeta> {
+  return invoke<SpriteMeta>("sprite_sheet", { input, outImage, cols, thumbPx, guard, renderWithAnnotations });
+}
+
+/** 每页单价，货币单位由调用方决定 */
+export type PrintRates = { colorPage: number; bwPage: number };
+export type PrintCost = { colorPages: number; bwPages: number; total: number };
+
+export function printEstimate(input: string | BytesInput, rates: PrintRates, guard?: PageGuard): Promise<PrintCost> {
+  return invoke<PrintCost>("print_estimate", { input, rates, guard });
+}
+
+/** 专色（Separation / DeviceN 色料，不含四色）及用到它的页；overprintPages 为其中启用了叠印的页 */
+export type SpotColorInfo = { name: string; pages: number[]; overprintPages: number[] };
+
+export function spotColorReport(input: string | BytesInput): Promise<SpotColorInfo[]> {
+  return invoke<SpotColorInfo[]>("spot_color_report", { input });
+}
+
+export type ImageDto = { base64: string; mime: "image/jpeg" | "image/png"; width: number; height: number };
+
+/** imageIndex 从 0 起，顺序同 listImages 中该页的图片 */
+export function getImage(input: string | BytesInput, page: number, imageIndex: number): Promise<ImageDto> {
+  return invoke<ImageDto>("get_image", { input, page, imageIndex });
+}
+
+/** 可排队的操作，字段同对应命令参数 */
+export type OpSpec =
+  | { op: "compress"; input: string | BytesInput; output: string; preset: CompressPreset; options?: CompressOptions }
+  | { op: "merge"; inputs: string[] | BytesInput[]; output: string; options?: MergeOptions }
+  | { op: "split"; input: string | BytesInput; ranges: string[]; outDir: string; options?: SplitOptions }
+  | { op: "linearize"; input: string | BytesInput; output: string; inPlace?: boolean }
+  | { op: "tiff"; input: string | BytesInput; output: string; dpi: number; compression: TiffCompression; guard?: PageGuard }
+  | { op: "flatten"; input: string | BytesInput; output: string; dpi: number; guard?: PageGuard; renderWithAnnotations?: boolean };
+
+/** "queue:progress" 事件；index 为本轮中的序号（1 起），done 带 result、failed 带 error */
+export type QueueProgress = {
+  jobId: number;
+  op: OpSpec["op"];
+  phase: "queued" | "running" | "done" | "failed" | "cancelled";
+  index: number;
+  total: number;
+  result: unknown | null;
+  error: string | null;
+};
+
+/** 入队返回任务号；任务按入队顺序逐个执行。rollbackOnCancel 时记录写出的文件，cancelBatch 会把它们删掉 */
+export function enqueue(op: OpSpec, rollbackOnCancel?: boolean): Promise<number> {
+  return invoke<number>("enqueue", { op, rollbackOnCancel: rollbackOnCancel ?? null });
+}
+
+/** 只能取消仍在排队的任务；已开始的返回 false */
+export function cancelJob(jobId: number): Promise<boolean> {
+  return invoke<boolean>("cancel_job", { jobId });
+}
+
+export type RollbackReport = {
+  cancelled: number[];
+  removed: string[];
+  /** 由 .bak 还原的原地修改文件 */
+  restored: string[];
+  failed: string[];
+  /** 追加写入的 rollback.log 路径 */
+  log: string | null;
+};
+
+/** 取消整轮：排队的全部取消，等正在执行的任务结束后回滚本轮 rollbackOnCancel 任务的输出 */
+export function cancelBatch(): Promise<RollbackReport> {
+  return invoke<RollbackReport>("cancel_batch");
+}
+
+/** 内容指纹：各页内容流的 SHA-256，写入 XMP，与文件名和元数据无关；返回指纹 */
+export function embedFingerprint(input: string | BytesInput, output: string): Promise<string> {
+  return invoke<string>("embed_fingerprint", { input, output });
+}
+
+/** stored 为 XMP 中记录的指纹（没有则为 null），current 为按当前内容重算的值 */
+export type FingerprintInfo = { stored: string | null; current: string; matches: boolean };
+
+export function readFingerprint(input: string | BytesInput): Promise<FingerprintInfo> {
+  return invoke<FingerprintInfo>("read_fingerprint", { input });
+}
+
+/** 文档级命名脚本（/Names /JavaScript），source 为脚本原文 */
+export type JsEntry = { name: string; source: string };
+export type RemoveJsResult = { path: string; removed: string[]; remaining: string[] };
+
+export function listJavascript(input: string | BytesInput): Promise<JsEntry[]> {
+  return invoke<JsEntry[]>("list_javascript", { input });
+}
+
+/** 只删除 names 中列出的脚本，其余保留；名称不存在时报错 */
+export function removeJavascript(input: string | BytesInput, output: string, names: string[]): Promise<RemoveJsResult> {
+  return invoke<RemoveJsResult>("remove_javascript", { input, output, names });
+}
+
+/** 改写 Producer / Creator（Info 与 XMP），省略 producer 时用应用名 */
+export function setProducer(input: string | BytesInput, output: string, producer?: string): Promise<string> {
+  return invoke<string>("set_producer", { input, output, producer: producer ?? null });
+}
+
+/** 取前 pages 页并沿对角线叠加半透明水印（仅 ASCII），用作可外发的试读版 */
+export function makePreview(input: string | BytesInput, output: string, pages: number, watermarkText: string): Promise<string> {
+  return invoke<string>("make_preview", { input, output, pages, watermarkText });
+}
+
+export type PageEntry = {
+  /** 1 基页码 */
+  index: number;
+  /** 可见区域尺寸，未按 rotation 转正 */
+  widthPt: number;
+  heightPt: number;
+  rotation: number;
+  label: string;
+  /** JPEG，base64 不带 data: 前缀 */
+  thumbnailB64: string;
+};
+
+/** 页面管理器一次取齐每页尺寸、旋转、页码标签与缩略图；缩略图按内容哈希缓存，未命中缓存时受 guard 页数上限约束 */
+export function pageManifest(input: string | BytesInput, guard?: PageGuard): Promise<PageEntry[]> {
+  return invoke<PageEntry[]>("page_manifest", { input, guard });
 }
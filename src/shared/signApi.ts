@@ -6,6 +6,8 @@ export type SignExportResp = {
   bytesWritten: number; // ✅ 对齐后端 camelCase
   sha256: string;
   tookMs: number;
+  /** 传了 chainPath 时为新追加行的哈希（链头） */
+  chainHead: string | null;
 };
 
 function u8ToB64(u8: Uint8Array): string {
@@ -20,20 +22,28 @@ function u8ToB64(u8: Uint8Array): string {
 }
 
 export async function signAndExportPdf(params: {
-  bytes: Uint8Array;
+  bytes?: Uint8Array;
+  /** 大文件：已落盘的 PDF 路径，传它就不走 base64（与 bytes 二选一） */
+  sourcePath?: string | null;
   suggestedName?: string;
   targetPath?: string | null;
   overwrite?: boolean;
+  /** 哈希链清单（JSON Lines）：每次导出追加 { timestamp, filename, sha256, prevHash } */
+  chainPath?: string | null;
 }) {
-  const pdfBytesB64 = u8ToB64(params.bytes);
+  const sourcePath = params.sourcePath ?? null;
+  if (!sourcePath && !params.bytes) throw new Error("bytes 与 sourcePath 必须提供一个");
+  const pdfBytesB64 = sourcePath ? null : u8ToB64(params.bytes!);
 
   // ⚠️ Tauri v2：参数名必须匹配 Rust 函数签名里的 `payload`
   const resp = await invoke<SignExportResp>("sign_and_export_pdf", {
     payload: {
       pdfBytesB64,
+      sourcePath,
       suggestedName: params.suggestedName ?? "signed.pdf",
       targetPath: params.targetPath ?? null,
       overwrite: !!params.overwrite,
+      chainPath: params.chainPath ?? null,
     }
   });
 